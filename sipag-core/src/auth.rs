@@ -29,10 +29,15 @@ fn resolve_token_with_env(
 /// This is the single source of truth for token-file reading — both
 /// [`resolve_token`] and `Credentials::resolve_oauth_token` delegate here.
 pub(crate) fn read_token_file(sipag_dir: &Path) -> Option<String> {
-    let token_file = sipag_dir.join("token");
+    read_token_path(&sipag_dir.join("token"))
+}
+
+/// Read an OAuth token from an arbitrary file, with the same permission check
+/// and trimming as [`read_token_file`].
+pub(crate) fn read_token_path(token_file: &Path) -> Option<String> {
     if token_file.exists() {
-        warn_if_token_world_readable(&token_file);
-        if let Ok(contents) = fs::read_to_string(&token_file) {
+        warn_if_token_world_readable(token_file);
+        if let Ok(contents) = fs::read_to_string(token_file) {
             let trimmed = contents.trim().to_string();
             if !trimmed.is_empty() {
                 return Some(trimmed);
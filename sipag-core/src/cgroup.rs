@@ -0,0 +1,105 @@
+//! Resource usage of the current container, read from its cgroup.
+//!
+//! The worker calls [`read_usage`] just before writing its final state, so
+//! peak memory and CPU time are recorded even though containers run with
+//! `--rm`. Both cgroup v2 (`memory.peak`, `cpu.stat`) and v1
+//! (`memory.max_usage_in_bytes`, `cpuacct.usage`) layouts are understood.
+
+use std::fs;
+use std::path::Path;
+
+/// Mount point of the container's cgroup filesystem.
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// Peak memory and total CPU time of a container.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceUsage {
+    pub peak_memory_bytes: Option<u64>,
+    pub cpu_secs: Option<f64>,
+}
+
+/// Read usage for the current container from [`CGROUP_ROOT`].
+pub fn read_usage() -> ResourceUsage {
+    read_usage_from(Path::new(CGROUP_ROOT))
+}
+
+/// Read usage from a cgroup filesystem rooted at `root`. Missing or
+/// unparsable files leave the corresponding field `None`.
+pub fn read_usage_from(root: &Path) -> ResourceUsage {
+    let read = |rel: &str| fs::read_to_string(root.join(rel)).ok();
+
+    let peak_memory_bytes = read("memory.peak")
+        .or_else(|| read("memory/memory.max_usage_in_bytes"))
+        .and_then(|s| s.trim().parse().ok());
+
+    let cpu_secs = match read("cpu.stat") {
+        Some(stat) => parse_cpu_stat_usec(&stat).map(|us| us as f64 / 1_000_000.0),
+        None => read("cpuacct/cpuacct.usage")
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .map(|ns| ns as f64 / 1_000_000_000.0),
+    };
+
+    ResourceUsage {
+        peak_memory_bytes,
+        cpu_secs,
+    }
+}
+
+/// Extract `usage_usec` from a cgroup v2 `cpu.stat` file.
+fn parse_cpu_stat_usec(stat: &str) -> Option<u64> {
+    stat.lines().find_map(|line| {
+        let (key, value) = line.split_once(' ')?;
+        if key == "usage_usec" {
+            value.trim().parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn parse_cpu_stat_usage() {
+        let stat = "usage_usec 2500000\nuser_usec 2000000\nsystem_usec 500000\n";
+        assert_eq!(parse_cpu_stat_usec(stat), Some(2_500_000));
+        assert_eq!(parse_cpu_stat_usec("user_usec 1\n"), None);
+    }
+
+    #[test]
+    fn read_usage_cgroup_v2() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("memory.peak"), "536870912\n").unwrap();
+        fs::write(dir.path().join("cpu.stat"), "usage_usec 90000000\n").unwrap();
+
+        let usage = read_usage_from(dir.path());
+        assert_eq!(usage.peak_memory_bytes, Some(536_870_912));
+        assert_eq!(usage.cpu_secs, Some(90.0));
+    }
+
+    #[test]
+    fn read_usage_cgroup_v1() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("memory")).unwrap();
+        fs::create_dir(dir.path().join("cpuacct")).unwrap();
+        fs::write(
+            dir.path().join("memory/memory.max_usage_in_bytes"),
+            "1048576\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("cpuacct/cpuacct.usage"), "1500000000\n").unwrap();
+
+        let usage = read_usage_from(dir.path());
+        assert_eq!(usage.peak_memory_bytes, Some(1_048_576));
+        assert_eq!(usage.cpu_secs, Some(1.5));
+    }
+
+    #[test]
+    fn read_usage_missing_files() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(read_usage_from(dir.path()), ResourceUsage::default());
+    }
+}
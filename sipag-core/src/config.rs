@@ -8,14 +8,57 @@
 //! sipag_dir           SIPAG_DIR                —                    ~/.sipag
 //! image               SIPAG_IMAGE              image                ghcr.io/dorky-robot/sipag-worker:latest
 //! timeout             SIPAG_TIMEOUT            timeout              7200s
-//! work_label          SIPAG_WORK_LABEL         work_label           "ready"
+//! per_issue_timeout   SIPAG_PER_ISSUE_TIMEOUT  per_issue_timeout    0 (no soft budget)
+//! work_label          SIPAG_WORK_LABEL         work_label           "ready" (comma-separated)
 //! max_open_prs        SIPAG_MAX_OPEN_PRS       max_open_prs         3 (0 = disabled)
 //! poll_interval       SIPAG_POLL_INTERVAL      poll_interval        120s
 //! heartbeat_interval  SIPAG_HEARTBEAT_INTERVAL heartbeat_interval   30s
 //! heartbeat_stale     SIPAG_HEARTBEAT_STALE    heartbeat_stale      90s
+//! state_max_age_days  SIPAG_ARCHIVE_DAYS       state_max_age_days   7
+//! log_max_age_days    SIPAG_LOG_MAX_AGE_DAYS   log_max_age_days     0 (keep logs forever)
+//! dispatch_cooldown   SIPAG_DISPATCH_COOLDOWN  dispatch_cooldown    0s (disabled)
+//! container_platform  SIPAG_CONTAINER_PLATFORM container_platform   unset (Docker chooses)
+//! cache_volume        SIPAG_CACHE_VOLUME       cache_volume         unset (volume[:/path], no shared cache)
+//! security_opt        SIPAG_SECURITY_OPT       security_opt         empty (repeatable, comma-separated)
+//! network_mode        SIPAG_NETWORK_MODE       network_mode         unset (Docker's default bridge)
+//! extra_hosts         SIPAG_EXTRA_HOSTS        extra_hosts          empty (host:ip, repeatable, comma-separated)
+//! assignee            SIPAG_ASSIGNEE           assignee             unset (any assignee)
+//! draft_prs           SIPAG_DRAFT_PRS          draft_prs            false
+//! clone_submodules    SIPAG_CLONE_SUBMODULES   clone_submodules     false
+//! clone_lfs           SIPAG_CLONE_LFS          clone_lfs            false
+//! clone_depth         SIPAG_CLONE_DEPTH        clone_depth          0 (full history)
+//! context_issue_limit SIPAG_CONTEXT_ISSUE_LIMIT context_issue_limit 30
+//! max_pr_body_chars   SIPAG_MAX_PR_BODY_CHARS  max_pr_body_chars    8000 (0 = no limit)
+//! prompt_filter_command SIPAG_PROMPT_FILTER_COMMAND prompt_filter_command unset
+//! post_merge_hook     SIPAG_POST_MERGE_HOOK    post_merge_hook      unset
+//! anthropic_base_url  SIPAG_ANTHROPIC_BASE_URL anthropic_base_url   unset (Anthropic's API)
+//! git_author_name     SIPAG_GIT_AUTHOR_NAME    git_author_name      unset ("sipag")
+//! git_author_email    SIPAG_GIT_AUTHOR_EMAIL   git_author_email     unset ("sipag@localhost")
+//! create_missing_labels SIPAG_CREATE_MISSING_LABELS create_missing_labels false
+//! require_labels      SIPAG_REQUIRE_LABELS     require_labels       empty (comma-separated)
+//! tui_backlog_counts  SIPAG_TUI_BACKLOG_COUNTS tui_backlog_counts   false
+//! branch_prefix       SIPAG_BRANCH_PREFIX      branch_prefix        "sipag/"
+//! rerequest_review    SIPAG_REREQUEST_REVIEW   rerequest_review     false
+//! audit_comments      SIPAG_AUDIT_COMMENTS     audit_comments       false
+//! propose_only        SIPAG_PROPOSE_ONLY       propose_only         false
+//! min_issue_age       SIPAG_MIN_ISSUE_AGE      min_issue_age        0s (no delay)
+//! skip_marker         SIPAG_SKIP_MARKER        skip_marker          "<!-- no-sipag -->" (empty = off)
+//! label_timeouts      SIPAG_LABEL_TIMEOUTS     label_timeouts       empty (label:secs,...)
+//! commit_prefixes     SIPAG_COMMIT_PREFIXES    commit_prefixes      empty (label:prefix,...)
+//! check_pr_requirements SIPAG_CHECK_PR_REQUIREMENTS check_pr_requirements false
+//! ```
+//!
+//! Entries under an `[owner/repo]` section header apply to that repo only and
+//! may set the credential overrides in [`REPO_SECTION_KEYS`]:
+//!
+//! ```text
+//! [acme/widgets]
+//! gh_token_env=ACME_GH_TOKEN
+//! token_file=tokens/acme
 //! ```
 
 use anyhow::Result;
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::{env, fs};
 
@@ -24,17 +67,67 @@ const TIMEOUT_MIN_SECS: u64 = 1;
 /// Default Docker image for worker containers.
 pub const DEFAULT_IMAGE: &str = "ghcr.io/dorky-robot/sipag-worker:latest";
 
+/// Where `cache_volume` is mounted when the value names no path. The worker
+/// image creates it owned by the `sipag` user, so a fresh volume inherits
+/// that ownership.
+pub const DEFAULT_CACHE_PATH: &str = "/home/sipag/.cache";
+
+/// Default branch prefix for sipag PRs.
+pub const DEFAULT_BRANCH_PREFIX: &str = "sipag/";
+
+/// Default issue-body marker that keeps an issue out of the candidates.
+pub const DEFAULT_SKIP_MARKER: &str = "<!-- no-sipag -->";
+
+/// Why `network_mode=none` is warned about: the worker clones over the network.
+const NETWORK_NONE_WARNING: &str = "cuts the worker off from GitHub; its clone will fail";
+
 /// All known keys in the `~/.sipag/config` file.
 const KNOWN_KEYS: &[&str] = &[
     "image",
     "timeout",
+    "per_issue_timeout",
     "work_label",
     "max_open_prs",
     "poll_interval",
     "heartbeat_interval",
     "heartbeat_stale",
+    "state_max_age_days",
+    "log_max_age_days",
+    "dispatch_cooldown",
+    "container_platform",
+    "cache_volume",
+    "security_opt",
+    "network_mode",
+    "extra_hosts",
+    "assignee",
+    "draft_prs",
+    "clone_submodules",
+    "clone_lfs",
+    "clone_depth",
+    "check_pr_requirements",
+    "context_issue_limit",
+    "max_pr_body_chars",
+    "prompt_filter_command",
+    "post_merge_hook",
+    "anthropic_base_url",
+    "git_author_name",
+    "git_author_email",
+    "create_missing_labels",
+    "require_labels",
+    "tui_backlog_counts",
+    "branch_prefix",
+    "rerequest_review",
+    "audit_comments",
+    "propose_only",
+    "min_issue_age",
+    "skip_marker",
+    "label_timeouts",
+    "commit_prefixes",
 ];
 
+/// Keys allowed inside an `[owner/repo]` section of the config file.
+pub const REPO_SECTION_KEYS: &[&str] = &["gh_token_env", "token_file"];
+
 /// Runtime configuration for sipag.
 #[derive(Debug, Clone)]
 pub struct WorkerConfig {
@@ -44,7 +137,13 @@ pub struct WorkerConfig {
     pub image: String,
     /// Per-container execution timeout in seconds (default 7200).
     pub timeout: u64,
-    /// GitHub issue label that marks a task ready for dispatch (default "ready").
+    /// Soft time budget per linked issue, in seconds, that the worker is told
+    /// to finish within so it wraps up before `timeout` kills it (default 0 =
+    /// no budget).
+    pub per_issue_timeout: u64,
+    /// GitHub issue label(s) that mark a task ready for dispatch, as a
+    /// comma-separated list in priority order (default "ready"). Use
+    /// [`work_labels`](Self::work_labels) to get the parsed list.
     pub work_label: String,
     /// Maximum open sipag PRs before dispatch is paused (default 3; 0 = disabled).
     pub max_open_prs: usize,
@@ -54,6 +153,102 @@ pub struct WorkerConfig {
     pub heartbeat_interval: u64,
     /// Seconds after which a stale heartbeat means the worker is dead (default 90).
     pub heartbeat_stale_secs: u64,
+    /// Days after which terminal workers are hidden from the TUI archive and
+    /// removed by `sipag prune` (default 7).
+    pub state_max_age_days: u64,
+    /// Days after which worker log files are removed by `sipag prune` and at
+    /// dispatch, independent of state retention (default 0 = never).
+    pub log_max_age_days: u64,
+    /// Seconds after a worker starts during which `sipag dispatch` refuses
+    /// another worker for the same PR or any of its issues, even if the first
+    /// one already exited (default 0 = disabled).
+    pub dispatch_cooldown: u64,
+    /// Platform passed to `docker run --platform` (e.g. `linux/amd64`).
+    /// `None` lets Docker pick the host's native platform.
+    pub container_platform: Option<String>,
+    /// Named Docker volume shared read-write by every worker, normalized to
+    /// `volume:/path` (path defaults to [`DEFAULT_CACHE_PATH`]) and passed
+    /// as `docker run -v`. Concurrent workers share it unlocked.
+    pub cache_volume: Option<String>,
+    /// Values passed as `docker run --security-opt` (e.g. `no-new-privileges`,
+    /// `seccomp=/path/profile.json`). Empty keeps Docker's defaults.
+    pub security_opt: Vec<String>,
+    /// Network passed to `docker run --network` (e.g. a locked-down user
+    /// network that only routes to GitHub and the Anthropic API). `None`
+    /// keeps Docker's default bridge.
+    pub network_mode: Option<String>,
+    /// `host:ip` entries passed as `docker run --add-host`.
+    pub extra_hosts: Vec<String>,
+    /// Only consider issues assigned to this GitHub user (`None` = any).
+    pub assignee: Option<String>,
+    /// Convert worker PRs to drafts so CI runs without pinging reviewers (default false).
+    pub draft_prs: bool,
+    /// Worker runs `git submodule update --init --recursive` after checkout
+    /// (default false).
+    pub clone_submodules: bool,
+    /// Worker runs `git lfs pull` after checkout (default false).
+    pub clone_lfs: bool,
+    /// Commits of history the worker clones (`git clone --depth`). Default 0
+    /// clones the full history, which history-dependent tasks need.
+    pub clone_depth: u64,
+    /// Probe the PR's base-branch protection before dispatch and warn about
+    /// rules a worker can't satisfy (default false).
+    pub check_pr_requirements: bool,
+    /// Max open issues a worker lists when scanning for related issues (default 30).
+    pub context_issue_limit: usize,
+    /// Characters of the PR body the worker puts in its prompt; longer bodies
+    /// are truncated with a marker (default 8000, 0 = no limit).
+    pub max_pr_body_chars: usize,
+    /// Shell command run on the host during `sipag dispatch` that receives the
+    /// assembled prompt on stdin and prints the transformed prompt; a non-zero
+    /// exit aborts dispatch (default unset).
+    pub prompt_filter_command: Option<String>,
+    /// Shell command run on the host after `merge-status --merge` merges a PR, with
+    /// `SIPAG_REPO`, `SIPAG_PR` and `SIPAG_ISSUES` set (default unset).
+    pub post_merge_hook: Option<String>,
+    /// Base URL Claude Code inside workers sends API traffic to, forwarded as
+    /// `ANTHROPIC_BASE_URL` (e.g. an LLM gateway). `None` uses Anthropic's API.
+    pub anthropic_base_url: Option<String>,
+    /// Commit author/committer name inside workers (default unset → "sipag").
+    pub git_author_name: Option<String>,
+    /// Commit author/committer email inside workers (default unset → "sipag@localhost").
+    pub git_author_email: Option<String>,
+    /// Create labels that don't exist on a repo before adding them to issues
+    /// (default false: warn and skip instead).
+    pub create_missing_labels: bool,
+    /// Labels an issue must carry, in addition to `work_label`, to be a
+    /// dispatch candidate (default empty).
+    pub require_labels: Vec<String>,
+    /// Show per-repo open issue/PR counts in the TUI header. Off by default
+    /// because it makes the TUI call `gh` periodically.
+    pub tui_backlog_counts: bool,
+    /// Branch prefix that marks a PR as sipag's (default `sipag/`). Used to
+    /// recognize sipag PRs alongside the `sipag` label, and checked against
+    /// the PR branch by `sipag dispatch`.
+    pub branch_prefix: String,
+    /// After a successful run, re-request review from reviewers whose latest
+    /// review requested changes (default false).
+    pub rerequest_review: bool,
+    /// Comment on each linked issue when a worker is dispatched for it and
+    /// when it finishes successfully (default false).
+    pub audit_comments: bool,
+    /// Workers never push: the change is saved to `~/.sipag/proposals/` and
+    /// posted on the PR as a diff for a human to apply (default false).
+    pub propose_only: bool,
+    /// Seconds since an issue's last update before it counts as ready, so
+    /// issues still being edited aren't picked up (default 0).
+    pub min_issue_age: u64,
+    /// Issues whose body contains this text (case-insensitive) are never
+    /// candidates, even when labeled ready (default `<!-- no-sipag -->`;
+    /// empty disables the check).
+    pub skip_marker: String,
+    /// Per-label timeout overrides, in config order. A dispatched PR whose
+    /// linked issues carry one of these labels runs with that timeout
+    /// instead of `timeout` (default empty).
+    pub label_timeouts: Vec<(String, u64)>,
+    /// Issue label → commit message prefix (e.g. `bug` → `fix`), in config
+    /// order. Dispatch tells the worker which prefix to use (default empty).
+    pub commit_prefixes: Vec<(String, String)>,
 }
 
 impl WorkerConfig {
@@ -61,11 +256,52 @@ impl WorkerConfig {
     pub fn load(sipag_dir: &Path) -> Result<Self> {
         let (cfg, warnings) = Self::load_with_env_inner(sipag_dir, |k| env::var(k).ok())?;
         for w in &warnings {
-            eprintln!("sipag warning: {w}");
+            log::warn!("{w}");
         }
         Ok(cfg)
     }
 
+    /// Like [`load`](Self::load), but discards warnings instead of printing
+    /// them. For callers that own the terminal (the TUI).
+    pub fn load_quiet(sipag_dir: &Path) -> Result<Self> {
+        let (cfg, _warnings) = Self::load_with_env_inner(sipag_dir, |k| env::var(k).ok())?;
+        Ok(cfg)
+    }
+
+    /// The configured work labels, in priority order.
+    pub fn work_labels(&self) -> Vec<String> {
+        parse_list(&self.work_label)
+    }
+
+    /// Timeout for a worker whose issues carry `labels`: the first matching
+    /// [`label_timeouts`](Self::label_timeouts) entry, else `timeout`.
+    pub fn timeout_for_labels(&self, labels: &[String]) -> u64 {
+        self.label_timeouts
+            .iter()
+            .find(|(label, _)| labels.contains(label))
+            .map_or(self.timeout, |&(_, secs)| secs)
+    }
+
+    /// Fail if any [`security_opt`](Self::security_opt) entry is not a
+    /// well-formed `--security-opt` value. Dispatch calls this so a typo'd
+    /// hardening option stops the worker instead of being dropped.
+    pub fn check_security_opt(&self) -> Result<()> {
+        let invalid: Vec<&str> = self
+            .security_opt
+            .iter()
+            .map(String::as_str)
+            .filter(|o| !is_valid_security_opt(o))
+            .collect();
+        if !invalid.is_empty() {
+            anyhow::bail!(
+                "security_opt {} is not a valid --security-opt value; fix it before dispatching \
+                 (see `sipag config validate`)",
+                invalid.join(", ")
+            );
+        }
+        Ok(())
+    }
+
     #[cfg(test)]
     fn load_with_env(sipag_dir: &Path, get_env: impl Fn(&str) -> Option<String>) -> Result<Self> {
         let (cfg, _warnings) = Self::load_with_env_inner(sipag_dir, get_env)?;
@@ -99,11 +335,44 @@ impl WorkerConfig {
             sipag_dir: sipag_dir.to_path_buf(),
             image: DEFAULT_IMAGE.to_string(),
             timeout: 7200,
+            per_issue_timeout: 0,
             work_label: "ready".to_string(),
             max_open_prs: 3,
             poll_interval: 120,
             heartbeat_interval: 30,
             heartbeat_stale_secs: 90,
+            state_max_age_days: 7,
+            log_max_age_days: 0,
+            dispatch_cooldown: 0,
+            container_platform: None,
+            cache_volume: None,
+            security_opt: Vec::new(),
+            network_mode: None,
+            extra_hosts: Vec::new(),
+            assignee: None,
+            draft_prs: false,
+            clone_submodules: false,
+            clone_lfs: false,
+            clone_depth: 0,
+            check_pr_requirements: false,
+            context_issue_limit: crate::prompt::DEFAULT_CONTEXT_ISSUE_LIMIT,
+            max_pr_body_chars: crate::prompt::DEFAULT_MAX_PR_BODY_CHARS,
+            prompt_filter_command: None,
+            post_merge_hook: None,
+            anthropic_base_url: None,
+            git_author_name: None,
+            git_author_email: None,
+            create_missing_labels: false,
+            require_labels: Vec::new(),
+            tui_backlog_counts: false,
+            branch_prefix: DEFAULT_BRANCH_PREFIX.to_string(),
+            rerequest_review: false,
+            audit_comments: false,
+            propose_only: false,
+            min_issue_age: 0,
+            skip_marker: DEFAULT_SKIP_MARKER.to_string(),
+            label_timeouts: Vec::new(),
+            commit_prefixes: Vec::new(),
         }
     }
 
@@ -175,6 +444,219 @@ impl WorkerConfig {
                     ));
                 }
             },
+            "state_max_age_days" => match value.parse::<u64>() {
+                Ok(n) => self.state_max_age_days = n,
+                Err(_) => {
+                    return Some(format!(
+                        "config: state_max_age_days={value} is not a valid number; using default 7"
+                    ));
+                }
+            },
+            "log_max_age_days" => match value.parse::<u64>() {
+                Ok(n) => self.log_max_age_days = n,
+                Err(_) => {
+                    return Some(format!(
+                        "config: log_max_age_days={value} is not a valid number; using default 0"
+                    ));
+                }
+            },
+            "per_issue_timeout" => match value.parse::<u64>() {
+                Ok(n) => self.per_issue_timeout = n,
+                Err(_) => {
+                    return Some(format!(
+                        "config: per_issue_timeout={value} is not a valid number; using default 0"
+                    ));
+                }
+            },
+            "clone_depth" => match value.parse::<u64>() {
+                Ok(n) => self.clone_depth = n,
+                Err(_) => {
+                    return Some(format!(
+                        "config: clone_depth={value} is not a valid number; using default 0"
+                    ));
+                }
+            },
+            "dispatch_cooldown" => match value.parse::<u64>() {
+                Ok(n) => self.dispatch_cooldown = n,
+                Err(_) => {
+                    return Some(format!(
+                        "config: dispatch_cooldown={value} is not a valid number; using default 0"
+                    ));
+                }
+            },
+            "security_opt" => {
+                // Invalid entries are kept so dispatch refuses to run rather
+                // than starting a container with weaker isolation than asked.
+                let (_, invalid) = split_security_opts(value);
+                self.security_opt.extend(parse_list(value));
+                if !invalid.is_empty() {
+                    return Some(format!(
+                        "config: security_opt {} is not a valid --security-opt value; \
+                         dispatch will refuse to start workers",
+                        invalid.join(", ")
+                    ));
+                }
+            }
+            "container_platform" => {
+                self.container_platform = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "cache_volume" => match parse_cache_volume(value) {
+                Ok(v) => self.cache_volume = v,
+                Err(e) => return Some(format!("config: cache_volume={value} {e}; ignoring")),
+            },
+            "network_mode" => {
+                self.network_mode = Some(value.to_string()).filter(|v| !v.is_empty());
+                if value == "none" {
+                    return Some(format!("config: network_mode=none {NETWORK_NONE_WARNING}"));
+                }
+            }
+            "extra_hosts" => {
+                let (valid, invalid) = split_extra_hosts(value);
+                self.extra_hosts.extend(valid);
+                if !invalid.is_empty() {
+                    return Some(format!(
+                        "config: extra_hosts {} is not host:ip; ignoring",
+                        invalid.join(", ")
+                    ));
+                }
+            }
+            "assignee" => self.assignee = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "git_author_name" => {
+                self.git_author_name = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "git_author_email" => {
+                self.git_author_email = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "require_labels" => self.require_labels = parse_list(value),
+            "skip_marker" => self.skip_marker = value.to_string(),
+            "commit_prefixes" => match parse_commit_prefixes(value) {
+                Ok(m) => self.commit_prefixes = m,
+                Err(e) => {
+                    return Some(format!(
+                        "config: commit_prefixes={value} is invalid ({e}); ignoring"
+                    ))
+                }
+            },
+            "label_timeouts" => match parse_label_timeouts(value) {
+                Ok(m) => self.label_timeouts = m,
+                Err(e) => {
+                    return Some(format!(
+                        "config: label_timeouts={value} is invalid ({e}); ignoring"
+                    ))
+                }
+            },
+            "branch_prefix" => self.branch_prefix = value.to_string(),
+            "post_merge_hook" => {
+                self.post_merge_hook = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "anthropic_base_url" => {
+                self.anthropic_base_url = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "prompt_filter_command" => {
+                self.prompt_filter_command = Some(value.to_string()).filter(|v| !v.is_empty());
+            }
+            "draft_prs" => match parse_bool(value) {
+                Some(b) => self.draft_prs = b,
+                None => {
+                    return Some(format!(
+                        "config: draft_prs={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "clone_submodules" => match parse_bool(value) {
+                Some(b) => self.clone_submodules = b,
+                None => {
+                    return Some(format!(
+                        "config: clone_submodules={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "clone_lfs" => match parse_bool(value) {
+                Some(b) => self.clone_lfs = b,
+                None => {
+                    return Some(format!(
+                        "config: clone_lfs={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "create_missing_labels" => match parse_bool(value) {
+                Some(b) => self.create_missing_labels = b,
+                None => {
+                    return Some(format!(
+                        "config: create_missing_labels={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "rerequest_review" => match parse_bool(value) {
+                Some(b) => self.rerequest_review = b,
+                None => {
+                    return Some(format!(
+                        "config: rerequest_review={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "audit_comments" => match parse_bool(value) {
+                Some(b) => self.audit_comments = b,
+                None => {
+                    return Some(format!(
+                        "config: audit_comments={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "propose_only" => match parse_bool(value) {
+                Some(b) => self.propose_only = b,
+                None => {
+                    return Some(format!(
+                        "config: propose_only={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "tui_backlog_counts" => match parse_bool(value) {
+                Some(b) => self.tui_backlog_counts = b,
+                None => {
+                    return Some(format!(
+                        "config: tui_backlog_counts={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "check_pr_requirements" => match parse_bool(value) {
+                Some(b) => self.check_pr_requirements = b,
+                None => {
+                    return Some(format!(
+                        "config: check_pr_requirements={value} is not a boolean; using default false"
+                    ));
+                }
+            },
+            "min_issue_age" => match value.parse::<u64>() {
+                Ok(n) => self.min_issue_age = n,
+                Err(_) => {
+                    return Some(format!(
+                        "config: min_issue_age={value} is not a valid number; using default 0"
+                    ));
+                }
+            },
+            "max_pr_body_chars" => match value.parse::<usize>() {
+                Ok(n) => self.max_pr_body_chars = n,
+                Err(_) => {
+                    return Some(format!(
+                        "config: max_pr_body_chars={value} is not a valid number; using default 8000"
+                    ));
+                }
+            },
+            "context_issue_limit" => match value.parse::<usize>() {
+                Ok(0) => {
+                    self.context_issue_limit = 1;
+                    return Some(
+                        "config: context_issue_limit=0 is invalid (minimum 1); using 1".to_string(),
+                    );
+                }
+                Ok(n) => self.context_issue_limit = n,
+                Err(_) => {
+                    return Some(format!(
+                        "config: context_issue_limit={value} is not a valid number; using default 30"
+                    ));
+                }
+            },
             _ => {
                 let msg = match closest_known_key(key) {
                     Some(suggestion) => format!(
@@ -261,6 +743,227 @@ impl WorkerConfig {
                 )),
             }
         }
+        if let Some(v) = get_env("SIPAG_CONTAINER_PLATFORM") {
+            self.container_platform = Some(v).filter(|v| !v.is_empty());
+        }
+        if let Some(v) = get_env("SIPAG_CACHE_VOLUME") {
+            match parse_cache_volume(&v) {
+                Ok(v) => self.cache_volume = v,
+                Err(e) => warnings.push(format!("SIPAG_CACHE_VOLUME={v} {e}; ignoring")),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_NETWORK_MODE") {
+            if v == "none" {
+                warnings.push(format!("SIPAG_NETWORK_MODE=none {NETWORK_NONE_WARNING}"));
+            }
+            self.network_mode = Some(v).filter(|v| !v.is_empty());
+        }
+        if let Some(v) = get_env("SIPAG_EXTRA_HOSTS") {
+            let (valid, invalid) = split_extra_hosts(&v);
+            self.extra_hosts = valid;
+            if !invalid.is_empty() {
+                warnings.push(format!(
+                    "SIPAG_EXTRA_HOSTS {} is not host:ip; ignoring",
+                    invalid.join(", ")
+                ));
+            }
+        }
+        if let Some(v) = get_env("SIPAG_ASSIGNEE") {
+            self.assignee = Some(v).filter(|v| !v.is_empty());
+        }
+        if let Some(v) = get_env("SIPAG_DRAFT_PRS") {
+            match parse_bool(&v) {
+                Some(b) => self.draft_prs = b,
+                None => warnings.push(format!(
+                    "SIPAG_DRAFT_PRS={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_CLONE_SUBMODULES") {
+            match parse_bool(&v) {
+                Some(b) => self.clone_submodules = b,
+                None => warnings.push(format!(
+                    "SIPAG_CLONE_SUBMODULES={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_CLONE_LFS") {
+            match parse_bool(&v) {
+                Some(b) => self.clone_lfs = b,
+                None => warnings.push(format!(
+                    "SIPAG_CLONE_LFS={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_CHECK_PR_REQUIREMENTS") {
+            match parse_bool(&v) {
+                Some(b) => self.check_pr_requirements = b,
+                None => warnings.push(format!(
+                    "SIPAG_CHECK_PR_REQUIREMENTS={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_MIN_ISSUE_AGE") {
+            match v.parse::<u64>() {
+                Ok(n) => self.min_issue_age = n,
+                Err(_) => warnings.push(format!(
+                    "SIPAG_MIN_ISSUE_AGE={v} is not a valid number; using default 0"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_CONTEXT_ISSUE_LIMIT") {
+            match v.parse::<usize>() {
+                Ok(0) => {
+                    self.context_issue_limit = 1;
+                    warnings.push(
+                        "SIPAG_CONTEXT_ISSUE_LIMIT=0 is invalid (minimum 1); using 1".to_string(),
+                    );
+                }
+                Ok(n) => self.context_issue_limit = n,
+                Err(_) => warnings.push(format!(
+                    "SIPAG_CONTEXT_ISSUE_LIMIT={v} is not a valid number; using default 30"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_MAX_PR_BODY_CHARS") {
+            match v.parse::<usize>() {
+                Ok(n) => self.max_pr_body_chars = n,
+                Err(_) => warnings.push(format!(
+                    "SIPAG_MAX_PR_BODY_CHARS={v} is not a valid number; using default 8000"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_PROMPT_FILTER_COMMAND") {
+            self.prompt_filter_command = Some(v).filter(|v| !v.is_empty());
+        }
+        if let Some(v) = get_env("SIPAG_POST_MERGE_HOOK") {
+            self.post_merge_hook = Some(v).filter(|v| !v.is_empty());
+        }
+        if let Some(v) = get_env("SIPAG_ANTHROPIC_BASE_URL") {
+            self.anthropic_base_url = Some(v).filter(|v| !v.is_empty());
+        }
+        if let Some(v) = get_env("SIPAG_GIT_AUTHOR_NAME") {
+            self.git_author_name = Some(v).filter(|v| !v.is_empty());
+        }
+        if let Some(v) = get_env("SIPAG_GIT_AUTHOR_EMAIL") {
+            self.git_author_email = Some(v).filter(|v| !v.is_empty());
+        }
+        if let Some(v) = get_env("SIPAG_CREATE_MISSING_LABELS") {
+            match parse_bool(&v) {
+                Some(b) => self.create_missing_labels = b,
+                None => warnings.push(format!(
+                    "SIPAG_CREATE_MISSING_LABELS={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_REREQUEST_REVIEW") {
+            match parse_bool(&v) {
+                Some(b) => self.rerequest_review = b,
+                None => warnings.push(format!(
+                    "SIPAG_REREQUEST_REVIEW={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_AUDIT_COMMENTS") {
+            match parse_bool(&v) {
+                Some(b) => self.audit_comments = b,
+                None => warnings.push(format!(
+                    "SIPAG_AUDIT_COMMENTS={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_PROPOSE_ONLY") {
+            match parse_bool(&v) {
+                Some(b) => self.propose_only = b,
+                None => warnings.push(format!(
+                    "SIPAG_PROPOSE_ONLY={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_TUI_BACKLOG_COUNTS") {
+            match parse_bool(&v) {
+                Some(b) => self.tui_backlog_counts = b,
+                None => warnings.push(format!(
+                    "SIPAG_TUI_BACKLOG_COUNTS={v} is not a boolean; using default false"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_BRANCH_PREFIX") {
+            self.branch_prefix = v;
+        }
+        if let Some(v) = get_env("SIPAG_REQUIRE_LABELS") {
+            self.require_labels = parse_list(&v);
+        }
+        if let Some(v) = get_env("SIPAG_SKIP_MARKER") {
+            self.skip_marker = v;
+        }
+        if let Some(v) = get_env("SIPAG_COMMIT_PREFIXES") {
+            match parse_commit_prefixes(&v) {
+                Ok(m) => self.commit_prefixes = m,
+                Err(e) => warnings.push(format!(
+                    "SIPAG_COMMIT_PREFIXES={v} is invalid ({e}); ignoring"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_LABEL_TIMEOUTS") {
+            match parse_label_timeouts(&v) {
+                Ok(m) => self.label_timeouts = m,
+                Err(e) => warnings.push(format!(
+                    "SIPAG_LABEL_TIMEOUTS={v} is invalid ({e}); ignoring"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_LOG_MAX_AGE_DAYS") {
+            match v.parse::<u64>() {
+                Ok(n) => self.log_max_age_days = n,
+                Err(_) => warnings.push(format!(
+                    "SIPAG_LOG_MAX_AGE_DAYS={v} is not a valid number; using default 0"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_PER_ISSUE_TIMEOUT") {
+            match v.parse::<u64>() {
+                Ok(n) => self.per_issue_timeout = n,
+                Err(_) => warnings.push(format!(
+                    "SIPAG_PER_ISSUE_TIMEOUT={v} is not a valid number; using default 0"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_CLONE_DEPTH") {
+            match v.parse::<u64>() {
+                Ok(n) => self.clone_depth = n,
+                Err(_) => warnings.push(format!(
+                    "SIPAG_CLONE_DEPTH={v} is not a valid number; using default 0"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_DISPATCH_COOLDOWN") {
+            match v.parse::<u64>() {
+                Ok(n) => self.dispatch_cooldown = n,
+                Err(_) => warnings.push(format!(
+                    "SIPAG_DISPATCH_COOLDOWN={v} is not a valid number; using default 0"
+                )),
+            }
+        }
+        if let Some(v) = get_env("SIPAG_SECURITY_OPT") {
+            let (_, invalid) = split_security_opts(&v);
+            self.security_opt = parse_list(&v);
+            if !invalid.is_empty() {
+                warnings.push(format!(
+                    "SIPAG_SECURITY_OPT {} is not a valid --security-opt value; \
+                     dispatch will refuse to start workers",
+                    invalid.join(", ")
+                ));
+            }
+        }
+        if let Some(v) = get_env("SIPAG_ARCHIVE_DAYS") {
+            match v.parse::<u64>() {
+                Ok(n) => self.state_max_age_days = n,
+                Err(_) => warnings.push(format!(
+                    "SIPAG_ARCHIVE_DAYS={v} is not a valid number; using default 7"
+                )),
+            }
+        }
         warnings
     }
 }
@@ -271,8 +974,16 @@ impl WorkerConfig {
 #[derive(Debug, PartialEq)]
 pub enum ConfigEntryStatus {
     Valid,
-    InvalidValue { clamped_to: String },
-    Unknown { suggestion: Option<String> },
+    InvalidValue {
+        clamped_to: String,
+    },
+    /// Invalid, with no safe fallback: commands that need the value fail.
+    Rejected {
+        reason: String,
+    },
+    Unknown {
+        suggestion: Option<String>,
+    },
 }
 
 /// A single validated config file entry, for display by `sipag doctor`.
@@ -290,10 +1001,13 @@ pub fn validate_config_file_for_doctor(sipag_dir: &Path) -> Option<Vec<ConfigFil
         return None;
     }
     let mut entries = Vec::new();
-    let _ = parse_config_file(&path, |key, value| {
-        let status = validate_entry_status(key, value);
+    let _ = parse_config_sections(&path, |section, key, value| {
+        let (key, status) = match section {
+            None => (key.to_string(), validate_entry_status(key, value)),
+            Some(repo) => (format!("{repo}.{key}"), validate_repo_entry_status(key)),
+        };
         entries.push(ConfigFileEntry {
-            key: key.to_string(),
+            key,
             value: value.to_string(),
             status,
         });
@@ -301,6 +1015,16 @@ pub fn validate_config_file_for_doctor(sipag_dir: &Path) -> Option<Vec<ConfigFil
     Some(entries)
 }
 
+fn validate_repo_entry_status(key: &str) -> ConfigEntryStatus {
+    if REPO_SECTION_KEYS.contains(&key) {
+        ConfigEntryStatus::Valid
+    } else {
+        ConfigEntryStatus::Unknown {
+            suggestion: closest_key(key, REPO_SECTION_KEYS),
+        }
+    }
+}
+
 fn validate_entry_status(key: &str, value: &str) -> ConfigEntryStatus {
     match key {
         "timeout" => match value.parse::<u64>() {
@@ -345,15 +1069,344 @@ fn validate_entry_status(key: &str, value: &str) -> ConfigEntryStatus {
                 clamped_to: "90 (default)".to_string(),
             },
         },
-        "image" | "work_label" => ConfigEntryStatus::Valid,
+        "state_max_age_days" => match value.parse::<u64>() {
+            Ok(_) => ConfigEntryStatus::Valid,
+            Err(_) => ConfigEntryStatus::InvalidValue {
+                clamped_to: "7 (default)".to_string(),
+            },
+        },
+        "cache_volume" => match parse_cache_volume(value) {
+            Ok(_) => ConfigEntryStatus::Valid,
+            Err(_) => ConfigEntryStatus::InvalidValue {
+                clamped_to: "unset (default)".to_string(),
+            },
+        },
+        "min_issue_age" | "log_max_age_days" | "dispatch_cooldown" | "clone_depth"
+        | "per_issue_timeout" => match value.parse::<u64>() {
+            Ok(_) => ConfigEntryStatus::Valid,
+            Err(_) => ConfigEntryStatus::InvalidValue {
+                clamped_to: "0 (default)".to_string(),
+            },
+        },
+        "commit_prefixes" => match parse_commit_prefixes(value) {
+            Ok(_) => ConfigEntryStatus::Valid,
+            Err(_) => ConfigEntryStatus::InvalidValue {
+                clamped_to: "empty (default)".to_string(),
+            },
+        },
+        "label_timeouts" => match parse_label_timeouts(value) {
+            Ok(_) => ConfigEntryStatus::Valid,
+            Err(_) => ConfigEntryStatus::InvalidValue {
+                clamped_to: "empty (default)".to_string(),
+            },
+        },
+        "max_pr_body_chars" => match value.parse::<usize>() {
+            Ok(_) => ConfigEntryStatus::Valid,
+            Err(_) => ConfigEntryStatus::InvalidValue {
+                clamped_to: "8000 (default)".to_string(),
+            },
+        },
+        "context_issue_limit" => match value.parse::<usize>() {
+            Ok(0) => ConfigEntryStatus::InvalidValue {
+                clamped_to: "1".to_string(),
+            },
+            Ok(_) => ConfigEntryStatus::Valid,
+            Err(_) => ConfigEntryStatus::InvalidValue {
+                clamped_to: "30 (default)".to_string(),
+            },
+        },
+        "draft_prs"
+        | "clone_submodules"
+        | "clone_lfs"
+        | "check_pr_requirements"
+        | "create_missing_labels"
+        | "tui_backlog_counts"
+        | "rerequest_review"
+        | "audit_comments"
+        | "propose_only" => match parse_bool(value) {
+            Some(_) => ConfigEntryStatus::Valid,
+            None => ConfigEntryStatus::InvalidValue {
+                clamped_to: "false (default)".to_string(),
+            },
+        },
+        "security_opt" => {
+            let invalid = split_security_opts(value).1;
+            if invalid.is_empty() {
+                ConfigEntryStatus::Valid
+            } else {
+                ConfigEntryStatus::Rejected {
+                    reason: format!(
+                        "{} is not a valid --security-opt value; dispatch refuses to run",
+                        invalid.join(", ")
+                    ),
+                }
+            }
+        }
+        "extra_hosts" => {
+            if split_extra_hosts(value).1.is_empty() {
+                ConfigEntryStatus::Valid
+            } else {
+                ConfigEntryStatus::InvalidValue {
+                    clamped_to: "invalid entries ignored".to_string(),
+                }
+            }
+        }
+        "image"
+        | "work_label"
+        | "container_platform"
+        | "network_mode"
+        | "assignee"
+        | "prompt_filter_command"
+        | "post_merge_hook"
+        | "anthropic_base_url"
+        | "git_author_name"
+        | "git_author_email"
+        | "require_labels"
+        | "skip_marker"
+        | "branch_prefix" => ConfigEntryStatus::Valid,
         _ => ConfigEntryStatus::Unknown {
             suggestion: closest_known_key(key),
         },
     }
 }
 
+/// Parse a `cache_volume` value, `volume` or `volume:/path`, into the
+/// normalized `volume:/path` mount spec. Empty unsets it.
+///
+/// Only named volumes are accepted: a host path would let workers write
+/// into the host filesystem.
+fn parse_cache_volume(value: &str) -> Result<Option<String>, &'static str> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let (name, path) = value.split_once(':').unwrap_or((value, DEFAULT_CACHE_PATH));
+    let valid_name = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    if !valid_name {
+        return Err("is not a named Docker volume");
+    }
+    if !path.starts_with('/') || path.contains(':') {
+        return Err("needs an absolute container path");
+    }
+    Ok(Some(format!("{name}:{path}")))
+}
+
+/// Parse a comma-separated list, trimming whitespace and dropping empties.
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parse a `label:secs,label:secs` mapping. Every entry must name a label and
+/// a timeout of at least [`TIMEOUT_MIN_SECS`].
+fn parse_label_timeouts(value: &str) -> std::result::Result<Vec<(String, u64)>, String> {
+    parse_list(value)
+        .iter()
+        .map(|entry| {
+            let (label, secs) = entry
+                .rsplit_once(':')
+                .ok_or_else(|| format!("'{entry}' is not label:secs"))?;
+            let label = label.trim();
+            let secs = secs
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .filter(|&n| n >= TIMEOUT_MIN_SECS)
+                .ok_or_else(|| format!("'{entry}' has no valid timeout"))?;
+            if label.is_empty() {
+                return Err(format!("'{entry}' has no label"));
+            }
+            Ok((label.to_string(), secs))
+        })
+        .collect()
+}
+
+/// Parse a `label:prefix,label:prefix` mapping, e.g. `bug:fix,enhancement:feat`.
+/// A trailing `:` on the prefix is dropped so `bug:fix:` also works.
+fn parse_commit_prefixes(value: &str) -> std::result::Result<Vec<(String, String)>, String> {
+    parse_list(value)
+        .iter()
+        .map(|entry| {
+            let (label, prefix) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("'{entry}' is not label:prefix"))?;
+            let (label, prefix) = (label.trim(), prefix.trim().trim_end_matches(':'));
+            if label.is_empty() || prefix.is_empty() {
+                return Err(format!("'{entry}' needs both a label and a prefix"));
+            }
+            Ok((label.to_string(), prefix.to_string()))
+        })
+        .collect()
+}
+
+/// Whether `opt` is a well-formed `docker run --security-opt` value.
+fn is_valid_security_opt(opt: &str) -> bool {
+    if let Some(flag) = opt.strip_prefix("no-new-privileges") {
+        return matches!(flag, "" | ":true" | ":false" | "=true" | "=false");
+    }
+    match opt.split_once('=') {
+        Some(("seccomp" | "apparmor" | "label", v)) => !v.is_empty(),
+        Some(("systempaths", "unconfined")) => true,
+        _ => false,
+    }
+}
+
+/// Split a comma-separated `extra_hosts` value into (valid, invalid) entries.
+/// Only the first `:` separates host from IP, so IPv6 addresses work.
+fn split_extra_hosts(value: &str) -> (Vec<String>, Vec<String>) {
+    parse_list(value).into_iter().partition(|h| {
+        h.split_once(':')
+            .is_some_and(|(host, ip)| !host.is_empty() && !ip.is_empty())
+    })
+}
+
+/// Split a comma-separated `security_opt` value into (valid, invalid) entries.
+fn split_security_opts(value: &str) -> (Vec<String>, Vec<String>) {
+    parse_list(value)
+        .into_iter()
+        .partition(|o| is_valid_security_opt(o))
+}
+
+/// Parse a config boolean: `true/false`, `yes/no`, `on/off`, `1/0` (case-insensitive).
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+// ── Config file editing for `sipag config get/set` ───────────────────────────
+
+/// Reject a key that isn't in [`KNOWN_KEYS`], suggesting the closest one.
+fn check_known_key(key: &str) -> Result<()> {
+    if KNOWN_KEYS.contains(&key) {
+        return Ok(());
+    }
+    match closest_known_key(key) {
+        Some(s) => anyhow::bail!("unknown config key '{key}'; did you mean '{s}'?"),
+        None => anyhow::bail!("unknown config key '{key}'"),
+    }
+}
+
+/// Value of top-level `key` in `~/.sipag/config`, if set there. The last
+/// occurrence wins, as when loading. Env vars and defaults are not consulted.
+pub fn config_get(sipag_dir: &Path, key: &str) -> Result<Option<String>> {
+    check_known_key(key)?;
+    let path = sipag_dir.join("config");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut found = None;
+    parse_config_sections(&path, |section, k, v| {
+        if section.is_none() && k == key {
+            found = Some(v.to_string());
+        }
+    })?;
+    Ok(found)
+}
+
+/// Set top-level `key=value` in `~/.sipag/config`, creating the file if needed.
+///
+/// The key and value are validated as `sipag config validate` would, and
+/// repeatable keys (`security_opt`, `extra_hosts`) are refused. Existing
+/// lines for the key are rewritten in place; otherwise the entry is added
+/// before the first `[owner/repo]` section. Comments and all other lines are
+/// kept as they are.
+pub fn config_set(sipag_dir: &Path, key: &str, value: &str) -> Result<()> {
+    check_known_key(key)?;
+    if REPEATABLE_KEYS.contains(&key) {
+        anyhow::bail!(
+            "{key} may be repeated and its lines accumulate; edit {} directly",
+            sipag_dir.join("config").display()
+        );
+    }
+    let value = value.trim();
+    match validate_entry_status(key, value) {
+        ConfigEntryStatus::InvalidValue { clamped_to } => {
+            anyhow::bail!("invalid value for {key}: '{value}' (would use {clamped_to})")
+        }
+        ConfigEntryStatus::Rejected { reason } => {
+            anyhow::bail!("invalid value for {key}: {reason}")
+        }
+        _ => {}
+    }
+    let path = sipag_dir.join("config");
+    let content = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+    fs::create_dir_all(sipag_dir)?;
+
+    // Write a temp file and rename it over the config, so a crash mid-write
+    // never leaves a truncated config behind.
+    let mut tmp = tempfile::NamedTempFile::new_in(sipag_dir)?;
+    tmp.write_all(set_config_line(&content, key, value).as_bytes())?;
+    tmp.as_file().sync_all()?;
+    if let Ok(meta) = fs::metadata(&path) {
+        fs::set_permissions(tmp.path(), meta.permissions())?;
+    }
+    tmp.persist(&path)?;
+    Ok(())
+}
+
+/// Keys whose file lines accumulate rather than override each other, so a
+/// single `config set` can't express them.
+const REPEATABLE_KEYS: &[&str] = &["security_opt", "extra_hosts"];
+
+/// `content` with every top-level `key=` line replaced by `key=value`, or
+/// the entry inserted before the first section when there is none.
+fn set_config_line(content: &str, key: &str, value: &str) -> String {
+    let entry = format!("{key}={value}");
+    let mut lines: Vec<String> = Vec::new();
+    let mut replaced = false;
+    let mut first_section = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            first_section.get_or_insert(lines.len());
+        } else if first_section.is_none()
+            && !trimmed.starts_with('#')
+            && trimmed
+                .split_once('=')
+                .is_some_and(|(k, _)| k.trim() == key)
+        {
+            lines.push(entry.clone());
+            replaced = true;
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    if !replaced {
+        // Keep the blank lines that separate the section from what precedes it.
+        let mut at = first_section.unwrap_or(lines.len());
+        while at > 0 && lines[at - 1].trim().is_empty() {
+            at -= 1;
+        }
+        lines.insert(at, entry);
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
 fn closest_known_key(unknown: &str) -> Option<String> {
-    KNOWN_KEYS
+    closest_key(unknown, KNOWN_KEYS)
+}
+
+fn closest_key(unknown: &str, candidates: &[&str]) -> Option<String> {
+    candidates
         .iter()
         .map(|k| (*k, levenshtein(unknown, k)))
         .filter(|(_, d)| *d <= 3)
@@ -395,6 +1448,64 @@ impl Credentials {
         Self::load_with_env(sipag_dir, |k| env::var(k).ok())
     }
 
+    /// Load credentials for `repo`, applying its `[owner/repo]` config section.
+    ///
+    /// `gh_token_env` names an env var holding the repo's GitHub token and
+    /// `token_file` points at its Claude OAuth token (relative paths resolve
+    /// against `sipag_dir`). Without `gh_token_env`, a GitHub token file at
+    /// [`repo_gh_token_path`] is used if present. Either falls back to the
+    /// global credentials when unset or empty. Token values are never
+    /// included in warnings.
+    pub fn load_for_repo(sipag_dir: &Path, repo: &str) -> Result<Self> {
+        Self::load_for_repo_with_env(sipag_dir, repo, |k| env::var(k).ok())
+    }
+
+    fn load_for_repo_with_env(
+        sipag_dir: &Path,
+        repo: &str,
+        get_env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let overrides = RepoCredentialOverrides::load(sipag_dir, repo)?;
+
+        let repo_gh_token = overrides
+            .gh_token_env
+            .as_deref()
+            .and_then(|var| {
+                let token = get_env(var).filter(|t| !t.is_empty());
+                if token.is_none() {
+                    log::warn!(
+                        "[{repo}] gh_token_env={var} is unset or empty; using the global GitHub token"
+                    );
+                }
+                token
+            })
+            .or_else(|| crate::auth::read_token_path(&repo_gh_token_path(sipag_dir, repo)));
+        let repo_oauth_token = overrides.token_file.as_deref().and_then(|path| {
+            let path = sipag_dir.join(path);
+            let token = crate::auth::read_token_path(&path);
+            if token.is_none() {
+                log::warn!(
+                    "[{repo}] token_file {} is missing or empty; using the global Claude token",
+                    path.display()
+                );
+            }
+            token
+        });
+
+        let oauth_token =
+            repo_oauth_token.or_else(|| Self::resolve_oauth_token(sipag_dir, &get_env));
+        let api_key = get_env("ANTHROPIC_API_KEY").filter(|s| !s.is_empty());
+        let gh_token = match repo_gh_token {
+            Some(token) => token,
+            None => Self::resolve_gh_token(&get_env)?,
+        };
+        Ok(Self {
+            oauth_token,
+            api_key,
+            gh_token,
+        })
+    }
+
     fn load_with_env(sipag_dir: &Path, get_env: impl Fn(&str) -> Option<String>) -> Result<Self> {
         let oauth_token = Self::resolve_oauth_token(sipag_dir, &get_env);
         let api_key = get_env("ANTHROPIC_API_KEY").filter(|s| !s.is_empty());
@@ -439,6 +1550,39 @@ impl Credentials {
     }
 }
 
+/// Per-repo GitHub token file: `{sipag_dir}/tokens/{owner}--{repo}`.
+pub fn repo_gh_token_path(sipag_dir: &Path, repo: &str) -> PathBuf {
+    sipag_dir.join("tokens").join(repo.replace('/', "--"))
+}
+
+/// Credential overrides from a repo's `[owner/repo]` config section.
+#[derive(Debug, Default, PartialEq)]
+struct RepoCredentialOverrides {
+    gh_token_env: Option<String>,
+    token_file: Option<PathBuf>,
+}
+
+impl RepoCredentialOverrides {
+    fn load(sipag_dir: &Path, repo: &str) -> Result<Self> {
+        let mut overrides = Self::default();
+        let config_file = sipag_dir.join("config");
+        if !config_file.exists() {
+            return Ok(overrides);
+        }
+        parse_config_sections(&config_file, |section, key, value| {
+            if section != Some(repo) || value.is_empty() {
+                return;
+            }
+            match key {
+                "gh_token_env" => overrides.gh_token_env = Some(value.to_string()),
+                "token_file" => overrides.token_file = Some(PathBuf::from(value)),
+                _ => {}
+            }
+        })?;
+        Ok(overrides)
+    }
+}
+
 /// Return the default sipag directory (`~/.sipag`).
 pub fn default_sipag_dir() -> PathBuf {
     env::var("SIPAG_DIR")
@@ -450,16 +1594,33 @@ pub fn default_sipag_dir() -> PathBuf {
         })
 }
 
-/// Parse a `key=value` config file, calling `f` for each entry.
+/// Parse a `key=value` config file, calling `f` for each global entry.
+///
+/// Entries under an `[owner/repo]` section header are skipped.
 fn parse_config_file(path: &Path, mut f: impl FnMut(&str, &str)) -> Result<()> {
+    parse_config_sections(path, |section, k, v| {
+        if section.is_none() {
+            f(k, v);
+        }
+    })
+}
+
+/// Parse a `key=value` config file, calling `f` with each entry's section
+/// (`None` before the first `[section]` header).
+fn parse_config_sections(path: &Path, mut f: impl FnMut(Option<&str>, &str, &str)) -> Result<()> {
     let content = fs::read_to_string(path)?;
+    let mut section: Option<String> = None;
     for line in content.lines() {
         let line = line.trim();
         if line.is_empty() || line.starts_with('#') {
             continue;
         }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(name.trim().to_string());
+            continue;
+        }
         if let Some((k, v)) = line.split_once('=') {
-            f(k.trim(), v.trim());
+            f(section.as_deref(), k.trim(), v.trim());
         }
     }
     Ok(())
@@ -500,6 +1661,537 @@ mod tests {
         assert_eq!(cfg.max_open_prs, 5);
     }
 
+    #[test]
+    fn worker_config_state_max_age_days() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.state_max_age_days, 7);
+
+        fs::write(dir.path().join("config"), "state_max_age_days=30\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.state_max_age_days, 30);
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| match k {
+            "SIPAG_ARCHIVE_DAYS" => Some("3".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(cfg.state_max_age_days, 3);
+    }
+
+    #[test]
+    fn worker_config_container_platform() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.container_platform, None);
+
+        fs::write(
+            dir.path().join("config"),
+            "container_platform=linux/amd64\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.container_platform.as_deref(), Some("linux/amd64"));
+
+        // An empty env var clears a file value back to "Docker chooses".
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| match k {
+            "SIPAG_CONTAINER_PLATFORM" => Some(String::new()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(cfg.container_platform, None);
+    }
+
+    #[test]
+    fn worker_config_cache_volume() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.cache_volume, None);
+
+        fs::write(dir.path().join("config"), "cache_volume=sipag-cache\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(
+            cfg.cache_volume.as_deref(),
+            Some("sipag-cache:/home/sipag/.cache")
+        );
+
+        let (cfg, warnings) = WorkerConfig::load_with_env_inner(dir.path(), |k| match k {
+            "SIPAG_CACHE_VOLUME" => Some("cargo-cache:/home/sipag/.cargo/registry".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(
+            cfg.cache_volume.as_deref(),
+            Some("cargo-cache:/home/sipag/.cargo/registry")
+        );
+
+        for bad in ["/var/cache:/cache", "cache:relative", "cache:/a:ro", "-x"] {
+            assert!(parse_cache_volume(bad).is_err(), "{bad}");
+        }
+        fs::write(dir.path().join("config"), "cache_volume=/tmp:/cache\n").unwrap();
+        let (cfg, warnings) = WorkerConfig::load_with_env_inner(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.cache_volume, None);
+        assert!(warnings[0].contains("not a named Docker volume"));
+    }
+
+    #[test]
+    fn worker_config_assignee() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.assignee, None);
+
+        fs::write(dir.path().join("config"), "assignee=octocat\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.assignee.as_deref(), Some("octocat"));
+    }
+
+    #[test]
+    fn worker_config_draft_prs() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(!cfg.draft_prs);
+
+        fs::write(dir.path().join("config"), "draft_prs=true\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.draft_prs);
+
+        fs::write(dir.path().join("config"), "draft_prs=maybe\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(!cfg.draft_prs);
+        assert!(matches!(
+            validate_entry_status("draft_prs", "maybe"),
+            ConfigEntryStatus::InvalidValue { .. }
+        ));
+    }
+
+    #[test]
+    fn worker_config_clone_flags() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(!cfg.clone_submodules);
+        assert!(!cfg.clone_lfs);
+
+        fs::write(
+            dir.path().join("config"),
+            "clone_submodules=true\nclone_lfs=yes\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.clone_submodules);
+        assert!(cfg.clone_lfs);
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_CLONE_LFS").then(|| "false".to_string())
+        })
+        .unwrap();
+        assert!(cfg.clone_submodules);
+        assert!(!cfg.clone_lfs);
+    }
+
+    #[test]
+    fn worker_config_audit_comments() {
+        let dir = TempDir::new().unwrap();
+        assert!(
+            !WorkerConfig::load_with_env(dir.path(), no_env)
+                .unwrap()
+                .audit_comments
+        );
+        fs::write(dir.path().join("config"), "audit_comments=true\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.audit_comments);
+    }
+
+    #[test]
+    fn worker_config_propose_only() {
+        let dir = TempDir::new().unwrap();
+        assert!(
+            !WorkerConfig::load_with_env(dir.path(), no_env)
+                .unwrap()
+                .propose_only
+        );
+        fs::write(dir.path().join("config"), "propose_only=true\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.propose_only);
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_PROPOSE_ONLY").then(|| "false".to_string())
+        })
+        .unwrap();
+        assert!(!cfg.propose_only);
+    }
+
+    #[test]
+    fn worker_config_context_issue_limit() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.context_issue_limit, 30);
+
+        fs::write(dir.path().join("config"), "context_issue_limit=0\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.context_issue_limit, 1);
+    }
+
+    #[test]
+    fn worker_config_max_pr_body_chars() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.max_pr_body_chars, 8000);
+
+        fs::write(dir.path().join("config"), "max_pr_body_chars=0\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.max_pr_body_chars, 0);
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_MAX_PR_BODY_CHARS").then(|| "20000".to_string())
+        })
+        .unwrap();
+        assert_eq!(cfg.max_pr_body_chars, 20000);
+    }
+
+    #[test]
+    fn worker_config_min_issue_age() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.min_issue_age, 0);
+
+        fs::write(dir.path().join("config"), "min_issue_age=300\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.min_issue_age, 300);
+
+        fs::write(dir.path().join("config"), "min_issue_age=soon\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.min_issue_age, 0);
+    }
+
+    #[test]
+    fn worker_config_skip_marker() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.skip_marker, "<!-- no-sipag -->");
+
+        fs::write(dir.path().join("config"), "skip_marker=[manual]\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.skip_marker, "[manual]");
+
+        fs::write(dir.path().join("config"), "skip_marker=\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.skip_marker.is_empty());
+    }
+
+    #[test]
+    fn worker_config_dispatch_cooldown() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.dispatch_cooldown, 0);
+
+        fs::write(dir.path().join("config"), "dispatch_cooldown=300\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.dispatch_cooldown, 300);
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_DISPATCH_COOLDOWN").then(|| "60".to_string())
+        })
+        .unwrap();
+        assert_eq!(cfg.dispatch_cooldown, 60);
+    }
+
+    #[test]
+    fn worker_config_post_merge_hook() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.post_merge_hook, None);
+
+        fs::write(
+            dir.path().join("config"),
+            "post_merge_hook=./deploy.sh --prod\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.post_merge_hook.as_deref(), Some("./deploy.sh --prod"));
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_POST_MERGE_HOOK").then(String::new)
+        })
+        .unwrap();
+        assert_eq!(cfg.post_merge_hook, None);
+    }
+
+    #[test]
+    fn worker_config_anthropic_base_url() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.anthropic_base_url, None);
+
+        fs::write(
+            dir.path().join("config"),
+            "anthropic_base_url=https://llm-gateway.internal/anthropic\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(
+            cfg.anthropic_base_url.as_deref(),
+            Some("https://llm-gateway.internal/anthropic")
+        );
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_ANTHROPIC_BASE_URL").then(String::new)
+        })
+        .unwrap();
+        assert_eq!(cfg.anthropic_base_url, None);
+    }
+
+    #[test]
+    fn worker_config_per_issue_timeout() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.per_issue_timeout, 0);
+
+        fs::write(dir.path().join("config"), "per_issue_timeout=1200\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.per_issue_timeout, 1200);
+
+        let (cfg, warnings) = WorkerConfig::load_with_env_inner(dir.path(), |k| {
+            (k == "SIPAG_PER_ISSUE_TIMEOUT").then(|| "soon".to_string())
+        })
+        .unwrap();
+        assert_eq!(cfg.per_issue_timeout, 1200);
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn worker_config_clone_depth() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.clone_depth, 0);
+
+        fs::write(dir.path().join("config"), "clone_depth=50\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.clone_depth, 50);
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_CLONE_DEPTH").then(|| "1".to_string())
+        })
+        .unwrap();
+        assert_eq!(cfg.clone_depth, 1);
+    }
+
+    #[test]
+    fn worker_config_log_max_age_days() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.log_max_age_days, 0);
+
+        fs::write(dir.path().join("config"), "log_max_age_days=3\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.log_max_age_days, 3);
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_LOG_MAX_AGE_DAYS").then(|| "14".to_string())
+        })
+        .unwrap();
+        assert_eq!(cfg.log_max_age_days, 14);
+    }
+
+    #[test]
+    fn worker_config_security_opt() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.security_opt.is_empty());
+
+        fs::write(
+            dir.path().join("config"),
+            "security_opt=no-new-privileges\nsecurity_opt=seccomp=/etc/sipag/seccomp.json, bogus\n",
+        )
+        .unwrap();
+        let (cfg, warnings) = WorkerConfig::load_with_env_inner(dir.path(), no_env).unwrap();
+        assert_eq!(
+            cfg.security_opt,
+            vec![
+                "no-new-privileges",
+                "seccomp=/etc/sipag/seccomp.json",
+                "bogus"
+            ]
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("bogus"));
+        let err = cfg.check_security_opt().unwrap_err().to_string();
+        assert!(err.contains("bogus"), "{err}");
+        assert!(!err.contains("no-new-privileges"), "{err}");
+
+        fs::write(
+            dir.path().join("config"),
+            "security_opt=no-new-privileges\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        cfg.check_security_opt().unwrap();
+    }
+
+    #[test]
+    fn worker_config_network_isolation() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.network_mode, None);
+        assert!(cfg.extra_hosts.is_empty());
+
+        fs::write(
+            dir.path().join("config"),
+            "network_mode=sipag-egress\nextra_hosts=github.com:140.82.112.3, bogus\nextra_hosts=api.anthropic.com:160.79.104.10\n",
+        )
+        .unwrap();
+        let (cfg, warnings) = WorkerConfig::load_with_env_inner(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.network_mode.as_deref(), Some("sipag-egress"));
+        assert_eq!(
+            cfg.extra_hosts,
+            vec!["github.com:140.82.112.3", "api.anthropic.com:160.79.104.10"]
+        );
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("bogus"));
+
+        // `none` is honored but warned about: the clone can't reach GitHub.
+        let (cfg, warnings) = WorkerConfig::load_with_env_inner(dir.path(), |k| match k {
+            "SIPAG_NETWORK_MODE" => Some("none".to_string()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(cfg.network_mode.as_deref(), Some("none"));
+        assert!(warnings.iter().any(|w| w.contains("clone will fail")));
+    }
+
+    #[test]
+    fn security_opt_validation() {
+        for ok in [
+            "no-new-privileges",
+            "no-new-privileges:true",
+            "seccomp=unconfined",
+            "apparmor=docker-default",
+            "label=disable",
+            "systempaths=unconfined",
+        ] {
+            assert!(is_valid_security_opt(ok), "{ok}");
+        }
+        for bad in [
+            "seccomp=",
+            "seccomp",
+            "no-new-privileges:maybe",
+            "privileged",
+            "",
+        ] {
+            assert!(!is_valid_security_opt(bad), "{bad}");
+        }
+    }
+
+    #[test]
+    fn worker_config_branch_prefix() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.branch_prefix, "sipag/");
+
+        fs::write(dir.path().join("config"), "branch_prefix=ai/\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.branch_prefix, "ai/");
+
+        let cfg = WorkerConfig::load_with_env(dir.path(), |k| {
+            (k == "SIPAG_BRANCH_PREFIX").then(|| "bot/".to_string())
+        })
+        .unwrap();
+        assert_eq!(cfg.branch_prefix, "bot/");
+    }
+
+    #[test]
+    fn worker_config_work_labels() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.work_labels(), vec!["ready"]);
+
+        fs::write(
+            dir.path().join("config"),
+            "work_label=ready-small, ready-epic\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.work_labels(), vec!["ready-small", "ready-epic"]);
+    }
+
+    #[test]
+    fn worker_config_label_timeouts() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.label_timeouts.is_empty());
+        assert_eq!(cfg.timeout_for_labels(&["ready-epic".to_string()]), 7200);
+
+        fs::write(
+            dir.path().join("config"),
+            "label_timeouts=ready-epic:14400, ready-small:1800\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(
+            cfg.label_timeouts,
+            vec![
+                ("ready-epic".to_string(), 14400),
+                ("ready-small".to_string(), 1800)
+            ]
+        );
+        let labels = |ls: &[&str]| ls.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            cfg.timeout_for_labels(&labels(&["bug", "ready-small"])),
+            1800
+        );
+        assert_eq!(
+            cfg.timeout_for_labels(&labels(&["ready-small", "ready-epic"])),
+            14400
+        );
+        assert_eq!(cfg.timeout_for_labels(&labels(&["bug"])), 7200);
+
+        fs::write(dir.path().join("config"), "label_timeouts=ready-epic\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.label_timeouts.is_empty());
+        assert_eq!(
+            validate_entry_status("label_timeouts", "ready-epic:0"),
+            ConfigEntryStatus::InvalidValue {
+                clamped_to: "empty (default)".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn worker_config_commit_prefixes() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.commit_prefixes.is_empty());
+
+        fs::write(
+            dir.path().join("config"),
+            "commit_prefixes=bug:fix, enhancement:feat:\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(
+            cfg.commit_prefixes,
+            vec![
+                ("bug".to_string(), "fix".to_string()),
+                ("enhancement".to_string(), "feat".to_string())
+            ]
+        );
+
+        fs::write(dir.path().join("config"), "commit_prefixes=bug\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.commit_prefixes.is_empty());
+    }
+
+    #[test]
+    fn worker_config_require_labels() {
+        let dir = TempDir::new().unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert!(cfg.require_labels.is_empty());
+
+        fs::write(dir.path().join("config"), "require_labels=triaged, p1,,\n").unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.require_labels, vec!["triaged", "p1"]);
+    }
+
     #[test]
     fn worker_config_env_overrides_file() {
         let dir = TempDir::new().unwrap();
@@ -610,4 +2302,191 @@ mod tests {
         .unwrap();
         assert_eq!(creds.gh_token, "my-gh");
     }
+
+    const REPO_SECTION_CONFIG: &str = "\
+timeout=600
+
+[acme/widgets]
+gh_token_env=ACME_GH_TOKEN
+token_file=tokens/acme
+
+[other/repo]
+gh_token_env=OTHER_GH_TOKEN
+";
+
+    fn section_env(k: &str) -> Option<String> {
+        match k {
+            "GH_TOKEN" => Some("global-gh".to_string()),
+            "CLAUDE_CODE_OAUTH_TOKEN" => Some("global-oauth".to_string()),
+            "ACME_GH_TOKEN" => Some("acme-gh".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn credentials_for_repo_uses_section_overrides() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("config"), REPO_SECTION_CONFIG).unwrap();
+        fs::create_dir(dir.path().join("tokens")).unwrap();
+        fs::write(dir.path().join("tokens/acme"), "acme-oauth\n").unwrap();
+
+        let creds =
+            Credentials::load_for_repo_with_env(dir.path(), "acme/widgets", section_env).unwrap();
+        assert_eq!(creds.gh_token, "acme-gh");
+        assert_eq!(creds.oauth_token, Some("acme-oauth".to_string()));
+    }
+
+    #[test]
+    fn credentials_for_repo_falls_back_to_global() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("config"), REPO_SECTION_CONFIG).unwrap();
+
+        // No section at all.
+        let creds =
+            Credentials::load_for_repo_with_env(dir.path(), "someone/else", section_env).unwrap();
+        assert_eq!(creds.gh_token, "global-gh");
+        assert_eq!(creds.oauth_token, Some("global-oauth".to_string()));
+
+        // Section names an env var that is unset.
+        let creds =
+            Credentials::load_for_repo_with_env(dir.path(), "other/repo", section_env).unwrap();
+        assert_eq!(creds.gh_token, "global-gh");
+    }
+
+    #[test]
+    fn credentials_for_repo_reads_gh_token_file() {
+        let dir = TempDir::new().unwrap();
+        let path = repo_gh_token_path(dir.path(), "someone/else");
+        assert_eq!(path, dir.path().join("tokens/someone--else"));
+        fs::create_dir(dir.path().join("tokens")).unwrap();
+        fs::write(&path, "file-gh\n").unwrap();
+
+        // The per-repo file beats the global GH_TOKEN...
+        let creds =
+            Credentials::load_for_repo_with_env(dir.path(), "someone/else", section_env).unwrap();
+        assert_eq!(creds.gh_token, "file-gh");
+
+        // ...but only for its own repo.
+        let creds =
+            Credentials::load_for_repo_with_env(dir.path(), "someone/other", section_env).unwrap();
+        assert_eq!(creds.gh_token, "global-gh");
+    }
+
+    #[test]
+    fn credentials_for_repo_gh_token_env_beats_token_file() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("config"), REPO_SECTION_CONFIG).unwrap();
+        let path = repo_gh_token_path(dir.path(), "acme/widgets");
+        fs::create_dir(dir.path().join("tokens")).unwrap();
+        fs::write(&path, "file-gh\n").unwrap();
+
+        let creds =
+            Credentials::load_for_repo_with_env(dir.path(), "acme/widgets", section_env).unwrap();
+        assert_eq!(creds.gh_token, "acme-gh");
+    }
+
+    #[test]
+    fn repo_sections_do_not_leak_into_global_config() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("config"),
+            "timeout=600\n[acme/widgets]\ntimeout=5\n",
+        )
+        .unwrap();
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.timeout, 600);
+    }
+
+    #[test]
+    fn doctor_validates_repo_section_keys() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("config"),
+            "[acme/widgets]\ngh_token_env=X\ngh_token_evn=Y\n",
+        )
+        .unwrap();
+        let entries = validate_config_file_for_doctor(dir.path()).unwrap();
+        assert_eq!(entries[0].key, "acme/widgets.gh_token_env");
+        assert!(matches!(entries[0].status, ConfigEntryStatus::Valid));
+        assert!(matches!(
+            &entries[1].status,
+            ConfigEntryStatus::Unknown { suggestion: Some(s) } if s == "gh_token_env"
+        ));
+    }
+
+    #[test]
+    fn config_set_then_get_round_trips() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(config_get(dir.path(), "timeout").unwrap(), None);
+
+        config_set(dir.path(), "timeout", "900").unwrap();
+        assert_eq!(
+            config_get(dir.path(), "timeout").unwrap().as_deref(),
+            Some("900")
+        );
+        let cfg = WorkerConfig::load_with_env(dir.path(), no_env).unwrap();
+        assert_eq!(cfg.timeout, 900);
+
+        config_set(dir.path(), "timeout", "1800").unwrap();
+        assert_eq!(
+            config_get(dir.path(), "timeout").unwrap().as_deref(),
+            Some("1800")
+        );
+    }
+
+    #[test]
+    fn config_set_preserves_comments_and_sections() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("config"),
+            "# my settings\ntimeout=900 \nimage=custom:v1\n\n[acme/widgets]\ngh_token_env=ACME\n",
+        )
+        .unwrap();
+
+        config_set(dir.path(), "timeout", "1200").unwrap();
+        config_set(dir.path(), "draft_prs", "true").unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.path().join("config")).unwrap(),
+            "# my settings\ntimeout=1200\nimage=custom:v1\ndraft_prs=true\n\n[acme/widgets]\ngh_token_env=ACME\n"
+        );
+    }
+
+    #[test]
+    fn config_set_rejects_unknown_key_and_bad_value() {
+        let dir = TempDir::new().unwrap();
+        let err = config_set(dir.path(), "timeot", "900").unwrap_err();
+        assert!(err.to_string().contains("did you mean 'timeout'"), "{err}");
+        assert!(config_get(dir.path(), "timeot").is_err());
+
+        let err = config_set(dir.path(), "draft_prs", "maybe").unwrap_err();
+        assert!(err.to_string().contains("invalid value"), "{err}");
+        assert!(!dir.path().join("config").exists());
+    }
+
+    #[test]
+    fn config_set_refuses_repeatable_keys() {
+        let dir = TempDir::new().unwrap();
+        let content = "security_opt=no-new-privileges\nsecurity_opt=seccomp=/p.json\n";
+        fs::write(dir.path().join("config"), content).unwrap();
+        for key in ["security_opt", "extra_hosts"] {
+            let err = config_set(dir.path(), key, "x").unwrap_err();
+            assert!(err.to_string().contains("edit"), "{err}");
+        }
+        assert_eq!(
+            fs::read_to_string(dir.path().join("config")).unwrap(),
+            content
+        );
+    }
+
+    #[test]
+    fn config_set_replaces_file_atomically() {
+        let dir = TempDir::new().unwrap();
+        config_set(dir.path(), "timeout", "900").unwrap();
+        config_set(dir.path(), "draft_prs", "true").unwrap();
+        let names: Vec<String> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["config"]);
+    }
 }
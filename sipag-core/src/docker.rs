@@ -24,18 +24,51 @@ pub fn resolve_timeout_command() -> Option<String> {
         .clone()
 }
 
-/// Check that Docker daemon is running and accessible.
-pub fn preflight_docker_running() -> Result<()> {
-    let status = Command::new("docker")
+/// Whether the `docker` CLI is on `PATH` and runs.
+pub fn docker_installed() -> bool {
+    Command::new("docker")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Whether the Docker daemon answers `docker info`.
+pub fn docker_daemon_running() -> bool {
+    Command::new("docker")
         .args(["info"])
         .stdout(Stdio::null())
         .stderr(Stdio::null())
-        .status();
-    match status {
-        Ok(s) if s.success() => Ok(()),
-        _ => anyhow::bail!(
-            "Docker is not running.\n\n  To fix:\n\n    Open Docker Desktop    (macOS)\n    systemctl start docker (Linux)"
-        ),
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Check that Docker is installed and its daemon is running and accessible.
+pub fn preflight_docker_running() -> Result<()> {
+    match preflight_error(docker_installed, docker_daemon_running) {
+        Some(msg) => anyhow::bail!(msg),
+        None => Ok(()),
+    }
+}
+
+/// The preflight failure message, if any. The daemon is only probed once
+/// the CLI is known to exist, so a missing install isn't reported as a
+/// stopped daemon.
+fn preflight_error(
+    installed: impl FnOnce() -> bool,
+    daemon_running: impl FnOnce() -> bool,
+) -> Option<&'static str> {
+    if !installed() {
+        Some(
+            "Docker is not installed (no `docker` on PATH).\n\n  To fix:\n\n    Install Docker Desktop  https://docs.docker.com/get-docker/\n    or your distro's docker package (Linux)",
+        )
+    } else if !daemon_running() {
+        Some(
+            "Docker daemon not running — start Docker Desktop.\n\n  To fix:\n\n    Open Docker Desktop    (macOS)\n    systemctl start docker (Linux)",
+        )
+    } else {
+        None
     }
 }
 
@@ -54,18 +87,140 @@ pub fn is_container_running(container_name: &str) -> bool {
         .unwrap_or(false)
 }
 
-/// Check that the required Docker image exists locally.
-pub fn preflight_docker_image(image: &str) -> Result<()> {
-    let status = Command::new("docker")
-        .args(["image", "inspect", image])
-        .stdout(Stdio::null())
+/// Build the `docker pull` argument list for an image, pinned to `platform` if set.
+pub fn pull_args(image: &str, platform: Option<&str>) -> Vec<String> {
+    let mut args = vec!["pull".to_string()];
+    if let Some(platform) = platform {
+        args.push("--platform".to_string());
+        args.push(platform.to_string());
+    }
+    args.push(image.to_string());
+    args
+}
+
+/// Build the `docker build` argument list for an image from the current
+/// directory, pinned to `platform` if set.
+pub fn build_args(image: &str, platform: Option<&str>) -> Vec<String> {
+    let mut args = vec!["build".to_string()];
+    if let Some(platform) = platform {
+        args.push("--platform".to_string());
+        args.push(platform.to_string());
+    }
+    args.extend(["-t".to_string(), image.to_string(), ".".to_string()]);
+    args
+}
+
+/// The Docker platform of the host, e.g. `linux/arm64` on Apple Silicon.
+pub fn host_platform() -> String {
+    platform_for_arch(std::env::consts::ARCH)
+}
+
+fn platform_for_arch(arch: &str) -> String {
+    let arch = match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        other => other,
+    };
+    format!("linux/{arch}")
+}
+
+/// Whether a configured platform differs from the host's, meaning Docker will
+/// run the worker under emulation. Only the OS/arch prefix is compared, so
+/// `linux/arm64/v8` matches an arm64 host.
+pub fn needs_emulation(configured: &str, host: &str) -> bool {
+    let base = |p: &str| p.split('/').take(2).collect::<Vec<_>>().join("/");
+    base(configured) != base(host)
+}
+
+/// Check that the required Docker image exists locally, and, when
+/// `platform` is set, that the local copy was built for it. An image pulled
+/// for the host's architecture doesn't satisfy `docker run --platform`.
+pub fn preflight_docker_image(image: &str, platform: Option<&str>) -> Result<()> {
+    let output = Command::new("docker")
+        .args([
+            "image",
+            "inspect",
+            "--format",
+            "{{.Os}}/{{.Architecture}}",
+            image,
+        ])
         .stderr(Stdio::null())
-        .status();
-    match status {
-        Ok(s) if s.success() => Ok(()),
+        .output();
+    let local = match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).trim().to_string(),
         _ => anyhow::bail!(
-            "Docker image '{}' not found.\n\n  To fix:\n\n    docker pull {}\n\n  Or build locally:\n\n    docker build -t {} .",
-            image, image, image
+            "Docker image '{}' not found.\n\n  To fix:\n\n    docker {}\n\n  Or build locally:\n\n    docker {}",
+            image,
+            pull_args(image, platform).join(" "),
+            build_args(image, platform).join(" ")
         ),
+    };
+    if let Some(platform) = platform {
+        if needs_emulation(platform, &local) {
+            anyhow::bail!(
+                "Docker image '{}' is {local}, but container_platform is {platform}.\n\n  To fix:\n\n    docker {}\n\n  Or build locally:\n\n    docker {}",
+                image,
+                pull_args(image, Some(platform)).join(" "),
+                build_args(image, Some(platform)).join(" ")
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pull_args_without_platform() {
+        assert_eq!(pull_args("img:latest", None), vec!["pull", "img:latest"]);
+    }
+
+    #[test]
+    fn pull_args_with_platform() {
+        assert_eq!(
+            pull_args("img:latest", Some("linux/amd64")),
+            vec!["pull", "--platform", "linux/amd64", "img:latest"]
+        );
+    }
+
+    #[test]
+    fn build_args_pin_platform_when_set() {
+        assert_eq!(
+            build_args("img:local", None),
+            vec!["build", "-t", "img:local", "."]
+        );
+        assert_eq!(
+            build_args("img:local", Some("linux/amd64")),
+            vec!["build", "--platform", "linux/amd64", "-t", "img:local", "."]
+        );
+    }
+
+    #[test]
+    fn platform_for_arch_maps_rust_names() {
+        assert_eq!(platform_for_arch("x86_64"), "linux/amd64");
+        assert_eq!(platform_for_arch("aarch64"), "linux/arm64");
+    }
+
+    #[test]
+    fn needs_emulation_compares_os_and_arch() {
+        assert!(needs_emulation("linux/amd64", "linux/arm64"));
+        assert!(!needs_emulation("linux/arm64", "linux/arm64"));
+        assert!(!needs_emulation("linux/arm64/v8", "linux/arm64"));
+    }
+
+    #[test]
+    fn preflight_error_distinguishes_missing_cli_from_stopped_daemon() {
+        assert_eq!(preflight_error(|| true, || true), None);
+
+        let stopped = preflight_error(|| true, || false).unwrap();
+        assert!(
+            stopped.starts_with("Docker daemon not running"),
+            "{stopped}"
+        );
+
+        let missing = preflight_error(|| false, || panic!("daemon probed")).unwrap();
+        assert!(missing.starts_with("Docker is not installed"), "{missing}");
     }
 }
@@ -0,0 +1,327 @@
+//! Drain signal: stop dispatching new workers while running ones finish.
+//!
+//! `sipag drain` writes `~/.sipag/drain`; while it exists `sipag dispatch`
+//! refuses to start workers for any repo. `sipag drain <repo>` writes
+//! `~/.sipag/drain.d/{owner}--{repo}` instead, draining just that repo.
+//! `sipag resume [repo]` removes the matching file. With `--wait`, drain
+//! blocks until no non-terminal workers remain so shutdown scripts know the
+//! fleet is actually quiescent.
+//!
+//! `sipag resume <repo>` during a global drain also writes
+//! `~/.sipag/active.d/{owner}--{repo}`, a force-active override that lets
+//! that one repo dispatch while the global drain stays in place. Precedence,
+//! highest first (see [`drain_decision`]): force-active override, global
+//! drain, per-repo drain, normal dispatch. A global drain or resume clears
+//! every override; draining a repo clears its own.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::state::WorkerState;
+
+/// Interval between worker scans while waiting for a drain to complete.
+pub const WAIT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Path of the drain signal file: the global one, or `repo`'s own.
+pub fn drain_path(sipag_dir: &Path, repo: Option<&str>) -> PathBuf {
+    match repo {
+        Some(repo) => sipag_dir.join("drain.d").join(repo.replace('/', "--")),
+        None => sipag_dir.join("drain"),
+    }
+}
+
+/// Path of `repo`'s force-active override file.
+pub fn active_path(sipag_dir: &Path, repo: &str) -> PathBuf {
+    sipag_dir.join("active.d").join(repo.replace('/', "--"))
+}
+
+/// Which drain signals are present for one repo.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrainSignals {
+    pub force_active: bool,
+    pub global: bool,
+    pub repo: bool,
+}
+
+/// Whether dispatch may start a worker for a repo, and if not, why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrainAction {
+    Dispatch,
+    DrainedGlobally,
+    DrainedRepo,
+}
+
+/// Resolve `signals` by precedence: force-active > global drain > per-repo
+/// drain > normal dispatch.
+pub fn drain_decision(signals: DrainSignals) -> DrainAction {
+    if signals.force_active {
+        DrainAction::Dispatch
+    } else if signals.global {
+        DrainAction::DrainedGlobally
+    } else if signals.repo {
+        DrainAction::DrainedRepo
+    } else {
+        DrainAction::Dispatch
+    }
+}
+
+/// Read the drain signals for `repo` from disk.
+pub fn drain_signals(sipag_dir: &Path, repo: &str) -> DrainSignals {
+    DrainSignals {
+        force_active: active_path(sipag_dir, repo).exists(),
+        global: is_draining(sipag_dir),
+        repo: drain_path(sipag_dir, Some(repo)).exists(),
+    }
+}
+
+/// Whether a global drain is in effect.
+pub fn is_draining(sipag_dir: &Path) -> bool {
+    drain_path(sipag_dir, None).exists()
+}
+
+/// Whether `repo` is draining, on its own or through a global drain that no
+/// force-active override exempts it from.
+pub fn is_repo_draining(sipag_dir: &Path, repo: &str) -> bool {
+    drain_decision(drain_signals(sipag_dir, repo)) != DrainAction::Dispatch
+}
+
+/// Repos with a force-active override, as `owner/repo`, sorted.
+pub fn force_active_repos(sipag_dir: &Path) -> Vec<String> {
+    list_repo_dir(&sipag_dir.join("active.d"))
+}
+
+/// Repos with their own drain file, as `owner/repo`, sorted.
+pub fn draining_repos(sipag_dir: &Path) -> Vec<String> {
+    list_repo_dir(&sipag_dir.join("drain.d"))
+}
+
+/// File names in `dir` turned back into `owner/repo`, sorted.
+fn list_repo_dir(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut repos: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().to_str().map(|n| n.replacen("--", "/", 1)))
+        .collect();
+    repos.sort();
+    repos
+}
+
+/// Write the drain signal file for `repo`, or the global one. Draining
+/// removes the force-active overrides it would otherwise lose to: the repo's
+/// own, or all of them for a global drain.
+pub fn set_draining(sipag_dir: &Path, repo: Option<&str>) -> Result<()> {
+    let path = drain_path(sipag_dir, repo);
+    write_signal(&path)?;
+    clear_force_active(sipag_dir, repo)
+}
+
+/// Write `repo`'s force-active override, letting it dispatch during a
+/// global drain.
+pub fn set_force_active(sipag_dir: &Path, repo: &str) -> Result<()> {
+    write_signal(&active_path(sipag_dir, repo))
+}
+
+/// Remove `repo`'s force-active override, or every override when `None`.
+pub fn clear_force_active(sipag_dir: &Path, repo: Option<&str>) -> Result<()> {
+    let result = match repo {
+        Some(repo) => std::fs::remove_file(active_path(sipag_dir, repo)),
+        None => std::fs::remove_dir_all(sipag_dir.join("active.d")),
+    };
+    match result {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn write_signal(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, format!("{}\n", chrono::Utc::now().to_rfc3339()))?;
+    Ok(())
+}
+
+/// Remove the drain signal file for `repo`, or the global one. Returns
+/// whether that drain was in effect.
+pub fn clear_draining(sipag_dir: &Path, repo: Option<&str>) -> Result<bool> {
+    match std::fs::remove_file(drain_path(sipag_dir, repo)) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Poll `scan` until it reports no non-terminal workers or `timeout` elapses.
+///
+/// `on_progress` is called with the running workers after every scan that
+/// still finds some. Returns `Ok(())` once idle, or the workers still running
+/// when the timeout hit. `None` waits indefinitely.
+pub fn wait_until_idle(
+    mut scan: impl FnMut() -> Vec<WorkerState>,
+    timeout: Option<Duration>,
+    interval: Duration,
+    mut on_progress: impl FnMut(&[WorkerState]),
+) -> std::result::Result<(), Vec<WorkerState>> {
+    let start = Instant::now();
+    loop {
+        let running: Vec<WorkerState> = scan()
+            .into_iter()
+            .filter(|w| !w.phase.is_terminal())
+            .collect();
+        if running.is_empty() {
+            return Ok(());
+        }
+        if timeout.is_some_and(|t| start.elapsed() >= t) {
+            return Err(running);
+        }
+        on_progress(&running);
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::WorkerPhase;
+    use tempfile::TempDir;
+
+    fn worker(pr_num: u64, phase: WorkerPhase) -> WorkerState {
+        WorkerState {
+            repo: "owner/repo".to_string(),
+            pr_num,
+            issues: vec![],
+            branch: format!("sipag/pr-{pr_num}"),
+            container_id: format!("sipag-owner--repo-pr-{pr_num}"),
+            phase,
+            heartbeat: "2026-01-01T00:00:00Z".to_string(),
+            started: "2026-01-01T00:00:00Z".to_string(),
+            ended: None,
+            exit_code: None,
+            error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
+            file_path: PathBuf::new(),
+        }
+    }
+
+    #[test]
+    fn drain_file_round_trip() {
+        let dir = TempDir::new().unwrap();
+        assert!(!is_draining(dir.path()));
+        assert!(!clear_draining(dir.path(), None).unwrap());
+
+        set_draining(dir.path(), None).unwrap();
+        assert!(is_draining(dir.path()));
+        assert!(is_repo_draining(dir.path(), "owner/any"));
+
+        assert!(clear_draining(dir.path(), None).unwrap());
+        assert!(!is_draining(dir.path()));
+    }
+
+    #[test]
+    fn drain_path_per_repo() {
+        let dir = Path::new("/home/u/.sipag");
+        assert_eq!(drain_path(dir, None), dir.join("drain"));
+        assert_eq!(
+            drain_path(dir, Some("acme/widgets")),
+            dir.join("drain.d").join("acme--widgets")
+        );
+    }
+
+    #[test]
+    fn repo_drain_is_scoped_to_that_repo() {
+        let dir = TempDir::new().unwrap();
+        set_draining(dir.path(), Some("acme/widgets")).unwrap();
+        assert!(is_repo_draining(dir.path(), "acme/widgets"));
+        assert!(!is_repo_draining(dir.path(), "acme/gadgets"));
+        assert!(!is_draining(dir.path()));
+        assert_eq!(draining_repos(dir.path()), vec!["acme/widgets"]);
+
+        assert!(clear_draining(dir.path(), Some("acme/widgets")).unwrap());
+        assert!(!is_repo_draining(dir.path(), "acme/widgets"));
+        assert!(draining_repos(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn wait_until_idle_returns_once_workers_finish() {
+        let mut scans = 0;
+        let mut progress = 0;
+        let result = wait_until_idle(
+            || {
+                scans += 1;
+                let phase = if scans < 3 {
+                    WorkerPhase::Working
+                } else {
+                    WorkerPhase::Finished
+                };
+                vec![worker(1, phase), worker(2, WorkerPhase::Failed)]
+            },
+            None,
+            Duration::ZERO,
+            |running| {
+                assert_eq!(running.len(), 1);
+                progress += 1;
+            },
+        );
+        assert!(result.is_ok());
+        assert_eq!(scans, 3);
+        assert_eq!(progress, 2);
+    }
+
+    #[test]
+    fn wait_until_idle_times_out_with_running_workers() {
+        let result = wait_until_idle(
+            || {
+                vec![
+                    worker(7, WorkerPhase::Working),
+                    worker(8, WorkerPhase::Finished),
+                ]
+            },
+            Some(Duration::ZERO),
+            Duration::ZERO,
+            |_| {},
+        );
+        let running = result.unwrap_err();
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].pr_num, 7);
+    }
+
+    #[test]
+    fn drain_decision_precedence() {
+        let decide = |force_active, global, repo| {
+            drain_decision(DrainSignals {
+                force_active,
+                global,
+                repo,
+            })
+        };
+        assert_eq!(decide(false, false, false), DrainAction::Dispatch);
+        assert_eq!(decide(false, false, true), DrainAction::DrainedRepo);
+        assert_eq!(decide(false, true, true), DrainAction::DrainedGlobally);
+        assert_eq!(decide(true, true, false), DrainAction::Dispatch);
+        assert_eq!(decide(true, true, true), DrainAction::Dispatch);
+    }
+
+    #[test]
+    fn force_active_exempts_repo_from_global_drain() {
+        let dir = TempDir::new().unwrap();
+        set_draining(dir.path(), None).unwrap();
+        set_force_active(dir.path(), "acme/widgets").unwrap();
+        assert!(!is_repo_draining(dir.path(), "acme/widgets"));
+        assert!(is_repo_draining(dir.path(), "acme/gadgets"));
+        assert_eq!(force_active_repos(dir.path()), vec!["acme/widgets"]);
+
+        // Draining the repo again drops its override.
+        set_draining(dir.path(), Some("acme/widgets")).unwrap();
+        assert!(is_repo_draining(dir.path(), "acme/widgets"));
+        assert!(force_active_repos(dir.path()).is_empty());
+
+        // A fresh global drain drops every override.
+        set_force_active(dir.path(), "acme/gadgets").unwrap();
+        set_draining(dir.path(), None).unwrap();
+        assert!(force_active_repos(dir.path()).is_empty());
+    }
+}
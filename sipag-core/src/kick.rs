@@ -0,0 +1,124 @@
+//! Kick signal: let the next dispatch bypass back-pressure once.
+//!
+//! Writing `~/.sipag/kick` (all repos) or `~/.sipag/kick.d/{owner}--{repo}`
+//! tells `sipag dispatch` to start a worker even when `max_open_prs` active
+//! workers are already running. The dispatch that uses the kick consumes the
+//! file once it is about to start the worker, so each kick admits one extra
+//! worker. A kick nobody uses expires after [`KICK_MAX_AGE_SECS`].
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How long an unused kick stays valid (1 hour), so a forgotten kick can't
+/// let a dispatch past back-pressure days later.
+pub const KICK_MAX_AGE_SECS: u64 = 3600;
+
+/// Path of the kick signal file: the global one, or `repo`'s own.
+pub fn kick_path(sipag_dir: &Path, repo: Option<&str>) -> PathBuf {
+    match repo {
+        Some(repo) => sipag_dir.join("kick.d").join(repo.replace('/', "--")),
+        None => sipag_dir.join("kick"),
+    }
+}
+
+/// Write the kick signal file for `repo`, or the global one. Returns its path.
+pub fn set_kick(sipag_dir: &Path, repo: Option<&str>) -> Result<PathBuf> {
+    let path = kick_path(sipag_dir, repo);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, format!("{}\n", chrono::Utc::now().to_rfc3339()))?;
+    Ok(path)
+}
+
+/// The live kick signal file for `repo`: its own if present, else the global
+/// one. Expired kicks are deleted along the way.
+fn live_kick(sipag_dir: &Path, repo: &str) -> Option<PathBuf> {
+    let max_age = Duration::from_secs(KICK_MAX_AGE_SECS);
+    [Some(repo), None].into_iter().find_map(|r| {
+        let path = kick_path(sipag_dir, r);
+        let modified = std::fs::metadata(&path).ok()?.modified().ok()?;
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+        if age > max_age {
+            let _ = std::fs::remove_file(&path);
+            return None;
+        }
+        Some(path)
+    })
+}
+
+/// Whether a live kick is pending for `repo`, without consuming it.
+pub fn has_kick(sipag_dir: &Path, repo: &str) -> bool {
+    live_kick(sipag_dir, repo).is_some()
+}
+
+/// Consume a live kick for `repo`: its own signal file if present, else the
+/// global one. Returns whether a kick was taken.
+pub fn take_kick(sipag_dir: &Path, repo: &str) -> bool {
+    live_kick(sipag_dir, repo).is_some_and(|path| std::fs::remove_file(path).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn kick_path_per_repo() {
+        let dir = Path::new("/home/u/.sipag");
+        assert_eq!(kick_path(dir, None), dir.join("kick"));
+        assert_eq!(
+            kick_path(dir, Some("acme/widgets")),
+            dir.join("kick.d").join("acme--widgets")
+        );
+    }
+
+    #[test]
+    fn take_kick_consumes_one_signal() {
+        let dir = TempDir::new().unwrap();
+        assert!(!take_kick(dir.path(), "acme/widgets"));
+
+        set_kick(dir.path(), Some("acme/widgets")).unwrap();
+        set_kick(dir.path(), None).unwrap();
+
+        // Another repo can only use the global kick.
+        assert!(take_kick(dir.path(), "acme/gadgets"));
+        assert!(!kick_path(dir.path(), None).exists());
+        assert!(!take_kick(dir.path(), "acme/gadgets"));
+
+        // Widgets still has its own, and it is consumed on use.
+        assert!(take_kick(dir.path(), "acme/widgets"));
+        assert!(!take_kick(dir.path(), "acme/widgets"));
+    }
+
+    #[test]
+    fn has_kick_peeks_without_consuming() {
+        let dir = TempDir::new().unwrap();
+        assert!(!has_kick(dir.path(), "acme/widgets"));
+        set_kick(dir.path(), None).unwrap();
+        assert!(has_kick(dir.path(), "acme/widgets"));
+        assert!(has_kick(dir.path(), "acme/widgets"));
+        assert!(take_kick(dir.path(), "acme/widgets"));
+        assert!(!has_kick(dir.path(), "acme/widgets"));
+    }
+
+    #[test]
+    fn expired_kick_is_ignored_and_removed() {
+        let dir = TempDir::new().unwrap();
+        let path = set_kick(dir.path(), Some("acme/widgets")).unwrap();
+        let old = SystemTime::now() - Duration::from_secs(KICK_MAX_AGE_SECS + 60);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        assert!(!has_kick(dir.path(), "acme/widgets"));
+        assert!(!path.exists());
+        assert!(!take_kick(dir.path(), "acme/widgets"));
+    }
+}
@@ -1,9 +1,16 @@
 pub mod auth;
+pub mod cgroup;
 pub mod config;
 pub mod docker;
+pub mod drain;
 pub mod events;
 pub mod init;
+pub mod kick;
 pub mod lessons;
+pub mod logging;
+pub mod prompt;
 pub mod repo;
 pub mod state;
+pub mod stats;
+pub mod tail;
 pub mod worker;
@@ -0,0 +1,98 @@
+//! Leveled stderr logging behind the `log` facade.
+//!
+//! Library code logs with `log::warn!`/`log::info!`/`log::debug!`; binaries
+//! call [`init`] once at startup. `SIPAG_LOG` (`error`, `warn`, `info`,
+//! `debug`, `trace`, `off`) sets the level, defaulting to `info`. Warnings
+//! keep the `sipag warning:` prefix they had as bare `eprintln!`s. The TUI
+//! never initializes a logger, so library output can't corrupt its screen.
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Environment variable that sets the log level.
+pub const LOG_ENV: &str = "SIPAG_LOG";
+
+struct StderrLogger {
+    level: LevelFilter,
+}
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("{}{}", prefix(record.level()), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Line prefix for each level. Info lines are unprefixed so ordinary
+/// progress output reads as before.
+fn prefix(level: Level) -> &'static str {
+    match level {
+        Level::Error => "sipag error: ",
+        Level::Warn => "sipag warning: ",
+        Level::Info => "",
+        Level::Debug => "sipag debug: ",
+        Level::Trace => "sipag trace: ",
+    }
+}
+
+/// Parse a `SIPAG_LOG` value (case-insensitive).
+pub fn parse_level(value: &str) -> Option<LevelFilter> {
+    value.trim().parse::<LevelFilter>().ok()
+}
+
+/// Install the stderr logger at the level from `SIPAG_LOG` (default info).
+/// An invalid value warns once and falls back to info. Calling this more
+/// than once has no effect.
+pub fn init() {
+    let raw = std::env::var(LOG_ENV).ok();
+    let level = raw.as_deref().and_then(parse_level);
+    let logger = StderrLogger {
+        level: level.unwrap_or(LevelFilter::Info),
+    };
+    let max = logger.level;
+    if log::set_logger(Box::leak(Box::new(logger))).is_ok() {
+        log::set_max_level(max);
+        if let (Some(raw), None) = (raw, level) {
+            log::warn!("{LOG_ENV}={raw} is not a log level; using info");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(filter: LevelFilter, level: Level) -> bool {
+        StderrLogger { level: filter }.enabled(&Metadata::builder().level(level).build())
+    }
+
+    #[test]
+    fn logger_respects_level_filter() {
+        assert!(enabled(LevelFilter::Info, Level::Warn));
+        assert!(enabled(LevelFilter::Info, Level::Info));
+        assert!(!enabled(LevelFilter::Info, Level::Debug));
+        assert!(enabled(LevelFilter::Debug, Level::Debug));
+        assert!(!enabled(LevelFilter::Error, Level::Warn));
+        assert!(!enabled(LevelFilter::Off, Level::Error));
+    }
+
+    #[test]
+    fn parse_level_values() {
+        assert_eq!(parse_level("debug"), Some(LevelFilter::Debug));
+        assert_eq!(parse_level(" WARN "), Some(LevelFilter::Warn));
+        assert_eq!(parse_level("off"), Some(LevelFilter::Off));
+        assert_eq!(parse_level("loud"), None);
+    }
+
+    #[test]
+    fn warnings_keep_sipag_prefix() {
+        assert_eq!(prefix(Level::Warn), "sipag warning: ");
+        assert_eq!(prefix(Level::Info), "");
+    }
+}
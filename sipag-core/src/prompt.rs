@@ -0,0 +1,422 @@
+//! Worker prompt rendering.
+//!
+//! The worker disposition (`lib/prompts/worker.md`) is a template with
+//! `{PLACEHOLDER}` tokens. Rendering lives here rather than in sipag-worker so
+//! host and container agree on placeholder names and the logic is testable.
+//!
+//! Operators can replace the embedded template without rebuilding by writing
+//! `~/.sipag/prompts/worker.md`; dispatch mounts that directory into the
+//! container and the worker prefers it via [`load_prompt_template`].
+
+use anyhow::{bail, Context, Result};
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Default cap on open issues listed in the "scan for related issues" step.
+pub const DEFAULT_CONTEXT_ISSUE_LIMIT: usize = 30;
+
+/// Default cap, in characters, on the PR body embedded in the prompt.
+pub const DEFAULT_MAX_PR_BODY_CHARS: usize = 8000;
+
+/// Appended to a body cut short by [`truncate_body`].
+pub const TRUNCATED_MARKER: &str = "\n\n…(truncated)";
+
+/// Values substituted into the worker prompt template.
+#[derive(Debug, Clone)]
+pub struct PromptVars<'a> {
+    pub repo: &'a str,
+    pub pr_num: u64,
+    pub branch: &'a str,
+    /// Max open issues the worker lists when scanning for related issues.
+    pub context_issue_limit: usize,
+}
+
+/// File name of the worker prompt override in the prompts directory.
+pub const WORKER_PROMPT_FILE: &str = "worker.md";
+
+/// Worker disposition prompt (embedded at compile time).
+pub const WORKER_PROMPT: &str = include_str!("../../lib/prompts/worker.md");
+
+/// Read template `name` from `dir`, falling back to `embedded` when the file
+/// is missing, unreadable, or blank.
+pub fn load_prompt_template(dir: &Path, name: &str, embedded: &str) -> String {
+    match std::fs::read_to_string(dir.join(name)) {
+        Ok(content) if !content.trim().is_empty() => content,
+        _ => embedded.to_string(),
+    }
+}
+
+/// Substitute `{BRANCH}`, `{PR_NUM}`, `{REPO}`, and `{CONTEXT_ISSUE_LIMIT}`.
+pub fn render_worker_prompt(template: &str, vars: &PromptVars) -> String {
+    template
+        .replace("{BRANCH}", vars.branch)
+        .replace("{PR_NUM}", &vars.pr_num.to_string())
+        .replace("{REPO}", vars.repo)
+        .replace(
+            "{CONTEXT_ISSUE_LIMIT}",
+            &vars.context_issue_limit.to_string(),
+        )
+}
+
+/// The "Lessons from previous workers" prompt section for a repo's lessons
+/// file `content`, or empty when there are none.
+///
+/// Truncates from the front if the file exceeds
+/// [`lessons::DEFAULT_MAX_BYTES`](crate::lessons::DEFAULT_MAX_BYTES),
+/// cutting at the nearest `## ` heading boundary so entries stay intact.
+/// This prevents old lessons from bloating the prompt.
+pub fn lessons_section(content: &str) -> String {
+    if content.trim().is_empty() {
+        return String::new();
+    }
+    let max = crate::lessons::DEFAULT_MAX_BYTES;
+    let trimmed = if content.len() <= max {
+        content.trim()
+    } else {
+        let mut start = content.len() - max;
+        while !content.is_char_boundary(start) {
+            start += 1;
+        }
+        let tail = &content[start..];
+        match tail.find("\n## ") {
+            Some(pos) => tail[pos + 1..].trim(),
+            None => tail.trim(),
+        }
+    };
+    format!(
+        "## Lessons from previous workers\n\n\
+         Previous workers for this repo recorded the following lessons.\n\
+         Avoid repeating their mistakes:\n\n\
+         {trimmed}\n\n",
+    )
+}
+
+/// Everything around the rendered worker template that makes up the prompt.
+#[derive(Debug, Clone, Default)]
+pub struct PromptParts<'a> {
+    /// The PR description, already capped with [`truncate_body`].
+    pub pr_body: &'a str,
+    /// Output of [`lessons_section`].
+    pub lessons: &'a str,
+    pub commit_prefix: Option<&'a str>,
+    /// Soft per-issue time budget in seconds; 0 adds no budget section.
+    pub per_issue_timeout: u64,
+    pub issue_count: usize,
+    pub propose_only: bool,
+}
+
+/// Assemble the full worker prompt: PR description, lessons, the rendered
+/// `worker_prompt`, and the commit/time-budget/propose-only sections.
+pub fn assemble_worker_prompt(worker_prompt: &str, parts: &PromptParts) -> String {
+    let mut commit_section = parts
+        .commit_prefix
+        .filter(|p| !p.is_empty())
+        .map(commit_prefix_section)
+        .unwrap_or_default();
+    if parts.per_issue_timeout > 0 {
+        commit_section.push_str(&time_budget_section(
+            parts.per_issue_timeout,
+            parts.issue_count,
+        ));
+    }
+    if parts.propose_only {
+        commit_section.push_str(PROPOSE_ONLY_SECTION);
+    }
+    format!(
+        "You are a sipag worker implementing a PR. The PR description below is your\n\
+         complete assignment — it contains the architectural insight, approach, affected\n\
+         issues, and constraints.\n\
+         \n\
+         --- PR DESCRIPTION ---\n\
+         \n\
+         {}\n\
+         \n\
+         --- END PR DESCRIPTION ---\n\
+         \n\
+         {}\
+         {worker_prompt}{commit_section}",
+        parts.pr_body, parts.lessons
+    )
+}
+
+/// Prompt section telling the worker which conventional-commit prefix to use.
+pub fn commit_prefix_section(prefix: &str) -> String {
+    format!(
+        "\n## Commit messages\n\n\
+         This repo uses conventional commits. Start every commit message \
+         subject with `{prefix}: ` (for example `{prefix}: handle empty input`).\n"
+    )
+}
+
+/// Prompt section giving the worker a soft time budget of `per_issue_secs`
+/// for each of its `issue_count` issues (at least one). The host `timeout`
+/// stays the hard limit; this lets the worker wrap up before it.
+pub fn time_budget_section(per_issue_secs: u64, issue_count: usize) -> String {
+    let issues = issue_count.max(1) as u64;
+    let minutes = (per_issue_secs * issues).div_ceil(60);
+    format!(
+        "\n## Time budget\n\n\
+         You have about {minutes} minutes for this PR ({issues} issue(s) at \
+         {per_issue_secs}s each). Track your time. If you are close to the \
+         budget, stop starting new work: commit and push what you have, and \
+         note what is left in a PR comment, rather than being killed mid-change.\n"
+    )
+}
+
+/// Prompt section for `propose_only` mode. It overrides the template's push,
+/// merge and comment steps: sipag collects the diff itself.
+pub const PROPOSE_ONLY_SECTION: &str = "\n## Propose only\n\n\
+     This run is in propose-only mode, which overrides the instructions above. \
+     Commit your work locally but do NOT push, merge, or comment on the PR or \
+     issues; pushing is disabled. When you are done, stop. sipag collects your \
+     commits as a diff for a human to review and apply.\n";
+
+/// Diff characters included in a proposal comment, keeping the comment
+/// under GitHub's 65536-character limit.
+pub const MAX_PROPOSAL_DIFF_CHARS: usize = 60_000;
+
+/// PR comment body presenting a propose-only worker's `diff`.
+///
+/// A diff too long for one comment is cut short, and a truncated diff
+/// can't be applied, so the comment then points at the full patch the
+/// worker saved on the host as `patch_name` under `~/.sipag/proposals`.
+pub fn proposal_comment(diff: &str, patch_name: &str) -> String {
+    let diff = diff.trim_end();
+    let shown = truncate_body(diff, MAX_PROPOSAL_DIFF_CHARS);
+    let how_to_apply = if shown.len() == diff.len() {
+        "Apply it with `git apply` after review.".to_string()
+    } else {
+        format!(
+            "The diff below is truncated, so don't apply it from this comment. \
+             The full patch is saved on the sipag host as \
+             `~/.sipag/proposals/{patch_name}`."
+        )
+    };
+    format!(
+        "sipag proposed the following change instead of pushing \
+         (`propose_only` is on). {how_to_apply}\n\n\
+         ```diff\n{shown}\n```\n"
+    )
+}
+
+/// `body` cut to its first `max` characters plus [`TRUNCATED_MARKER`], or
+/// unchanged if it already fits. Counts chars, not bytes, so multi-byte text
+/// is never split mid-character. `max == 0` means no limit.
+pub fn truncate_body(body: &str, max: usize) -> String {
+    match body.char_indices().nth(max) {
+        Some((cut, _)) if max > 0 => format!("{}{TRUNCATED_MARKER}", &body[..cut]),
+        _ => body.to_string(),
+    }
+}
+
+/// Pipe `prompt` through `command` (run via `sh -c`) and return its stdout.
+///
+/// A non-zero exit is an error carrying the filter's stderr, so a failing
+/// filter aborts dispatch rather than silently running with an unfiltered
+/// prompt.
+pub fn apply_prompt_filter(command: &str, prompt: &str) -> Result<String> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run prompt filter: {command}"))?;
+
+    // Write on a separate thread so a filter that streams output before
+    // reading all input can't deadlock on a full pipe.
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("prompt filter stdin unavailable")?;
+    let input = prompt.to_string();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+
+    let output = child
+        .wait_with_output()
+        .context("failed to wait for prompt filter")?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "prompt filter exited with {}: {}",
+            output.status,
+            stderr.trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORKER_TEMPLATE: &str = WORKER_PROMPT;
+
+    fn vars(limit: usize) -> PromptVars<'static> {
+        PromptVars {
+            repo: "acme/app",
+            pr_num: 42,
+            branch: "sipag/pr-42",
+            context_issue_limit: limit,
+        }
+    }
+
+    #[test]
+    fn assemble_worker_prompt_orders_sections() {
+        let out = assemble_worker_prompt(
+            "DISPOSITION",
+            &PromptParts {
+                pr_body: "the assignment",
+                lessons: &lessons_section("## Lesson\nbe careful"),
+                commit_prefix: Some("fix"),
+                per_issue_timeout: 600,
+                issue_count: 2,
+                propose_only: true,
+            },
+        );
+        let pos = |needle: &str| out.find(needle).unwrap_or_else(|| panic!("{needle}"));
+        assert!(pos("the assignment") < pos("be careful"));
+        assert!(pos("be careful") < pos("DISPOSITION"));
+        assert!(pos("DISPOSITION") < pos("`fix: `"));
+        assert!(pos("`fix: `") < pos("## Time budget"));
+        assert!(pos("## Time budget") < pos("## Propose only"));
+
+        let bare = assemble_worker_prompt("DISPOSITION", &PromptParts::default());
+        assert!(!bare.contains("Lessons"));
+        assert!(!bare.contains("## Commit messages"));
+        assert!(!bare.contains("## Time budget"));
+    }
+
+    #[test]
+    fn lessons_section_keeps_whole_recent_entries() {
+        assert_eq!(lessons_section("  \n"), "");
+
+        let entry = format!("## Lesson\n{}\n", "x".repeat(1000));
+        let content = format!("{}## Newest\nkeep me\n", entry.repeat(10));
+        let out = lessons_section(&content);
+        assert!(out.contains("keep me"));
+        assert!(out.len() < content.len());
+        assert!(out.contains("mistakes:\n\n## "), "cut at a heading: {out}");
+    }
+
+    #[test]
+    fn prompt_filter_transforms_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("upper.sh");
+        std::fs::write(&script, "#!/bin/sh\ntr '[:lower:]' '[:upper:]'\n").unwrap();
+        let cmd = format!("sh {}", script.display());
+        let out = apply_prompt_filter(&cmd, "implement pr #42\n").unwrap();
+        assert_eq!(out, "IMPLEMENT PR #42\n");
+    }
+
+    #[test]
+    fn prompt_filter_nonzero_exit_is_error() {
+        let err = apply_prompt_filter("echo nope >&2; exit 3", "x").unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn load_prompt_template_prefers_override() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            load_prompt_template(dir.path(), WORKER_PROMPT_FILE, "embedded"),
+            "embedded"
+        );
+
+        std::fs::write(dir.path().join(WORKER_PROMPT_FILE), "  \n").unwrap();
+        assert_eq!(
+            load_prompt_template(dir.path(), WORKER_PROMPT_FILE, "embedded"),
+            "embedded"
+        );
+
+        std::fs::write(dir.path().join(WORKER_PROMPT_FILE), "Work on {BRANCH}\n").unwrap();
+        let template = load_prompt_template(dir.path(), WORKER_PROMPT_FILE, "embedded");
+        assert_eq!(template, "Work on {BRANCH}\n");
+        assert_eq!(
+            render_worker_prompt(&template, &vars(30)),
+            "Work on sipag/pr-42\n"
+        );
+    }
+
+    #[test]
+    fn commit_prefix_section_names_prefix() {
+        let section = commit_prefix_section("fix");
+        assert!(section.contains("`fix: `"));
+        assert!(section.starts_with("\n## Commit messages"));
+    }
+
+    #[test]
+    fn time_budget_scales_with_issue_count() {
+        let section = time_budget_section(600, 3);
+        assert!(section.starts_with("\n## Time budget"));
+        assert!(section.contains("about 30 minutes"));
+        assert!(section.contains("3 issue(s) at 600s each"));
+        // A PR without linked issues still gets one issue's budget.
+        assert!(time_budget_section(90, 0).contains("about 2 minutes"));
+    }
+
+    #[test]
+    fn proposal_comment_wraps_and_caps_diff() {
+        let comment = proposal_comment("diff --git a/x b/x\n+hi\n", "o--r--pr-1.patch");
+        assert!(comment.contains("```diff\ndiff --git a/x b/x\n+hi\n```"));
+        assert!(comment.contains("git apply"));
+        assert!(!comment.contains("o--r--pr-1.patch"));
+
+        let huge = "+".repeat(MAX_PROPOSAL_DIFF_CHARS + 10);
+        let comment = proposal_comment(&huge, "o--r--pr-1.patch");
+        assert!(comment.contains(TRUNCATED_MARKER));
+        assert!(comment.chars().count() < 65_536);
+        assert!(!comment.contains("git apply"), "{}", &comment[..300]);
+        assert!(comment.contains("`~/.sipag/proposals/o--r--pr-1.patch`"));
+    }
+
+    #[test]
+    fn renders_all_placeholders() {
+        let out = render_worker_prompt(WORKER_TEMPLATE, &vars(30));
+        assert!(out.contains("`sipag/pr-42` for PR #42 in `acme/app`"));
+        assert!(!out.contains("{BRANCH}"));
+        assert!(!out.contains("{PR_NUM}"));
+        assert!(!out.contains("{REPO}"));
+        assert!(!out.contains("{CONTEXT_ISSUE_LIMIT}"));
+    }
+
+    #[test]
+    fn issue_scan_capped_to_configured_limit() {
+        let out = render_worker_prompt(WORKER_TEMPLATE, &vars(12));
+        assert!(out
+            .contains("gh issue list --repo acme/app --state open --json number,title --limit 12"));
+        assert!(!out.contains("--limit 100"));
+    }
+
+    #[test]
+    fn truncate_body_under_limit_is_unchanged() {
+        assert_eq!(truncate_body("short", 10), "short");
+        assert_eq!(truncate_body("exactly10!", 10), "exactly10!");
+        assert_eq!(truncate_body("anything", 0), "anything");
+    }
+
+    #[test]
+    fn truncate_body_over_limit_adds_marker() {
+        assert_eq!(
+            truncate_body("abcdefghij", 4),
+            format!("abcd{TRUNCATED_MARKER}")
+        );
+    }
+
+    #[test]
+    fn truncate_body_respects_char_boundaries() {
+        // Each of these is several bytes; a byte-based cut would panic.
+        let body = "héllo 🌏 wörld";
+        assert_eq!(
+            truncate_body(body, 7),
+            format!("héllo 🌏{TRUNCATED_MARKER}")
+        );
+        assert_eq!(
+            truncate_body("🌏🌏🌏", 2),
+            format!("🌏🌏{TRUNCATED_MARKER}")
+        );
+    }
+}
@@ -15,6 +15,9 @@ pub enum WorkerPhase {
     Starting,
     Working,
     Finished,
+    /// Finished in `propose_only` mode: the change was saved and posted as a
+    /// diff for a human to apply instead of being pushed.
+    Proposed,
     Failed,
 }
 
@@ -24,6 +27,7 @@ impl fmt::Display for WorkerPhase {
             Self::Starting => write!(f, "starting"),
             Self::Working => write!(f, "working"),
             Self::Finished => write!(f, "finished"),
+            Self::Proposed => write!(f, "proposed"),
             Self::Failed => write!(f, "failed"),
         }
     }
@@ -35,13 +39,14 @@ impl WorkerPhase {
             "starting" => Self::Starting,
             "working" => Self::Working,
             "finished" => Self::Finished,
+            "proposed" => Self::Proposed,
             "failed" => Self::Failed,
             _ => Self::Failed,
         }
     }
 
     pub fn is_terminal(&self) -> bool {
-        matches!(self, Self::Finished | Self::Failed)
+        matches!(self, Self::Finished | Self::Proposed | Self::Failed)
     }
 }
 
@@ -59,10 +64,32 @@ pub struct WorkerState {
     pub ended: Option<String>,
     pub exit_code: Option<i32>,
     pub error: Option<String>,
+    /// Peak container memory, measured by the worker at completion.
+    pub peak_memory_bytes: Option<u64>,
+    /// Total container CPU time, measured by the worker at completion.
+    pub cpu_secs: Option<f64>,
     /// Path to the state file on disk.
     pub file_path: PathBuf,
 }
 
+impl WorkerState {
+    /// One-line failure description for listings: the recorded exit code
+    /// (so a timeout's 124 reads differently from a clone failure's 128)
+    /// followed by the error, when present. `None` for workers with neither,
+    /// and for finished workers' exit code 0.
+    pub fn failure_summary(&self) -> Option<String> {
+        let code = self
+            .exit_code
+            .filter(|&c| c != 0 || self.phase == WorkerPhase::Failed);
+        match (code, self.error.as_deref()) {
+            (Some(c), Some(e)) => Some(format!("exit {c} — {e}")),
+            (Some(c), None) => Some(format!("exit {c}")),
+            (None, Some(e)) => Some(e.to_string()),
+            (None, None) => None,
+        }
+    }
+}
+
 /// Compute the state file path for a given repo and PR number.
 pub fn state_file_path(sipag_dir: &Path, repo: &str, pr_num: u64) -> PathBuf {
     let slug = repo.replace('/', "--");
@@ -71,6 +98,24 @@ pub fn state_file_path(sipag_dir: &Path, repo: &str, pr_num: u64) -> PathBuf {
         .join(format!("{slug}--pr-{pr_num}.json"))
 }
 
+/// Path of the file the host-side wrapper writes the container's exit code to.
+///
+/// Sits next to the state file (`…--pr-N.exit`); see `dispatch::dispatch_worker`.
+pub fn exit_file_path(state_path: &Path) -> PathBuf {
+    state_path.with_extension("exit")
+}
+
+/// The `sub_phase` the worker last wrote to its heartbeat file
+/// (`…--pr-N.heartbeat`), e.g. `cloning`, `working`, or `grace_period`.
+pub fn read_sub_phase(state_path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(state_path.with_extension("heartbeat")).ok()?;
+    let v: serde_json::Value = serde_json::from_str(&content).ok()?;
+    v["sub_phase"]
+        .as_str()
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
 /// Read a single worker state file.
 pub fn read_state(path: &Path) -> Result<WorkerState> {
     let content = std::fs::read_to_string(path)?;
@@ -91,6 +136,8 @@ pub fn read_state(path: &Path) -> Result<WorkerState> {
         ended: v["ended"].as_str().map(|s| s.to_string()),
         exit_code: v["exit_code"].as_i64().map(|n| n as i32),
         error: v["error"].as_str().map(|s| s.to_string()),
+        peak_memory_bytes: v["peak_memory_bytes"].as_u64(),
+        cpu_secs: v["cpu_secs"].as_f64(),
         file_path: path.to_path_buf(),
     })
 }
@@ -126,14 +173,23 @@ pub fn write_state(state: &WorkerState) -> Result<()> {
     if let Some(ref error) = state.error {
         obj.insert("error".into(), error.clone().into());
     }
+    if let Some(bytes) = state.peak_memory_bytes {
+        obj.insert("peak_memory_bytes".into(), bytes.into());
+    }
+    if let Some(secs) = state.cpu_secs {
+        obj.insert("cpu_secs".into(), secs.into());
+    }
 
     let json = serde_json::to_string_pretty(&obj)?;
 
     // Atomic write: write to temp file in same directory, then rename.
     // rename(2) is atomic on POSIX when src and dst are on the same filesystem.
+    // Sync first so a crash or power loss after the rename can't expose a
+    // file whose data never reached disk.
     let parent = state.file_path.parent().unwrap_or(Path::new("."));
     let mut tmp = tempfile::NamedTempFile::new_in(parent)?;
     tmp.write_all(json.as_bytes())?;
+    tmp.as_file().sync_all()?;
     tmp.persist(&state.file_path)?;
     Ok(())
 }
@@ -153,7 +209,7 @@ pub fn list_all(sipag_dir: &Path) -> Vec<WorkerState> {
         if path.extension().and_then(|e| e.to_str()) == Some("json") {
             match read_state(&path) {
                 Ok(state) => states.push(state),
-                Err(e) => eprintln!("sipag: failed to read state file {}: {e}", path.display()),
+                Err(e) => log::warn!("failed to read state file {}: {e}", path.display()),
             }
         }
     }
@@ -170,6 +226,21 @@ pub fn remove_state(path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Whether a worker that ended at `ended` is at least `max_age_days` old as of `now`.
+///
+/// Shared by the TUI archive view (which hides expired workers) and
+/// `sipag prune` (which deletes them) so both agree on what "old" means.
+/// Workers without an end time are never expired.
+pub fn is_expired(
+    ended: Option<chrono::DateTime<chrono::Utc>>,
+    now: chrono::DateTime<chrono::Utc>,
+    max_age_days: u64,
+) -> bool {
+    ended.is_some_and(|ended| {
+        now.signed_duration_since(ended) >= chrono::Duration::days(max_age_days as i64)
+    })
+}
+
 /// Format a duration in seconds as a human-readable string.
 pub fn format_duration(secs: u64) -> String {
     if secs < 60 {
@@ -199,6 +270,8 @@ mod tests {
             ended: None,
             exit_code: None,
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: state_file_path(dir, "owner/repo", pr_num),
         }
     }
@@ -233,6 +306,48 @@ mod tests {
         assert_eq!(all.len(), 2);
     }
 
+    #[test]
+    fn list_all_skips_corrupt_files() {
+        let dir = TempDir::new().unwrap();
+        let workers = dir.path().join("workers");
+        std::fs::create_dir_all(&workers).unwrap();
+        write_state(&sample_state(dir.path(), 1)).unwrap();
+        write_state(&sample_state(dir.path(), 2)).unwrap();
+
+        // A write cut off mid-file, as after a crash without atomic writes.
+        let valid = std::fs::read_to_string(workers.join("owner--repo--pr-1.json")).unwrap();
+        std::fs::write(
+            workers.join("owner--repo--pr-3.json"),
+            &valid[..valid.len() / 2],
+        )
+        .unwrap();
+        // A temp file left behind by an interrupted write_state.
+        std::fs::write(workers.join(".tmpAbC123"), "{").unwrap();
+
+        let mut prs: Vec<u64> = list_all(dir.path()).iter().map(|s| s.pr_num).collect();
+        prs.sort_unstable();
+        assert_eq!(prs, vec![1, 2]);
+    }
+
+    #[test]
+    fn write_state_leaves_no_temp_files() {
+        let dir = TempDir::new().unwrap();
+        let mut s = sample_state(dir.path(), 1);
+        write_state(&s).unwrap();
+        s.phase = WorkerPhase::Finished;
+        write_state(&s).unwrap();
+
+        let names: Vec<String> = std::fs::read_dir(dir.path().join("workers"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["owner--repo--pr-1.json"]);
+        assert_eq!(
+            read_state(&s.file_path).unwrap().phase,
+            WorkerPhase::Finished
+        );
+    }
+
     #[test]
     fn state_file_path_format() {
         let path = state_file_path(Path::new("/tmp/.sipag"), "Dorky-Robot/sipag", 501);
@@ -247,7 +362,9 @@ mod tests {
         assert_eq!(WorkerPhase::Starting.to_string(), "starting");
         assert_eq!(WorkerPhase::Working.to_string(), "working");
         assert_eq!(WorkerPhase::Finished.to_string(), "finished");
+        assert_eq!(WorkerPhase::Proposed.to_string(), "proposed");
         assert_eq!(WorkerPhase::Failed.to_string(), "failed");
+        assert_eq!(WorkerPhase::parse("proposed"), WorkerPhase::Proposed);
     }
 
     #[test]
@@ -255,6 +372,7 @@ mod tests {
         assert!(!WorkerPhase::Starting.is_terminal());
         assert!(!WorkerPhase::Working.is_terminal());
         assert!(WorkerPhase::Finished.is_terminal());
+        assert!(WorkerPhase::Proposed.is_terminal());
         assert!(WorkerPhase::Failed.is_terminal());
     }
 
@@ -265,6 +383,24 @@ mod tests {
         assert_eq!(format_duration(3661), "1h1m");
     }
 
+    #[test]
+    fn read_sub_phase_from_heartbeat() {
+        let dir = TempDir::new().unwrap();
+        let state = sample_state(dir.path(), 5);
+        assert_eq!(read_sub_phase(&state.file_path), None);
+
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        std::fs::write(
+            state.file_path.with_extension("heartbeat"),
+            r#"{"repo":"owner/repo","pr_num":5,"sub_phase":"cloning"}"#,
+        )
+        .unwrap();
+        assert_eq!(read_sub_phase(&state.file_path).as_deref(), Some("cloning"));
+
+        std::fs::write(state.file_path.with_extension("heartbeat"), "garbage").unwrap();
+        assert_eq!(read_sub_phase(&state.file_path), None);
+    }
+
     #[test]
     fn remove_state_file() {
         let dir = TempDir::new().unwrap();
@@ -308,4 +444,25 @@ mod tests {
         assert_eq!(WorkerPhase::parse("bogus"), WorkerPhase::Failed);
         assert_eq!(WorkerPhase::parse(""), WorkerPhase::Failed);
     }
+
+    #[test]
+    fn failure_summary_includes_exit_code() {
+        let dir = TempDir::new().unwrap();
+        let mut s = sample_state(dir.path(), 1);
+        assert_eq!(s.failure_summary(), None);
+
+        s.phase = WorkerPhase::Finished;
+        s.exit_code = Some(0);
+        assert_eq!(s.failure_summary(), None);
+
+        s.phase = WorkerPhase::Failed;
+        s.exit_code = Some(124);
+        assert_eq!(s.failure_summary().as_deref(), Some("exit 124"));
+
+        s.error = Some("timed out".to_string());
+        assert_eq!(s.failure_summary().as_deref(), Some("exit 124 — timed out"));
+
+        s.exit_code = None;
+        assert_eq!(s.failure_summary().as_deref(), Some("timed out"));
+    }
 }
@@ -0,0 +1,394 @@
+//! Aggregate statistics over worker state files.
+//!
+//! `sipag ps` answers "what is running right now"; this module answers "how
+//! have workers been doing" — counts per phase, average duration, duration
+//! percentiles per outcome, and failure rate across every state file on disk.
+//!
+//! [`compute_stats`] stays pure; [`stats_json`] and [`workers_csv`] render
+//! for `sipag stats --format json|csv`. The table lives in the CLI.
+
+use crate::state::{WorkerPhase, WorkerState};
+
+/// Aggregate view over a set of workers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    pub total: usize,
+    pub starting: usize,
+    pub working: usize,
+    pub finished: usize,
+    pub proposed: usize,
+    pub failed: usize,
+    /// Mean duration of terminal workers with parsable `started`/`ended`.
+    pub avg_duration_secs: Option<u64>,
+    /// Duration percentiles of finished and failed workers, respectively.
+    pub finished_durations: Option<Percentiles>,
+    pub failed_durations: Option<Percentiles>,
+    /// Mean and maximum peak memory over workers that recorded it.
+    pub avg_peak_memory_bytes: Option<u64>,
+    pub max_peak_memory_bytes: Option<u64>,
+    /// Total CPU time over workers that recorded it.
+    pub total_cpu_secs: Option<f64>,
+}
+
+impl Stats {
+    /// Workers that reached a terminal phase.
+    pub fn terminal(&self) -> usize {
+        self.finished + self.proposed + self.failed
+    }
+
+    /// Fraction of terminal workers that failed, or `None` if none have ended.
+    pub fn failure_rate(&self) -> Option<f64> {
+        let terminal = self.terminal();
+        if terminal == 0 {
+            None
+        } else {
+            Some(self.failed as f64 / terminal as f64)
+        }
+    }
+}
+
+/// p50/p90/p99 of a set of durations, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Percentiles {
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+impl Percentiles {
+    /// Percentiles of `durations`, or `None` if empty.
+    pub fn from_durations(mut durations: Vec<u64>) -> Option<Self> {
+        durations.sort_unstable();
+        Some(Self {
+            p50: percentile(&durations, 50.0)?,
+            p90: percentile(&durations, 90.0)?,
+            p99: percentile(&durations, 99.0)?,
+        })
+    }
+}
+
+/// Nearest-rank percentile `p` (0–100) of an ascending slice.
+pub fn percentile(sorted: &[u64], p: f64) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Keep workers for `repo` (if given) that started within the last `days`
+/// days (if given). Workers with an unparsable start time are dropped when
+/// filtering by age.
+pub fn filter_states(
+    states: Vec<WorkerState>,
+    repo: Option<&str>,
+    days: Option<u64>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Vec<WorkerState> {
+    states
+        .into_iter()
+        .filter(|w| repo.is_none_or(|r| w.repo == r))
+        .filter(|w| {
+            days.is_none_or(|d| {
+                chrono::DateTime::parse_from_rfc3339(&w.started).is_ok_and(|started| {
+                    now.signed_duration_since(started) < chrono::Duration::days(d as i64)
+                })
+            })
+        })
+        .collect()
+}
+
+/// Compute aggregate stats for a set of workers.
+pub fn compute_stats(states: &[WorkerState]) -> Stats {
+    let mut stats = Stats {
+        total: states.len(),
+        ..Stats::default()
+    };
+    let mut durations: Vec<u64> = Vec::new();
+    let mut finished: Vec<u64> = Vec::new();
+    let mut failed: Vec<u64> = Vec::new();
+
+    for w in states {
+        match w.phase {
+            WorkerPhase::Starting => stats.starting += 1,
+            WorkerPhase::Working => stats.working += 1,
+            WorkerPhase::Finished => stats.finished += 1,
+            WorkerPhase::Proposed => stats.proposed += 1,
+            WorkerPhase::Failed => stats.failed += 1,
+        }
+        if w.phase.is_terminal() {
+            if let Some(secs) = duration_secs(w) {
+                durations.push(secs);
+                match w.phase {
+                    WorkerPhase::Finished => finished.push(secs),
+                    WorkerPhase::Failed => failed.push(secs),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if !durations.is_empty() {
+        stats.avg_duration_secs = Some(durations.iter().sum::<u64>() / durations.len() as u64);
+    }
+    stats.finished_durations = Percentiles::from_durations(finished);
+    stats.failed_durations = Percentiles::from_durations(failed);
+
+    let peaks: Vec<u64> = states.iter().filter_map(|w| w.peak_memory_bytes).collect();
+    if !peaks.is_empty() {
+        stats.avg_peak_memory_bytes = Some(peaks.iter().sum::<u64>() / peaks.len() as u64);
+        stats.max_peak_memory_bytes = peaks.iter().max().copied();
+    }
+    let cpu: Vec<f64> = states.iter().filter_map(|w| w.cpu_secs).collect();
+    if !cpu.is_empty() {
+        stats.total_cpu_secs = Some(cpu.iter().sum());
+    }
+    stats
+}
+
+/// Header row of [`workers_csv`].
+pub const CSV_HEADER: &str = "repo,issue,outcome,duration_s,started_at";
+
+/// The aggregate as JSON. Durations are in seconds; absent values are `null`.
+pub fn stats_json(stats: &Stats) -> serde_json::Value {
+    let percentiles = |p: Option<Percentiles>| {
+        p.map(|p| serde_json::json!({ "p50": p.p50, "p90": p.p90, "p99": p.p99 }))
+    };
+    serde_json::json!({
+        "total": stats.total,
+        "starting": stats.starting,
+        "working": stats.working,
+        "finished": stats.finished,
+        "proposed": stats.proposed,
+        "failed": stats.failed,
+        "failure_rate": stats.failure_rate(),
+        "avg_duration_secs": stats.avg_duration_secs,
+        "finished_durations": percentiles(stats.finished_durations),
+        "failed_durations": percentiles(stats.failed_durations),
+        "avg_peak_memory_bytes": stats.avg_peak_memory_bytes,
+        "max_peak_memory_bytes": stats.max_peak_memory_bytes,
+        "total_cpu_secs": stats.total_cpu_secs,
+    })
+}
+
+/// One CSV row per terminal worker, for charting throughput over time.
+/// A worker covering several issues lists them `;`-separated; an unknown
+/// duration is left empty.
+pub fn workers_csv(states: &[WorkerState]) -> String {
+    let mut out = format!("{CSV_HEADER}\n");
+    for w in states.iter().filter(|w| w.phase.is_terminal()) {
+        let issues: Vec<String> = w.issues.iter().map(u64::to_string).collect();
+        let duration = duration_secs(w).map(|d| d.to_string()).unwrap_or_default();
+        out.push_str(&format!(
+            "{},{},{},{},{}\n",
+            w.repo,
+            issues.join(";"),
+            w.phase,
+            duration,
+            w.started
+        ));
+    }
+    out
+}
+
+/// Seconds between `started` and `ended`, if both are present and parsable.
+pub fn duration_secs(w: &WorkerState) -> Option<u64> {
+    let started = chrono::DateTime::parse_from_rfc3339(&w.started).ok()?;
+    let ended = chrono::DateTime::parse_from_rfc3339(w.ended.as_deref()?).ok()?;
+    Some((ended - started).num_seconds().max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state;
+    use tempfile::TempDir;
+
+    fn write_worker(dir: &TempDir, pr_num: u64, phase: WorkerPhase, ended: Option<&str>) {
+        let s = WorkerState {
+            repo: "owner/repo".to_string(),
+            pr_num,
+            issues: vec![],
+            branch: format!("sipag/pr-{pr_num}"),
+            container_id: format!("sipag-owner--repo-pr-{pr_num}"),
+            phase,
+            heartbeat: "2026-01-01T00:00:00Z".to_string(),
+            started: "2026-01-01T00:00:00Z".to_string(),
+            ended: ended.map(|s| s.to_string()),
+            exit_code: None,
+            error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
+            file_path: state::state_file_path(dir.path(), "owner/repo", pr_num),
+        };
+        state::write_state(&s).unwrap();
+    }
+
+    #[test]
+    fn compute_stats_empty() {
+        let stats = compute_stats(&[]);
+        assert_eq!(stats, Stats::default());
+        assert_eq!(stats.failure_rate(), None);
+    }
+
+    #[test]
+    fn compute_stats_over_state_dir() {
+        let dir = TempDir::new().unwrap();
+        write_worker(&dir, 1, WorkerPhase::Working, None);
+        write_worker(&dir, 2, WorkerPhase::Finished, Some("2026-01-01T00:10:00Z"));
+        write_worker(&dir, 3, WorkerPhase::Finished, Some("2026-01-01T00:30:00Z"));
+        write_worker(&dir, 4, WorkerPhase::Failed, Some("2026-01-01T00:20:00Z"));
+        write_worker(&dir, 5, WorkerPhase::Starting, None);
+
+        let stats = compute_stats(&state::list_all(dir.path()));
+        assert_eq!(stats.total, 5);
+        assert_eq!(stats.starting, 1);
+        assert_eq!(stats.working, 1);
+        assert_eq!(stats.finished, 2);
+        assert_eq!(stats.failed, 1);
+        // (600 + 1800 + 1200) / 3
+        assert_eq!(stats.avg_duration_secs, Some(1200));
+        let rate = stats.failure_rate().unwrap();
+        assert!((rate - 1.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn percentile_nearest_rank() {
+        let d: Vec<u64> = (1..=10).map(|n| n * 10).collect();
+        assert_eq!(percentile(&d, 50.0), Some(50));
+        assert_eq!(percentile(&d, 90.0), Some(90));
+        assert_eq!(percentile(&d, 99.0), Some(100));
+        assert_eq!(percentile(&d, 0.0), Some(10));
+        assert_eq!(percentile(&[7], 99.0), Some(7));
+        assert_eq!(percentile(&[], 50.0), None);
+        assert_eq!(Percentiles::from_durations(vec![]), None);
+        assert_eq!(
+            Percentiles::from_durations(vec![300, 100, 200]),
+            Some(Percentiles {
+                p50: 200,
+                p90: 300,
+                p99: 300
+            })
+        );
+    }
+
+    #[test]
+    fn compute_stats_percentiles_by_outcome() {
+        let dir = TempDir::new().unwrap();
+        write_worker(&dir, 1, WorkerPhase::Finished, Some("2026-01-01T00:10:00Z"));
+        write_worker(&dir, 2, WorkerPhase::Finished, Some("2026-01-01T00:20:00Z"));
+        write_worker(&dir, 3, WorkerPhase::Finished, Some("2026-01-01T00:30:00Z"));
+        write_worker(&dir, 4, WorkerPhase::Failed, Some("2026-01-01T02:00:00Z"));
+
+        let stats = compute_stats(&state::list_all(dir.path()));
+        assert_eq!(
+            stats.finished_durations,
+            Some(Percentiles {
+                p50: 1200,
+                p90: 1800,
+                p99: 1800
+            })
+        );
+        assert_eq!(stats.failed_durations.unwrap().p50, 7200);
+        assert_eq!(compute_stats(&[]).finished_durations, None);
+    }
+
+    #[test]
+    fn filter_states_by_repo_and_age() {
+        let dir = TempDir::new().unwrap();
+        write_worker(&dir, 1, WorkerPhase::Finished, None);
+        let mut other =
+            state::read_state(&state::state_file_path(dir.path(), "owner/repo", 1)).unwrap();
+        other.repo = "owner/other".to_string();
+        other.pr_num = 2;
+        other.started = "2026-01-05T00:00:00Z".to_string();
+        other.file_path = state::state_file_path(dir.path(), "owner/other", 2);
+        state::write_state(&other).unwrap();
+
+        let now = "2026-01-06T00:00:00Z".parse().unwrap();
+        let all = state::list_all(dir.path());
+        assert_eq!(filter_states(all.clone(), None, None, now).len(), 2);
+        let only = filter_states(all.clone(), Some("owner/other"), None, now);
+        assert_eq!(only.len(), 1);
+        assert_eq!(only[0].pr_num, 2);
+        // PR 1 started 2026-01-01, five days before `now`.
+        let recent = filter_states(all, None, Some(3), now);
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].pr_num, 2);
+    }
+
+    #[test]
+    fn compute_stats_resource_usage() {
+        let dir = TempDir::new().unwrap();
+        for (pr, mem, cpu) in [
+            (1, Some(100), Some(1.5)),
+            (2, Some(300), Some(2.5)),
+            (3, None, None),
+        ] {
+            write_worker(
+                &dir,
+                pr,
+                WorkerPhase::Finished,
+                Some("2026-01-01T00:10:00Z"),
+            );
+            let path = state::state_file_path(dir.path(), "owner/repo", pr);
+            let mut s = state::read_state(&path).unwrap();
+            s.peak_memory_bytes = mem;
+            s.cpu_secs = cpu;
+            state::write_state(&s).unwrap();
+        }
+
+        let stats = compute_stats(&state::list_all(dir.path()));
+        assert_eq!(stats.avg_peak_memory_bytes, Some(200));
+        assert_eq!(stats.max_peak_memory_bytes, Some(300));
+        assert_eq!(stats.total_cpu_secs, Some(4.0));
+    }
+
+    #[test]
+    fn workers_csv_has_header_and_terminal_rows() {
+        let dir = TempDir::new().unwrap();
+        write_worker(&dir, 1, WorkerPhase::Working, None);
+        write_worker(&dir, 2, WorkerPhase::Finished, Some("2026-01-01T00:10:00Z"));
+        let path = state::state_file_path(dir.path(), "owner/repo", 2);
+        let mut s = state::read_state(&path).unwrap();
+        s.issues = vec![7, 8];
+        state::write_state(&s).unwrap();
+        write_worker(&dir, 3, WorkerPhase::Failed, None);
+
+        let csv = workers_csv(&state::list_all(dir.path()));
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "repo,issue,outcome,duration_s,started_at");
+        assert_eq!(lines.len(), 3);
+        assert!(lines.contains(&"owner/repo,7;8,finished,600,2026-01-01T00:00:00Z"));
+        assert!(lines.contains(&"owner/repo,,failed,,2026-01-01T00:00:00Z"));
+        assert_eq!(workers_csv(&[]), format!("{CSV_HEADER}\n"));
+    }
+
+    #[test]
+    fn stats_json_reports_aggregate() {
+        let dir = TempDir::new().unwrap();
+        write_worker(&dir, 1, WorkerPhase::Finished, Some("2026-01-01T00:10:00Z"));
+        write_worker(&dir, 2, WorkerPhase::Failed, Some("2026-01-01T00:30:00Z"));
+
+        let json = stats_json(&compute_stats(&state::list_all(dir.path())));
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["finished"], 1);
+        assert_eq!(json["failed"], 1);
+        assert_eq!(json["failure_rate"], 0.5);
+        assert_eq!(json["avg_duration_secs"], 1200);
+        assert_eq!(json["finished_durations"]["p50"], 600);
+        assert!(json["avg_peak_memory_bytes"].is_null());
+    }
+
+    #[test]
+    fn compute_stats_skips_unparsable_durations() {
+        let dir = TempDir::new().unwrap();
+        write_worker(&dir, 1, WorkerPhase::Failed, Some("not a timestamp"));
+        write_worker(&dir, 2, WorkerPhase::Failed, None);
+
+        let stats = compute_stats(&state::list_all(dir.path()));
+        assert_eq!(stats.failed, 2);
+        assert_eq!(stats.avg_duration_secs, None);
+        assert_eq!(stats.failure_rate(), Some(1.0));
+    }
+}
@@ -0,0 +1,458 @@
+//! Live log following for `sipag tail`.
+//!
+//! Worker stdout/stderr is piped straight into `~/.sipag/logs/*.log` by
+//! dispatch, so following a worker is just following a growing file until
+//! its state file reaches a terminal phase. [`RepoTail`] does the same for
+//! every running worker of a repo at once, interleaving their lines.
+
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::state::WorkerState;
+
+/// How often to poll the log file for new output.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often [`RepoTail`] polls when multiplexing a repo's workers.
+pub const MULTIPLEX_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Lines of existing output shown before following.
+pub const BACKLOG_LINES: usize = 20;
+
+/// Path of the log file for a worker.
+pub fn log_path(sipag_dir: &Path, repo: &str, pr_num: u64) -> PathBuf {
+    sipag_dir
+        .join("logs")
+        .join(format!("{}--pr-{pr_num}.log", repo.replace('/', "--")))
+}
+
+/// The non-terminal worker that started most recently, if any.
+pub fn most_recent_running(workers: &[WorkerState]) -> Option<&WorkerState> {
+    workers
+        .iter()
+        .filter(|w| !w.phase.is_terminal())
+        .max_by(|a, b| a.started.cmp(&b.started))
+}
+
+/// Print the last `n` lines of `content`.
+fn last_lines(content: &str, n: usize) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(n);
+    let mut out = lines[start..].join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// Follow the log at `path`, writing new output to `out` until `is_done`
+/// returns true. Output written before `is_done` flipped is drained before
+/// returning.
+///
+/// `is_done` is the caller's liveness check — typically "the worker's state is
+/// terminal after `scan_workers` reconciliation", so a container that died
+/// without updating its state file still ends the follow.
+pub fn follow_log(
+    path: &Path,
+    out: &mut impl Write,
+    mut is_done: impl FnMut() -> bool,
+) -> Result<()> {
+    let mut offset: u64 = 0;
+
+    if let Ok(content) = std::fs::read_to_string(path) {
+        write!(out, "{}", last_lines(&content, BACKLOG_LINES))?;
+        offset = content.len() as u64;
+    }
+
+    loop {
+        let done = is_done();
+        offset = copy_new_output(path, offset, out)?;
+        out.flush()?;
+        if done {
+            return Ok(());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Like [`follow_log`], but from the start of the log and with every line
+/// prefixed by `prefix` (e.g. `[#42] `). Used by `sipag dispatch --verbose`
+/// to echo a freshly started worker's output.
+pub fn follow_log_prefixed(
+    path: &Path,
+    prefix: &str,
+    out: &mut impl Write,
+    mut is_done: impl FnMut() -> bool,
+) -> Result<()> {
+    let mut cursor = LogCursor::new(path.to_path_buf());
+    loop {
+        let done = is_done();
+        let lines = if done {
+            cursor.finish()?
+        } else {
+            cursor.read_lines()?
+        };
+        for line in lines {
+            writeln!(out, "{prefix}{line}")?;
+        }
+        out.flush()?;
+        if done {
+            return Ok(());
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Copy bytes appended to `path` since `offset` into `out`; returns the new offset.
+fn copy_new_output(path: &Path, offset: u64, out: &mut impl Write) -> Result<u64> {
+    let Ok(mut f) = std::fs::File::open(path) else {
+        return Ok(offset);
+    };
+    let len = f.metadata()?.len();
+    // Truncated (e.g. a redispatch recreated the log) — start over.
+    let offset = if len < offset { 0 } else { offset };
+    f.seek(SeekFrom::Start(offset))?;
+    let mut buf = Vec::new();
+    f.read_to_end(&mut buf)?;
+    out.write_all(&buf)?;
+    Ok(offset + buf.len() as u64)
+}
+
+/// Byte-offset reader over a growing log file that yields complete lines.
+///
+/// A trailing partial line is held back until its newline arrives (or
+/// [`LogCursor::finish`] is called), so interleaved output never splits a line.
+/// It is kept as raw bytes and only complete lines are decoded, so a
+/// multi-byte character split across two reads comes out intact.
+#[derive(Debug)]
+pub struct LogCursor {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+}
+
+impl LogCursor {
+    /// Start reading `path` from the beginning.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Start reading `path` from its current end (only new output).
+    pub fn at_end(path: PathBuf) -> Self {
+        let offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            offset,
+            ..Self::new(path)
+        }
+    }
+
+    /// Complete lines appended since the last read.
+    pub fn read_lines(&mut self) -> Result<Vec<String>> {
+        self.offset = copy_new_output(&self.path, self.offset, &mut self.partial)?;
+        let Some(last_newline) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let rest = self.partial.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        Ok(String::from_utf8_lossy(&complete)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Remaining lines, including a trailing line without a newline.
+    pub fn finish(&mut self) -> Result<Vec<String>> {
+        let mut lines = self.read_lines()?;
+        if !self.partial.is_empty() {
+            let rest = std::mem::take(&mut self.partial);
+            lines.push(String::from_utf8_lossy(&rest).into_owned());
+        }
+        Ok(lines)
+    }
+}
+
+/// Interleaves the logs of every running worker of one repo, prefixing each
+/// line with `[#<pr>]`.
+///
+/// Call [`RepoTail::poll`] with freshly reconciled worker states on each
+/// round. Workers running at the first poll are followed from their current
+/// end; workers that start later are followed from the beginning of their
+/// log. When a worker reaches a terminal phase (or its state disappears) its
+/// remaining output is drained and a one-line summary is written.
+#[derive(Debug)]
+pub struct RepoTail {
+    sipag_dir: PathBuf,
+    repo: String,
+    cursors: BTreeMap<u64, LogCursor>,
+    started: bool,
+}
+
+impl RepoTail {
+    pub fn new(sipag_dir: &Path, repo: &str) -> Self {
+        Self {
+            sipag_dir: sipag_dir.to_path_buf(),
+            repo: repo.to_string(),
+            cursors: BTreeMap::new(),
+            started: false,
+        }
+    }
+
+    /// Number of workers currently being followed.
+    pub fn following(&self) -> usize {
+        self.cursors.len()
+    }
+
+    /// Run one polling round. Returns `true` once no workers for the repo are
+    /// running or being followed.
+    pub fn poll(&mut self, workers: &[WorkerState], out: &mut impl Write) -> Result<bool> {
+        let first = !self.started;
+        self.started = true;
+        let repo_workers: Vec<&WorkerState> =
+            workers.iter().filter(|w| w.repo == self.repo).collect();
+
+        for w in repo_workers.iter().filter(|w| !w.phase.is_terminal()) {
+            if self.cursors.contains_key(&w.pr_num) {
+                continue;
+            }
+            let path = log_path(&self.sipag_dir, &self.repo, w.pr_num);
+            let cursor = if first {
+                LogCursor::at_end(path)
+            } else {
+                LogCursor::new(path)
+            };
+            writeln!(
+                out,
+                "[#{}] ── following {} ({})",
+                w.pr_num, w.branch, w.phase
+            )?;
+            self.cursors.insert(w.pr_num, cursor);
+        }
+
+        let mut ended = Vec::new();
+        for (&pr_num, cursor) in self.cursors.iter_mut() {
+            let current = repo_workers.iter().find(|w| w.pr_num == pr_num);
+            let done = current.is_none_or(|w| w.phase.is_terminal());
+            let lines = if done {
+                cursor.finish()?
+            } else {
+                cursor.read_lines()?
+            };
+            for line in lines {
+                writeln!(out, "[#{pr_num}] {line}")?;
+            }
+            if done {
+                let summary = match current {
+                    Some(w) => match w.error {
+                        Some(ref e) => format!("{}: {e}", w.phase),
+                        None => w.phase.to_string(),
+                    },
+                    None => "gone".to_string(),
+                };
+                writeln!(out, "[#{pr_num}] ── {summary}")?;
+                ended.push(pr_num);
+            }
+        }
+        for pr_num in ended {
+            self.cursors.remove(&pr_num);
+        }
+        out.flush()?;
+        Ok(self.cursors.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::WorkerPhase;
+    use tempfile::TempDir;
+
+    fn worker(pr_num: u64, phase: WorkerPhase, started: &str) -> WorkerState {
+        WorkerState {
+            repo: "o/r".to_string(),
+            pr_num,
+            issues: vec![],
+            branch: String::new(),
+            container_id: String::new(),
+            phase,
+            heartbeat: started.to_string(),
+            started: started.to_string(),
+            ended: None,
+            exit_code: None,
+            error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
+            file_path: PathBuf::new(),
+        }
+    }
+
+    #[test]
+    fn most_recent_running_picks_latest_start() {
+        let workers = vec![
+            worker(1, WorkerPhase::Working, "2026-01-01T01:00:00Z"),
+            worker(2, WorkerPhase::Starting, "2026-01-01T03:00:00Z"),
+            worker(3, WorkerPhase::Finished, "2026-01-01T05:00:00Z"),
+            worker(4, WorkerPhase::Working, "2026-01-01T02:00:00Z"),
+        ];
+        assert_eq!(most_recent_running(&workers).unwrap().pr_num, 2);
+    }
+
+    #[test]
+    fn most_recent_running_none_when_all_terminal() {
+        let workers = vec![
+            worker(1, WorkerPhase::Finished, "2026-01-01T01:00:00Z"),
+            worker(2, WorkerPhase::Failed, "2026-01-01T02:00:00Z"),
+        ];
+        assert!(most_recent_running(&workers).is_none());
+    }
+
+    #[test]
+    fn follow_log_drains_output_written_before_done() {
+        let dir = TempDir::new().unwrap();
+        let log = log_path(dir.path(), "o/r", 7);
+        std::fs::create_dir_all(log.parent().unwrap()).unwrap();
+        std::fs::write(&log, "one\ntwo\n").unwrap();
+
+        // First check appends more output and reports done; it must still show up.
+        let mut out = Vec::new();
+        let log_for_check = log.clone();
+        follow_log(&log, &mut out, || {
+            let mut f = std::fs::OpenOptions::new()
+                .append(true)
+                .open(&log_for_check)
+                .unwrap();
+            f.write_all(b"three\n").unwrap();
+            true
+        })
+        .unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "one\ntwo\nthree\n");
+    }
+
+    #[test]
+    fn follow_log_prefixed_prefixes_every_line() {
+        let dir = TempDir::new().unwrap();
+        let log = dir.path().join("w.log");
+        std::fs::write(&log, "cloning\n").unwrap();
+
+        let mut out = Vec::new();
+        let mut checks = 0;
+        follow_log_prefixed(&log, "[#7] ", &mut out, || {
+            checks += 1;
+            if checks == 1 {
+                let mut f = std::fs::OpenOptions::new().append(true).open(&log).unwrap();
+                f.write_all(b"working\ndone").unwrap();
+            }
+            checks > 1
+        })
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[#7] cloning\n[#7] working\n[#7] done\n"
+        );
+    }
+
+    #[test]
+    fn log_cursor_holds_back_partial_lines() {
+        let dir = TempDir::new().unwrap();
+        let log = dir.path().join("w.log");
+        std::fs::write(&log, "a\nb").unwrap();
+
+        let mut cursor = LogCursor::new(log.clone());
+        assert_eq!(cursor.read_lines().unwrap(), vec!["a"]);
+
+        let mut f = std::fs::OpenOptions::new().append(true).open(&log).unwrap();
+        f.write_all(b"c\nd").unwrap();
+        assert_eq!(cursor.read_lines().unwrap(), vec!["bc"]);
+        assert_eq!(cursor.finish().unwrap(), vec!["d"]);
+    }
+
+    #[test]
+    fn log_cursor_keeps_multibyte_char_split_across_reads() {
+        let dir = TempDir::new().unwrap();
+        let log = dir.path().join("w.log");
+        // "é" is 0xC3 0xA9; write it in two halves.
+        std::fs::write(&log, [b'x', 0xC3]).unwrap();
+
+        let mut cursor = LogCursor::new(log.clone());
+        assert!(cursor.read_lines().unwrap().is_empty());
+
+        let mut f = std::fs::OpenOptions::new().append(true).open(&log).unwrap();
+        f.write_all(&[0xA9, b'\n']).unwrap();
+        assert_eq!(cursor.read_lines().unwrap(), vec!["xé"]);
+    }
+
+    fn append(path: &Path, text: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut f = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        f.write_all(text.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn repo_tail_interleaves_and_summarizes() {
+        let dir = TempDir::new().unwrap();
+        let log1 = log_path(dir.path(), "o/r", 1);
+        let log2 = log_path(dir.path(), "o/r", 2);
+        append(&log1, "old output\n");
+
+        let mut tail = RepoTail::new(dir.path(), "o/r");
+        let mut out = Vec::new();
+
+        // #1 is already running; #9 belongs to another repo.
+        let mut other = worker(9, WorkerPhase::Working, "2026-01-01T00:00:00Z");
+        other.repo = "x/y".to_string();
+        let mut workers = vec![
+            worker(1, WorkerPhase::Working, "2026-01-01T00:00:00Z"),
+            other,
+        ];
+        assert!(!tail.poll(&workers, &mut out).unwrap());
+
+        // #2 starts mid-tail and is followed from the top of its log.
+        append(&log1, "one\n");
+        append(&log2, "two\n");
+        workers.push(worker(2, WorkerPhase::Starting, "2026-01-01T00:01:00Z"));
+        assert!(!tail.poll(&workers, &mut out).unwrap());
+        assert_eq!(tail.following(), 2);
+
+        // Both finish; remaining output is drained before the summary.
+        append(&log1, "last words");
+        workers[0].phase = WorkerPhase::Finished;
+        workers[2].phase = WorkerPhase::Failed;
+        workers[2].error = Some("timed out".to_string());
+        assert!(tail.poll(&workers, &mut out).unwrap());
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[#1] ── following  (working)\n\
+             [#2] ── following  (starting)\n\
+             [#1] one\n\
+             [#2] two\n\
+             [#1] last words\n\
+             [#1] ── finished\n\
+             [#2] ── failed: timed out\n"
+        );
+    }
+
+    #[test]
+    fn repo_tail_done_when_nothing_running() {
+        let dir = TempDir::new().unwrap();
+        let mut tail = RepoTail::new(dir.path(), "o/r");
+        let workers = vec![worker(1, WorkerPhase::Finished, "2026-01-01T00:00:00Z")];
+        assert!(tail.poll(&workers, &mut Vec::new()).unwrap());
+    }
+
+    #[test]
+    fn last_lines_keeps_tail() {
+        assert_eq!(last_lines("a\nb\nc\n", 2), "b\nc\n");
+        assert_eq!(last_lines("", 2), "");
+    }
+}
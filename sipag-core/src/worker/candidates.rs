@@ -0,0 +1,216 @@
+//! Ready-issue candidate selection.
+//!
+//! Starts from open issues carrying any of the work labels, in label order
+//! (optionally narrowed to an assignee), and applies the configured filters
+//! on top. `sipag status` reports the result as the repo's ready issues.
+//! Issues whose body carries the `skip_marker` are left labeled but dropped,
+//! so humans still see them. `sipag dispatch` applies the same marker and
+//! `require_labels` to a PR's linked issues through [`dispatch_refusal`].
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::github;
+use crate::config::WorkerConfig;
+
+/// Open issues on `repo` that pass every configured candidate filter.
+pub fn ready_candidates(repo: &str, cfg: &WorkerConfig) -> Result<Vec<u64>> {
+    let mut issues: Vec<u64> = Vec::new();
+    for label in cfg.work_labels() {
+        for n in github::list_labeled_issues(repo, &label, cfg.assignee.as_deref())? {
+            if !issues.contains(&n) {
+                issues.push(n);
+            }
+        }
+    }
+    let filtered =
+        !cfg.require_labels.is_empty() || cfg.min_issue_age > 0 || !cfg.skip_marker.is_empty();
+    if filtered {
+        // One `gh issue view` per issue feeds every filter. An issue that
+        // can't be read is dropped, as it can't be shown to qualify.
+        let now = Utc::now();
+        issues.retain(|&n| match github::get_issue_details(repo, n) {
+            Ok(details) => passes_filters(n, &details, cfg, now),
+            Err(e) => {
+                log::warn!("#{n} skipped: {e:#}");
+                false
+            }
+        });
+    }
+    Ok(issues)
+}
+
+/// Whether issue `n` passes `require_labels`, `min_issue_age`, and
+/// `skip_marker`.
+fn passes_filters(
+    n: u64,
+    details: &github::IssueDetails,
+    cfg: &WorkerConfig,
+    now: DateTime<Utc>,
+) -> bool {
+    let required: Vec<&str> = cfg.require_labels.iter().map(String::as_str).collect();
+    if !github::has_all_labels(&details.labels, &required) {
+        return false;
+    }
+    if cfg.min_issue_age > 0 && !is_settled(details.updated_at, now, cfg.min_issue_age) {
+        return false;
+    }
+    if body_has_skip_marker(&details.body, &cfg.skip_marker) {
+        log::info!(
+            "#{n} skipped: body contains skip_marker {}",
+            cfg.skip_marker
+        );
+        return false;
+    }
+    true
+}
+
+/// Why `sipag dispatch` must not start a worker for a PR that links
+/// `issue`, with the given labels and body; `None` if the issue may be worked.
+pub fn dispatch_refusal(
+    issue: u64,
+    labels: &[String],
+    body: &str,
+    cfg: &WorkerConfig,
+) -> Option<String> {
+    let missing: Vec<&str> = cfg
+        .require_labels
+        .iter()
+        .filter(|l| !labels.contains(l))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Some(format!(
+            "issue #{issue} lacks require_labels {}",
+            missing.join(", ")
+        ));
+    }
+    body_has_skip_marker(body, &cfg.skip_marker)
+        .then(|| format!("issue #{issue} contains skip_marker {}", cfg.skip_marker))
+}
+
+/// Whether `body` contains `marker`, ignoring ASCII case. An empty marker
+/// never matches.
+pub fn body_has_skip_marker(body: &str, marker: &str) -> bool {
+    !marker.is_empty()
+        && body
+            .to_ascii_lowercase()
+            .contains(&marker.to_ascii_lowercase())
+}
+
+/// Whether an issue last updated at `updated` has been left alone for at
+/// least `min_age_secs`. An unknown update time is treated as not settled.
+fn is_settled(updated: Option<DateTime<Utc>>, now: DateTime<Utc>, min_age_secs: u64) -> bool {
+    match updated {
+        Some(t) => (now - t).num_seconds() >= min_age_secs as i64,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn is_settled_compares_age() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let five_min_ago = Utc.with_ymd_and_hms(2026, 1, 1, 11, 55, 0).unwrap();
+        assert!(is_settled(Some(five_min_ago), now, 300));
+        assert!(is_settled(Some(five_min_ago), now, 60));
+        assert!(!is_settled(Some(five_min_ago), now, 301));
+    }
+
+    #[test]
+    fn is_settled_unknown_or_future_update() {
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let later = Utc.with_ymd_and_hms(2026, 1, 1, 12, 1, 0).unwrap();
+        assert!(!is_settled(None, now, 60));
+        assert!(!is_settled(Some(later), now, 60));
+    }
+
+    #[test]
+    fn body_has_skip_marker_matches() {
+        let marker = "<!-- no-sipag -->";
+        assert!(body_has_skip_marker(
+            "Fix the parser.\n\n<!-- no-sipag -->\n",
+            marker
+        ));
+        assert!(!body_has_skip_marker("Fix the parser.", marker));
+        assert!(!body_has_skip_marker("<!-- no sipag -->", marker));
+    }
+
+    #[test]
+    fn body_has_skip_marker_ignores_case() {
+        assert!(body_has_skip_marker(
+            "<!-- NO-SIPAG -->",
+            "<!-- no-sipag -->"
+        ));
+        assert!(body_has_skip_marker("Needs [Manual] review", "[manual]"));
+        // An empty marker disables the check.
+        assert!(!body_has_skip_marker("anything", ""));
+    }
+
+    #[test]
+    fn dispatch_refusal_for_skip_marker() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = WorkerConfig::load(dir.path()).unwrap();
+        cfg.skip_marker = "<!-- no-sipag -->".to_string();
+        let why = dispatch_refusal(4, &[], "Manual only <!-- NO-SIPAG -->", &cfg).unwrap();
+        assert!(why.contains("#4") && why.contains("skip_marker"), "{why}");
+        assert_eq!(dispatch_refusal(4, &[], "Fix the parser.", &cfg), None);
+
+        cfg.skip_marker.clear();
+        assert_eq!(dispatch_refusal(4, &[], "<!-- no-sipag -->", &cfg), None);
+    }
+
+    #[test]
+    fn dispatch_refusal_for_missing_require_labels() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = WorkerConfig::load(dir.path()).unwrap();
+        cfg.require_labels = vec!["triaged".to_string(), "backend".to_string()];
+        let labels = |ls: &[&str]| ls.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+
+        let why = dispatch_refusal(9, &labels(&["ready", "backend"]), "", &cfg).unwrap();
+        assert_eq!(why, "issue #9 lacks require_labels triaged");
+        let why = dispatch_refusal(9, &[], "", &cfg).unwrap();
+        assert!(why.ends_with("triaged, backend"), "{why}");
+        assert_eq!(
+            dispatch_refusal(9, &labels(&["triaged", "backend"]), "", &cfg),
+            None
+        );
+    }
+
+    #[test]
+    fn passes_filters_applies_each_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = WorkerConfig::load(dir.path()).unwrap();
+        cfg.require_labels = vec!["triaged".to_string()];
+        cfg.min_issue_age = 300;
+        let now = Utc.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap();
+        let ok = github::IssueDetails {
+            labels: vec!["ready".to_string(), "triaged".to_string()],
+            body: "Fix the parser.".to_string(),
+            updated_at: Some(Utc.with_ymd_and_hms(2026, 1, 1, 11, 0, 0).unwrap()),
+        };
+        assert!(passes_filters(1, &ok, &cfg, now));
+
+        let unlabeled = github::IssueDetails {
+            labels: vec!["ready".to_string()],
+            ..ok.clone()
+        };
+        assert!(!passes_filters(1, &unlabeled, &cfg, now));
+
+        let fresh = github::IssueDetails {
+            updated_at: Some(now),
+            ..ok.clone()
+        };
+        assert!(!passes_filters(1, &fresh, &cfg, now));
+
+        let marked = github::IssueDetails {
+            body: format!("Fix it.\n{}", cfg.skip_marker),
+            ..ok.clone()
+        };
+        assert!(!passes_filters(1, &marked, &cfg, now));
+    }
+}
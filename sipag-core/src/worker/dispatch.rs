@@ -6,6 +6,7 @@ use std::path::Path;
 use std::process::{Command, Stdio};
 
 use crate::config::{Credentials, WorkerConfig};
+use crate::prompt;
 use crate::state::{self, WorkerPhase, WorkerState};
 
 /// Launch a Docker container to implement a PR.
@@ -13,6 +14,9 @@ use crate::state::{self, WorkerPhase, WorkerState};
 /// The worker clones the repo, checks out the PR branch, reads the PR
 /// description as its assignment, and runs Claude Code.
 ///
+/// `commit_prefix` (see [`resolve_commit_prefix`]) is passed to the worker as
+/// the conventional-commit prefix to use.
+///
 /// Returns the Docker container ID on success.
 pub fn dispatch_worker(
     repo: &str,
@@ -21,6 +25,7 @@ pub fn dispatch_worker(
     issues: &[u64],
     cfg: &WorkerConfig,
     creds: &Credentials,
+    commit_prefix: Option<&str>,
 ) -> Result<String> {
     let repo_slug = repo.replace('/', "--");
     let container_name = format!("sipag-{repo_slug}-pr-{pr_num}");
@@ -28,6 +33,9 @@ pub fn dispatch_worker(
     fs::create_dir_all(&log_dir)?;
     let events_dir = cfg.sipag_dir.join("events");
     fs::create_dir_all(&events_dir)?;
+    if cfg.propose_only {
+        fs::create_dir_all(cfg.sipag_dir.join("proposals"))?;
+    }
     let log_path = log_dir.join(format!("{repo_slug}--pr-{pr_num}.log"));
 
     // Clean up any stale container from a previous attempt.
@@ -40,6 +48,8 @@ pub fn dispatch_worker(
     // Write initial state file.
     let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
     let state_path = state::state_file_path(&cfg.sipag_dir, repo, pr_num);
+    let exit_path = state::exit_file_path(&state_path);
+    let _ = fs::remove_file(&exit_path);
     // Write container_id in the initial state (it's deterministic and known before
     // spawn). This eliminates a race where the container could overwrite the state
     // file between the host's initial write and the second write that sets container_id.
@@ -55,6 +65,8 @@ pub fn dispatch_worker(
         ended: None,
         exit_code: None,
         error: None,
+        peak_memory_bytes: None,
+        cpu_secs: None,
         file_path: state_path.clone(),
     };
     state::write_state(&initial_state)?;
@@ -64,70 +76,43 @@ pub fn dispatch_worker(
         .with_context(|| format!("Failed to create log file: {}", log_path.display()))?;
     let log_err = log_out.try_clone()?;
 
-    let workers_dir = cfg.sipag_dir.join("workers");
-    let state_filename = format!("{repo_slug}--pr-{pr_num}.json");
+    let get_env = |k: &str| std::env::var(k).ok();
+    let run_args = docker_run_args(
+        &container_name,
+        repo,
+        pr_num,
+        branch,
+        cfg,
+        commit_prefix,
+        &get_env,
+    );
+    log::debug!("docker {}", run_args.join(" "));
 
-    let timeout_bin = crate::docker::resolve_timeout_command();
-    let mut cmd;
-    if let Some(ref bin) = timeout_bin {
-        cmd = Command::new(bin);
-        cmd.arg(cfg.timeout.to_string()).arg("docker").arg("run");
-    } else {
-        cmd = Command::new("docker");
-        cmd.arg("run");
+    // `docker run` goes through a small shell wrapper that records the exit
+    // code and, on timeout, kills the container itself — `timeout` only kills
+    // the `docker run` client, which can leave the container running.
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(WRAPPER_SCRIPT)
+        .arg("sipag-worker-wrapper");
+    match crate::docker::resolve_timeout_command() {
+        Some(bin) => {
+            cmd.arg(bin).arg(cfg.timeout.to_string());
+        }
+        // Without coreutils the wrapper bounds the container itself.
+        None => {
+            cmd.env("SIPAG_WATCHDOG_SECS", cfg.timeout.to_string());
+        }
     }
-
-    cmd.arg("--rm")
-        .arg("--name")
-        .arg(&container_name)
-        // Labels for debugging
-        .arg("--label")
-        .arg(format!("org.sipag.repo={repo}"))
-        .arg("--label")
-        .arg(format!("org.sipag.pr={pr_num}"))
-        // Mount state directory for heartbeats
-        .arg("-v")
-        .arg(format!("{}:/sipag-state", workers_dir.display()))
-        // Mount lessons directory (read-only) for cross-worker learning
-        .arg("-v")
-        .arg(format!(
-            "{}:/sipag-lessons:ro",
-            cfg.sipag_dir.join("lessons").display()
-        ))
-        // Mount events directory for lifecycle event emission
-        .arg("-v")
-        .arg(format!(
-            "{}:/sipag-events",
-            cfg.sipag_dir.join("events").display()
-        ))
-        .arg("-e")
-        .arg("EVENTS_DIR=/sipag-events")
-        .arg("-e")
-        .arg(format!(
-            "SIPAG_HEARTBEAT_INTERVAL={}",
-            cfg.heartbeat_interval
-        ))
-        .arg("-e")
-        .arg(format!("STATE_FILE=/sipag-state/{state_filename}"))
-        // Environment
-        .arg("-e")
-        .arg(format!("REPO={repo}"))
-        .arg("-e")
-        .arg(format!("PR_NUM={pr_num}"))
-        .arg("-e")
-        .arg(format!("BRANCH={branch}"))
-        .arg("-e")
-        .arg("CLAUDE_CODE_OAUTH_TOKEN")
-        .arg("-e")
-        .arg("ANTHROPIC_API_KEY")
-        .arg("-e")
-        .arg("GH_TOKEN");
-
-    // Image and entrypoint
-    cmd.arg(&cfg.image)
-        .arg("/usr/local/bin/sipag-worker")
+    cmd.arg("docker")
+        .args(&run_args)
+        .env("SIPAG_EXIT_FILE", &exit_path)
+        .env("SIPAG_CONTAINER", &container_name)
         .stdout(Stdio::from(log_out))
         .stderr(Stdio::from(log_err));
+    // Own process group, so Ctrl-C in a terminal following the worker
+    // (`sipag dispatch --verbose`) doesn't reach `docker run` and stop it.
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
 
     // Set credentials.
     if let Some(ref token) = creds.oauth_token {
@@ -144,11 +129,448 @@ pub fn dispatch_worker(
         let _ = child.wait();
     });
 
-    println!("[PR #{pr_num}] Worker dispatched: {container_name}");
+    log::info!("[PR #{pr_num}] Worker dispatched: {container_name}");
 
     Ok(container_name)
 }
 
+/// Exit status `timeout(1)` uses when the command ran out of time.
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// Runs `"$@"` (optionally `timeout N docker run …`), writes the exit code to
+/// `$SIPAG_EXIT_FILE`, and `docker kill`s `$SIPAG_CONTAINER` on timeout.
+///
+/// When neither `timeout` nor `gtimeout` exists, `$SIPAG_WATCHDOG_SECS` is set
+/// instead: a background watchdog kills the container after that many
+/// seconds and the run is recorded as a timeout (124). The watchdog lives in
+/// the wrapper rather than in sipag because `sipag dispatch` exits as soon as
+/// the worker is spawned.
+const WRAPPER_SCRIPT: &str = r#"watchdog=
+if [ -n "$SIPAG_WATCHDOG_SECS" ]; then
+    (
+        sleep "$SIPAG_WATCHDOG_SECS"
+        : > "$SIPAG_EXIT_FILE.timedout"
+        docker kill "$SIPAG_CONTAINER" >/dev/null 2>&1
+    ) &
+    watchdog=$!
+fi
+"$@"
+rc=$?
+if [ -n "$watchdog" ]; then
+    kill "$watchdog" 2>/dev/null
+    if [ -e "$SIPAG_EXIT_FILE.timedout" ]; then
+        rm -f "$SIPAG_EXIT_FILE.timedout"
+        rc=124
+    fi
+fi
+printf '%s\n' "$rc" > "$SIPAG_EXIT_FILE"
+if [ "$rc" -eq 124 ]; then
+    echo "sipag: worker timed out; killing $SIPAG_CONTAINER"
+    docker kill "$SIPAG_CONTAINER" >/dev/null 2>&1
+fi
+exit "$rc"
+"#;
+
+/// Why a worker failed, derived from its `docker run` exit code and log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FailureReason {
+    /// The timeout wrapper fired (exit 124).
+    TimedOut,
+    /// Docker itself could not start the container (exit 125).
+    DockerRunFailed,
+    /// The container was SIGKILLed (exit 137) or the log reports an OOM kill.
+    OutOfMemory,
+    /// Cloning or checking out the repo failed; carries the log detail.
+    CloneFailed(String),
+    /// The container exited non-zero for another reason.
+    ExitedNonZero { code: i32, detail: Option<String> },
+    /// The container exited cleanly (or with an unknown code) without
+    /// writing a terminal state.
+    NoTerminalState { detail: Option<String> },
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TimedOut => write!(f, "timed out"),
+            Self::DockerRunFailed => write!(f, "docker run failed (exit 125)"),
+            Self::OutOfMemory => write!(f, "container killed (out of memory or SIGKILL)"),
+            Self::CloneFailed(detail) => write!(f, "clone failed — {detail}"),
+            Self::ExitedNonZero { code, detail } => {
+                write!(f, "container exited non-zero ({code})")?;
+                match detail {
+                    Some(d) => write!(f, " — {d}"),
+                    None => Ok(()),
+                }
+            }
+            Self::NoTerminalState { detail } => {
+                write!(f, "container exited without updating state")?;
+                match detail {
+                    Some(d) => write!(f, " — {d}"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Classify a worker failure from its `docker run` exit code (if known) and
+/// the patterns [`extract_failure_reason`] finds in its log.
+///
+/// Exit codes that identify the failure on their own (timeout, docker run
+/// errors, SIGKILL) win over the log.
+pub fn classify_failure(exit_code: Option<i32>, log_path: &Path) -> FailureReason {
+    match exit_code {
+        Some(TIMEOUT_EXIT_CODE) => return FailureReason::TimedOut,
+        Some(125) => return FailureReason::DockerRunFailed,
+        Some(137) => return FailureReason::OutOfMemory,
+        _ => {}
+    }
+    let detail = extract_failure_reason(log_path);
+    match detail {
+        Some(ref d) if d.starts_with("git clone failed") => {
+            FailureReason::CloneFailed(d.trim_start_matches("git clone failed: ").to_string())
+        }
+        Some(ref d) if d.contains("OOM") => FailureReason::OutOfMemory,
+        _ => match exit_code {
+            Some(code) if code != 0 => FailureReason::ExitedNonZero { code, detail },
+            _ => FailureReason::NoTerminalState { detail },
+        },
+    }
+}
+
+/// Proxy variables forwarded into the container when set on the host.
+/// Both cases are checked: curl and git only honour the lowercase forms.
+const PROXY_ENV_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "NO_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "no_proxy",
+];
+
+/// Build the `docker run ...` argument list for a worker container.
+///
+/// Everything after the `docker` binary itself, ending with the image and the
+/// worker entrypoint. Credentials and proxy settings are forwarded by name
+/// only (`-e GH_TOKEN`); their values come from the spawned process's
+/// environment, never argv. `get_env` decides which proxy vars are present.
+fn docker_run_args(
+    container_name: &str,
+    repo: &str,
+    pr_num: u64,
+    branch: &str,
+    cfg: &WorkerConfig,
+    commit_prefix: Option<&str>,
+    get_env: &dyn Fn(&str) -> Option<String>,
+) -> Vec<String> {
+    let repo_slug = repo.replace('/', "--");
+    let workers_dir = cfg.sipag_dir.join("workers");
+    let state_filename = format!("{repo_slug}--pr-{pr_num}.json");
+
+    let mut args: Vec<String> = vec![
+        "run".to_string(),
+        "--rm".to_string(),
+        "--name".to_string(),
+        container_name.to_string(),
+    ];
+    if let Some(ref platform) = cfg.container_platform {
+        args.push("--platform".to_string());
+        args.push(platform.clone());
+    }
+    if let Some(ref cache) = cfg.cache_volume {
+        args.extend(["-v".to_string(), cache.clone()]);
+    }
+    for opt in &cfg.security_opt {
+        args.push("--security-opt".to_string());
+        args.push(opt.clone());
+    }
+    if let Some(ref network) = cfg.network_mode {
+        args.push("--network".to_string());
+        args.push(network.clone());
+    }
+    for host in &cfg.extra_hosts {
+        args.push("--add-host".to_string());
+        args.push(host.clone());
+    }
+    args.extend([
+        // Labels for debugging
+        "--label".to_string(),
+        format!("org.sipag.repo={repo}"),
+        "--label".to_string(),
+        format!("org.sipag.pr={pr_num}"),
+        // Mount state directory for heartbeats
+        "-v".to_string(),
+        format!("{}:/sipag-state", workers_dir.display()),
+        // Mount lessons directory (read-only) for cross-worker learning
+        "-v".to_string(),
+        format!(
+            "{}:/sipag-lessons:ro",
+            cfg.sipag_dir.join("lessons").display()
+        ),
+        // Mount events directory for lifecycle event emission
+        "-v".to_string(),
+        format!("{}:/sipag-events", cfg.sipag_dir.join("events").display()),
+        "-e".to_string(),
+        "EVENTS_DIR=/sipag-events".to_string(),
+    ]);
+    // Mount prompt overrides (read-only) only when the operator created the
+    // directory, so Docker doesn't create an empty root-owned one.
+    let prompts_dir = cfg.sipag_dir.join("prompts");
+    if prompts_dir.is_dir() {
+        args.extend([
+            "-v".to_string(),
+            format!("{}:/sipag-prompts:ro", prompts_dir.display()),
+        ]);
+    }
+    args.extend([
+        "-e".to_string(),
+        format!("SIPAG_HEARTBEAT_INTERVAL={}", cfg.heartbeat_interval),
+        "-e".to_string(),
+        format!("STATE_FILE=/sipag-state/{state_filename}"),
+        "-e".to_string(),
+        format!("CONTEXT_ISSUE_LIMIT={}", cfg.context_issue_limit),
+        "-e".to_string(),
+        format!("MAX_PR_BODY_CHARS={}", cfg.max_pr_body_chars),
+        // Environment
+        "-e".to_string(),
+        format!("REPO={repo}"),
+        "-e".to_string(),
+        format!("PR_NUM={pr_num}"),
+        "-e".to_string(),
+        format!("BRANCH={branch}"),
+    ]);
+    if cfg.draft_prs {
+        args.push("-e".to_string());
+        args.push("DRAFT=1".to_string());
+    }
+    if cfg.rerequest_review {
+        args.push("-e".to_string());
+        args.push("REREQUEST_REVIEW=1".to_string());
+    }
+    if cfg.audit_comments {
+        args.push("-e".to_string());
+        args.push("AUDIT_COMMENTS=1".to_string());
+    }
+    if cfg.propose_only {
+        args.extend([
+            "-v".to_string(),
+            format!(
+                "{}:/sipag-proposals",
+                cfg.sipag_dir.join("proposals").display()
+            ),
+            "-e".to_string(),
+            "PROPOSE_ONLY=1".to_string(),
+        ]);
+    }
+    if cfg.clone_submodules {
+        args.push("-e".to_string());
+        args.push("CLONE_SUBMODULES=1".to_string());
+    }
+    if cfg.clone_lfs {
+        args.push("-e".to_string());
+        args.push("CLONE_LFS=1".to_string());
+    }
+    if cfg.clone_depth > 0 {
+        args.push("-e".to_string());
+        args.push(format!("CLONE_DEPTH={}", cfg.clone_depth));
+    }
+    if cfg.per_issue_timeout > 0 {
+        args.push("-e".to_string());
+        args.push(format!("PER_ISSUE_TIMEOUT={}", cfg.per_issue_timeout));
+    }
+    if let Some(prefix) = commit_prefix {
+        args.push("-e".to_string());
+        args.push(format!("COMMIT_PREFIX={prefix}"));
+    }
+    // Commit identity: git reads these env vars directly, overriding the
+    // worker's default `sipag` user.name/user.email.
+    if let Some(ref name) = cfg.git_author_name {
+        args.extend([
+            "-e".to_string(),
+            format!("GIT_AUTHOR_NAME={name}"),
+            "-e".to_string(),
+            format!("GIT_COMMITTER_NAME={name}"),
+        ]);
+    }
+    if let Some(ref email) = cfg.git_author_email {
+        args.extend([
+            "-e".to_string(),
+            format!("GIT_AUTHOR_EMAIL={email}"),
+            "-e".to_string(),
+            format!("GIT_COMMITTER_EMAIL={email}"),
+        ]);
+    }
+    // The host already ran prompt_filter_command; hand over its output.
+    if cfg.prompt_filter_command.is_some() {
+        args.extend([
+            "-v".to_string(),
+            format!(
+                "{}:/sipag-filtered-prompts:ro",
+                cfg.sipag_dir.join(FILTERED_PROMPTS_DIR).display()
+            ),
+            "-e".to_string(),
+            format!("PROMPT_FILE=/sipag-filtered-prompts/{repo_slug}--pr-{pr_num}.md"),
+        ]);
+    }
+    if let Some(ref url) = cfg.anthropic_base_url {
+        args.push("-e".to_string());
+        args.push(format!("ANTHROPIC_BASE_URL={url}"));
+    }
+    if get_env(crate::logging::LOG_ENV).is_some_and(|v| !v.is_empty()) {
+        args.push("-e".to_string());
+        args.push(crate::logging::LOG_ENV.to_string());
+    }
+    for var in PROXY_ENV_VARS {
+        if get_env(var).is_some_and(|v| !v.is_empty()) {
+            args.push("-e".to_string());
+            args.push(var.to_string());
+        }
+    }
+    args.extend([
+        "-e".to_string(),
+        "CLAUDE_CODE_OAUTH_TOKEN".to_string(),
+        "-e".to_string(),
+        "ANTHROPIC_API_KEY".to_string(),
+        "-e".to_string(),
+        "GH_TOKEN".to_string(),
+    ]);
+
+    // Image and entrypoint
+    args.push(cfg.image.clone());
+    args.push("/usr/local/bin/sipag-worker".to_string());
+    args
+}
+
+/// Directory under the sipag dir holding host-filtered worker prompts.
+const FILTERED_PROMPTS_DIR: &str = "filtered-prompts";
+
+/// Host-side inputs for assembling a worker prompt before dispatch.
+pub struct PromptRequest<'a> {
+    pub repo: &'a str,
+    pub pr_num: u64,
+    pub branch: &'a str,
+    pub pr_body: &'a str,
+    pub issues: &'a [u64],
+    pub commit_prefix: Option<&'a str>,
+}
+
+/// Run `prompt_filter_command` on the host, before any container starts.
+///
+/// Assembles the same prompt the worker would, pipes it through the filter,
+/// and writes the result where [`docker_run_args`] mounts it for the worker
+/// to use verbatim. A failing filter is an error carrying its stderr, so
+/// dispatch aborts. Does nothing when no filter is configured.
+pub fn prepare_filtered_prompt(req: &PromptRequest, cfg: &WorkerConfig) -> Result<()> {
+    let Some(ref filter) = cfg.prompt_filter_command else {
+        return Ok(());
+    };
+    if req.pr_body.trim().is_empty() {
+        anyhow::bail!(
+            "PR #{} has an empty body — cannot proceed without an assignment",
+            req.pr_num
+        );
+    }
+    let repo_slug = req.repo.replace('/', "--");
+    let template = prompt::load_prompt_template(
+        &cfg.sipag_dir.join("prompts"),
+        prompt::WORKER_PROMPT_FILE,
+        prompt::WORKER_PROMPT,
+    );
+    let worker_prompt = prompt::render_worker_prompt(
+        &template,
+        &prompt::PromptVars {
+            repo: req.repo,
+            pr_num: req.pr_num,
+            branch: req.branch,
+            context_issue_limit: cfg.context_issue_limit,
+        },
+    );
+    let lessons = fs::read_to_string(
+        cfg.sipag_dir
+            .join("lessons")
+            .join(format!("{repo_slug}.md")),
+    )
+    .unwrap_or_default();
+    let assembled = prompt::assemble_worker_prompt(
+        &worker_prompt,
+        &prompt::PromptParts {
+            pr_body: &prompt::truncate_body(req.pr_body.trim(), cfg.max_pr_body_chars),
+            lessons: &prompt::lessons_section(&lessons),
+            commit_prefix: req.commit_prefix,
+            per_issue_timeout: cfg.per_issue_timeout,
+            issue_count: req.issues.len(),
+            propose_only: cfg.propose_only,
+        },
+    );
+    let filtered = prompt::apply_prompt_filter(filter, &assembled)
+        .with_context(|| format!("prompt_filter_command failed for PR #{}", req.pr_num))?;
+
+    let dir = cfg.sipag_dir.join(FILTERED_PROMPTS_DIR);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{repo_slug}--pr-{}.md", req.pr_num));
+    fs::write(&path, filtered)
+        .with_context(|| format!("Failed to write filtered prompt: {}", path.display()))
+}
+
+/// Pick the commit prefix for a worker from its linked issues' labels.
+///
+/// `issue_labels` holds each linked issue's labels, anchor issue first. The
+/// anchor's first mapped label wins; otherwise the prefix mapped from the
+/// most issues, ties going to the earlier `prefixes` entry.
+pub fn resolve_commit_prefix(
+    issue_labels: &[Vec<String>],
+    prefixes: &[(String, String)],
+) -> Option<String> {
+    let prefix_for = |labels: &[String]| {
+        prefixes
+            .iter()
+            .find(|(label, _)| labels.contains(label))
+            .map(|(_, prefix)| prefix.clone())
+    };
+    if let Some(prefix) = issue_labels.first().and_then(|l| prefix_for(l)) {
+        return Some(prefix);
+    }
+    let mut best: Option<(&str, usize)> = None;
+    for (_, prefix) in prefixes {
+        let count = issue_labels
+            .iter()
+            .filter(|l| prefix_for(l).as_deref() == Some(prefix.as_str()))
+            .count();
+        if count > 0 && best.is_none_or(|(_, c)| count > c) {
+            best = Some((prefix, count));
+        }
+    }
+    best.map(|(prefix, _)| prefix.to_string())
+}
+
+/// Whether a worker for `repo` covering `pr_num` or any of `issues` started
+/// less than `cooldown_secs` before `now`, in any phase.
+///
+/// A worker that crashes before relabeling its issues could otherwise be
+/// followed immediately by a second dispatch for the same work. A zero
+/// cooldown disables the check; unparsable start times never match.
+pub fn recently_dispatched(
+    states: &[WorkerState],
+    repo: &str,
+    pr_num: u64,
+    issues: &[u64],
+    cooldown_secs: u64,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    if cooldown_secs == 0 {
+        return false;
+    }
+    states.iter().any(|w| {
+        w.repo == repo
+            && (w.pr_num == pr_num || w.issues.iter().any(|i| issues.contains(i)))
+            && chrono::DateTime::parse_from_rfc3339(&w.started).is_ok_and(|started| {
+                now.signed_duration_since(started) < chrono::Duration::seconds(cooldown_secs as i64)
+            })
+    })
+}
+
 /// Extract a failure reason from a log file.
 ///
 /// Checks for known patterns (git errors, Claude failures, OOM, etc.) and
@@ -218,6 +640,137 @@ pub fn extract_failure_reason(log_path: &Path) -> Option<String> {
 mod tests {
     use super::*;
 
+    /// Run [`WRAPPER_SCRIPT`] around `sh -c script` with a fake `docker` on
+    /// `PATH` that records `kill` calls by creating `dir/killed`.
+    fn run_wrapper(dir: &Path, watchdog_secs: Option<u64>, script: &str) -> i32 {
+        use std::os::unix::fs::PermissionsExt;
+        let bin = dir.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        let docker = bin.join("docker");
+        fs::write(&docker, "#!/bin/sh\n: > \"$FAKE_KILLED\"\n").unwrap();
+        fs::set_permissions(&docker, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c")
+            .arg(WRAPPER_SCRIPT)
+            .arg("sipag-worker-wrapper")
+            .args(["sh", "-c", script])
+            .env(
+                "PATH",
+                format!("{}:{}", bin.display(), std::env::var("PATH").unwrap()),
+            )
+            .env("FAKE_KILLED", dir.join("killed"))
+            .env("SIPAG_EXIT_FILE", dir.join("exit"))
+            .env("SIPAG_CONTAINER", "sipag-test")
+            .stdout(Stdio::null());
+        if let Some(secs) = watchdog_secs {
+            cmd.env("SIPAG_WATCHDOG_SECS", secs.to_string());
+        }
+        cmd.status().unwrap().code().unwrap()
+    }
+
+    #[test]
+    fn wrapper_watchdog_kills_container_and_records_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        // Stands in for `docker run`: exits as SIGKILLed once killed.
+        let rc = run_wrapper(
+            dir.path(),
+            Some(1),
+            r#"while [ ! -e "$FAKE_KILLED" ]; do sleep 0.1; done; exit 137"#,
+        );
+        assert_eq!(rc, TIMEOUT_EXIT_CODE);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("exit")).unwrap(),
+            "124\n"
+        );
+        assert!(!dir.path().join("exit.timedout").exists());
+    }
+
+    #[test]
+    fn wrapper_watchdog_is_cancelled_on_completion() {
+        let dir = tempfile::tempdir().unwrap();
+        let rc = run_wrapper(dir.path(), Some(1), "exit 3");
+        assert_eq!(rc, 3);
+        std::thread::sleep(std::time::Duration::from_millis(1500));
+        assert!(!dir.path().join("killed").exists());
+        assert_eq!(fs::read_to_string(dir.path().join("exit")).unwrap(), "3\n");
+
+        // No watchdog without SIPAG_WATCHDOG_SECS.
+        assert_eq!(run_wrapper(dir.path(), None, "exit 0"), 0);
+    }
+
+    #[test]
+    fn classify_failure_timeout_wins_over_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("test.log");
+        fs::write(&log, "fatal: repository not found\n").unwrap();
+        let reason = classify_failure(Some(TIMEOUT_EXIT_CODE), &log);
+        assert_eq!(reason, FailureReason::TimedOut);
+        assert_eq!(reason.to_string(), "timed out");
+    }
+
+    #[test]
+    fn classify_failure_generic_non_zero() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("test.log");
+        fs::write(&log, "Working...\nclaude exited with code 2\n").unwrap();
+        assert_eq!(
+            classify_failure(Some(2), &log),
+            FailureReason::ExitedNonZero {
+                code: 2,
+                detail: Some("claude exited with code 2".to_string()),
+            }
+        );
+        // Missing log: still categorized by exit code alone.
+        assert_eq!(
+            classify_failure(Some(1), &dir.path().join("missing.log")),
+            FailureReason::ExitedNonZero {
+                code: 1,
+                detail: None
+            }
+        );
+    }
+
+    #[test]
+    fn classify_failure_clone_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("test.log");
+        for (line, detail) in [
+            ("fatal: repository not found", "repository not found"),
+            (
+                "fatal: unable to access: Could not resolve host: github.com",
+                "could not resolve host",
+            ),
+            (
+                "fatal: Authentication failed for 'https://github.com/o/r'",
+                "authentication failed",
+            ),
+        ] {
+            fs::write(&log, format!("Cloning into '/work'...\n{line}\n")).unwrap();
+            assert_eq!(
+                classify_failure(Some(128), &log),
+                FailureReason::CloneFailed(detail.to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn classify_failure_exit_code_only() {
+        let missing = Path::new("/nonexistent/sipag/test.log");
+        assert_eq!(
+            classify_failure(Some(125), missing),
+            FailureReason::DockerRunFailed
+        );
+        assert_eq!(
+            classify_failure(Some(137), missing),
+            FailureReason::OutOfMemory
+        );
+        assert_eq!(
+            classify_failure(Some(0), missing),
+            FailureReason::NoTerminalState { detail: None }
+        );
+    }
+
     #[test]
     fn failure_reason_repo_not_found() {
         let dir = tempfile::tempdir().unwrap();
@@ -257,6 +810,428 @@ mod tests {
         assert!(reason.contains("authentication failed"));
     }
 
+    fn test_config(dir: &Path) -> WorkerConfig {
+        WorkerConfig::load(dir).unwrap()
+    }
+
+    fn no_env(_: &str) -> Option<String> {
+        None
+    }
+
+    #[test]
+    fn run_args_forward_proxy_vars_when_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let cfg = test_config(dir.path());
+        let env = |k: &str| match k {
+            "HTTPS_PROXY" => Some("http://proxy:3128".to_string()),
+            "NO_PROXY" => Some("localhost".to_string()),
+            "HTTP_PROXY" => Some(String::new()),
+            _ => None,
+        };
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &env);
+        assert!(args.windows(2).any(|w| w == ["-e", "HTTPS_PROXY"]));
+        assert!(args.windows(2).any(|w| w == ["-e", "NO_PROXY"]));
+        // Empty and unset vars are not forwarded.
+        assert!(!args.contains(&"HTTP_PROXY".to_string()));
+        assert!(!args.contains(&"https_proxy".to_string()));
+        // Values never appear in argv.
+        assert!(!args.iter().any(|a| a.contains("proxy:3128")));
+    }
+
+    #[test]
+    fn run_args_omit_proxy_vars_when_absent() {
+        let dir = tempfile::tempdir().unwrap();
+        let cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        for var in PROXY_ENV_VARS {
+            assert!(!args.contains(&var.to_string()));
+        }
+    }
+
+    #[test]
+    fn run_args_end_with_image_and_entrypoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let cfg = test_config(dir.path());
+        let args = docker_run_args("sipag-o--r-pr-1", "o/r", 1, "b", &cfg, None, &no_env);
+        assert_eq!(args[0], "run");
+        assert_eq!(args[args.len() - 2], cfg.image);
+        assert_eq!(args[args.len() - 1], "/usr/local/bin/sipag-worker");
+        assert!(args.contains(&"REPO=o/r".to_string()));
+        assert!(args.contains(&"STATE_FILE=/sipag-state/o--r--pr-1.json".to_string()));
+    }
+
+    #[test]
+    fn run_args_include_platform_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.container_platform = None;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.contains(&"--platform".to_string()));
+
+        cfg.container_platform = Some("linux/amd64".to_string());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        let pos = args.iter().position(|a| a == "--platform").unwrap();
+        assert_eq!(args[pos + 1], "linux/amd64");
+        // Must precede the image, or docker treats it as a container argument.
+        let image_pos = args.iter().position(|a| *a == cfg.image).unwrap();
+        assert!(pos < image_pos);
+    }
+
+    #[test]
+    fn run_args_mount_cache_volume_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.contains("/home/sipag/.cache")));
+
+        cfg.cache_volume = Some("sipag-cache:/home/sipag/.cache".to_string());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-v", "sipag-cache:/home/sipag/.cache"]));
+    }
+
+    #[test]
+    fn run_args_mount_prompts_dir_only_when_present() {
+        let dir = tempfile::tempdir().unwrap();
+        let cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.ends_with(":/sipag-prompts:ro")));
+
+        std::fs::create_dir_all(dir.path().join("prompts")).unwrap();
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        let mount = format!("{}:/sipag-prompts:ro", dir.path().join("prompts").display());
+        assert!(args.windows(2).any(|w| w[0] == "-v" && w[1] == mount));
+    }
+
+    fn labels(ls: &[&str]) -> Vec<String> {
+        ls.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn prefixes() -> Vec<(String, String)> {
+        vec![
+            ("bug".to_string(), "fix".to_string()),
+            ("enhancement".to_string(), "feat".to_string()),
+            ("docs".to_string(), "docs".to_string()),
+        ]
+    }
+
+    #[test]
+    fn commit_prefix_from_single_issue() {
+        let p = prefixes();
+        assert_eq!(
+            resolve_commit_prefix(&[labels(&["ready", "bug"])], &p).as_deref(),
+            Some("fix")
+        );
+        assert_eq!(resolve_commit_prefix(&[labels(&["ready"])], &p), None);
+        assert_eq!(resolve_commit_prefix(&[], &p), None);
+        assert_eq!(resolve_commit_prefix(&[labels(&["bug"])], &[]), None);
+    }
+
+    #[test]
+    fn commit_prefix_for_grouped_issues() {
+        let p = prefixes();
+        // Anchor's label wins even when outnumbered.
+        let issues = [labels(&["docs"]), labels(&["bug"]), labels(&["bug"])];
+        assert_eq!(resolve_commit_prefix(&issues, &p).as_deref(), Some("docs"));
+
+        // Unmapped anchor: dominant label decides.
+        let issues = [
+            labels(&["ready"]),
+            labels(&["enhancement"]),
+            labels(&["bug"]),
+            labels(&["enhancement"]),
+        ];
+        assert_eq!(resolve_commit_prefix(&issues, &p).as_deref(), Some("feat"));
+
+        // Tie: earlier config entry wins.
+        let issues = [labels(&[]), labels(&["enhancement"]), labels(&["bug"])];
+        assert_eq!(resolve_commit_prefix(&issues, &p).as_deref(), Some("fix"));
+    }
+
+    #[test]
+    fn run_args_set_commit_prefix_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.starts_with("COMMIT_PREFIX=")));
+
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, Some("fix"), &no_env);
+        assert!(args.windows(2).any(|w| w == ["-e", "COMMIT_PREFIX=fix"]));
+    }
+
+    #[test]
+    fn run_args_include_security_opts() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.contains(&"--security-opt".to_string()));
+
+        cfg.security_opt = vec![
+            "no-new-privileges".to_string(),
+            "seccomp=/etc/sipag/seccomp.json".to_string(),
+        ];
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        let opts: Vec<&str> = args
+            .windows(2)
+            .filter(|w| w[0] == "--security-opt")
+            .map(|w| w[1].as_str())
+            .collect();
+        assert_eq!(
+            opts,
+            ["no-new-privileges", "seccomp=/etc/sipag/seccomp.json"]
+        );
+        let image_pos = args.iter().position(|a| *a == cfg.image).unwrap();
+        let last_opt = args.iter().rposition(|a| a == "--security-opt").unwrap();
+        assert!(last_opt < image_pos);
+    }
+
+    #[test]
+    fn run_args_include_network_isolation() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.contains(&"--network".to_string()));
+        assert!(!args.contains(&"--add-host".to_string()));
+
+        cfg.network_mode = Some("sipag-egress".to_string());
+        cfg.extra_hosts = vec![
+            "github.com:140.82.112.3".to_string(),
+            "api.anthropic.com:160.79.104.10".to_string(),
+        ];
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w == ["--network", "sipag-egress"]));
+        let hosts: Vec<&str> = args
+            .windows(2)
+            .filter(|w| w[0] == "--add-host")
+            .map(|w| w[1].as_str())
+            .collect();
+        assert_eq!(
+            hosts,
+            ["github.com:140.82.112.3", "api.anthropic.com:160.79.104.10"]
+        );
+        let image_pos = args.iter().position(|a| *a == cfg.image).unwrap();
+        let last_host = args.iter().rposition(|a| a == "--add-host").unwrap();
+        assert!(last_host < image_pos);
+    }
+
+    #[test]
+    fn run_args_forward_pr_body_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.max_pr_body_chars = 1234;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-e", "MAX_PR_BODY_CHARS=1234"]));
+    }
+
+    #[test]
+    fn run_args_forward_per_issue_timeout_when_set() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.per_issue_timeout = 0;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.starts_with("PER_ISSUE_TIMEOUT=")));
+
+        cfg.per_issue_timeout = 900;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-e", "PER_ISSUE_TIMEOUT=900"]));
+    }
+
+    #[test]
+    fn run_args_forward_clone_depth_when_shallow() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.clone_depth = 0;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.starts_with("CLONE_DEPTH=")));
+
+        cfg.clone_depth = 25;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w == ["-e", "CLONE_DEPTH=25"]));
+    }
+
+    #[test]
+    fn run_args_forward_clone_flags_only_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.starts_with("CLONE_")));
+
+        cfg.clone_submodules = true;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w == ["-e", "CLONE_SUBMODULES=1"]));
+        assert!(!args.contains(&"CLONE_LFS=1".to_string()));
+
+        cfg.clone_lfs = true;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w == ["-e", "CLONE_LFS=1"]));
+    }
+
+    #[test]
+    fn run_args_set_draft_env_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.draft_prs = false;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.contains(&"DRAFT=1".to_string()));
+
+        cfg.draft_prs = true;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w == ["-e", "DRAFT=1"]));
+    }
+
+    #[test]
+    fn run_args_set_rerequest_review_env_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.contains(&"REREQUEST_REVIEW=1".to_string()));
+
+        cfg.rerequest_review = true;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w == ["-e", "REREQUEST_REVIEW=1"]));
+    }
+
+    #[test]
+    fn run_args_set_audit_comments_env_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.contains(&"AUDIT_COMMENTS=1".to_string()));
+
+        cfg.audit_comments = true;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w == ["-e", "AUDIT_COMMENTS=1"]));
+    }
+
+    #[test]
+    fn run_args_mount_proposals_only_in_propose_only_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.contains(&"PROPOSE_ONLY=1".to_string()));
+        assert!(!args.iter().any(|a| a.ends_with(":/sipag-proposals")));
+
+        cfg.propose_only = true;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w == ["-e", "PROPOSE_ONLY=1"]));
+        let mount = format!(
+            "{}:/sipag-proposals",
+            dir.path().join("proposals").display()
+        );
+        assert!(args.windows(2).any(|w| w[0] == "-v" && w[1] == mount));
+    }
+
+    #[test]
+    fn run_args_mount_filtered_prompt_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.prompt_filter_command = None;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.starts_with("PROMPT_FILE=")));
+        assert!(!args.iter().any(|a| a.starts_with("PROMPT_FILTER_COMMAND=")));
+
+        cfg.prompt_filter_command = Some("/opt/filters/redact".to_string());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        let mount = format!(
+            "{}:/sipag-filtered-prompts:ro",
+            dir.path().join("filtered-prompts").display()
+        );
+        assert!(args.windows(2).any(|w| w[0] == "-v" && w[1] == mount));
+        assert!(args
+            .windows(2)
+            .any(|w| w == ["-e", "PROMPT_FILE=/sipag-filtered-prompts/o--r--pr-1.md"]));
+        // The filter itself never reaches the container.
+        assert!(!args.iter().any(|a| a.contains("/opt/filters/redact")));
+    }
+
+    fn prompt_request<'a>(body: &'a str) -> PromptRequest<'a> {
+        PromptRequest {
+            repo: "o/r",
+            pr_num: 7,
+            branch: "sipag/pr-7",
+            pr_body: body,
+            issues: &[3],
+            commit_prefix: None,
+        }
+    }
+
+    #[test]
+    fn prepare_filtered_prompt_writes_transformed_prompt() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.prompt_filter_command = Some("tr '[:lower:]' '[:upper:]'".to_string());
+        prepare_filtered_prompt(&prompt_request("fix the parser"), &cfg).unwrap();
+
+        let written =
+            fs::read_to_string(dir.path().join("filtered-prompts/o--r--pr-7.md")).unwrap();
+        assert!(written.contains("FIX THE PARSER"), "{written}");
+        assert!(!written.contains("fix the parser"));
+    }
+
+    #[test]
+    fn prepare_filtered_prompt_fails_with_filter_stderr() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.prompt_filter_command = Some("echo secret found >&2; exit 2".to_string());
+        let err = prepare_filtered_prompt(&prompt_request("fix"), &cfg).unwrap_err();
+        assert!(format!("{err:#}").contains("secret found"), "{err:#}");
+        assert!(!dir.path().join("filtered-prompts/o--r--pr-7.md").exists());
+    }
+
+    #[test]
+    fn prepare_filtered_prompt_is_a_no_op_without_a_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.prompt_filter_command = None;
+        prepare_filtered_prompt(&prompt_request(""), &cfg).unwrap();
+        assert!(!dir.path().join("filtered-prompts").exists());
+    }
+
+    #[test]
+    fn run_args_forward_anthropic_base_url_only_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.anthropic_base_url = None;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.starts_with("ANTHROPIC_BASE_URL")));
+
+        cfg.anthropic_base_url = Some("https://llm-gateway.internal/anthropic".to_string());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(args.windows(2).any(|w| w
+            == [
+                "-e",
+                "ANTHROPIC_BASE_URL=https://llm-gateway.internal/anthropic"
+            ]));
+    }
+
+    #[test]
+    fn run_args_forward_git_identity_only_when_configured() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cfg = test_config(dir.path());
+        cfg.git_author_name = None;
+        cfg.git_author_email = None;
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        assert!(!args.iter().any(|a| a.starts_with("GIT_AUTHOR_")));
+        assert!(!args.iter().any(|a| a.starts_with("GIT_COMMITTER_")));
+
+        cfg.git_author_name = Some("Jane Doe".to_string());
+        cfg.git_author_email = Some("jane@example.com".to_string());
+        let args = docker_run_args("c", "o/r", 1, "b", &cfg, None, &no_env);
+        for expected in [
+            "GIT_AUTHOR_NAME=Jane Doe",
+            "GIT_COMMITTER_NAME=Jane Doe",
+            "GIT_AUTHOR_EMAIL=jane@example.com",
+            "GIT_COMMITTER_EMAIL=jane@example.com",
+        ] {
+            assert!(args.contains(&expected.to_string()), "missing {expected}");
+        }
+    }
+
     #[test]
     fn container_name_format() {
         // The naming convention in dispatch_worker is: sipag-{repo_slug}-pr-{pr_num}
@@ -284,6 +1259,8 @@ mod tests {
             ended: None,
             exit_code: None,
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: state_path.clone(),
         };
         state::write_state(&initial).unwrap();
@@ -295,4 +1272,67 @@ mod tests {
         assert_eq!(loaded.issues, vec![10, 20]);
         assert!(loaded.container_id.is_empty());
     }
+
+    #[test]
+    fn recently_dispatched_respects_cooldown() {
+        let worker = |repo: &str, pr_num: u64, issues: Vec<u64>, started: &str| WorkerState {
+            repo: repo.to_string(),
+            pr_num,
+            issues,
+            branch: format!("sipag/pr-{pr_num}"),
+            container_id: String::new(),
+            phase: WorkerPhase::Failed,
+            heartbeat: started.to_string(),
+            started: started.to_string(),
+            ended: None,
+            exit_code: None,
+            error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
+            file_path: std::path::PathBuf::new(),
+        };
+        let states = vec![
+            worker("owner/repo", 7, vec![10], "2026-01-01T00:00:00Z"),
+            worker("owner/other", 8, vec![20], "2026-01-01T00:09:00Z"),
+        ];
+        let now = "2026-01-01T00:10:00Z".parse().unwrap();
+
+        // PR 7 started ten minutes ago.
+        assert!(recently_dispatched(&states, "owner/repo", 7, &[], 900, now));
+        assert!(!recently_dispatched(
+            &states,
+            "owner/repo",
+            7,
+            &[],
+            600,
+            now
+        ));
+        assert!(!recently_dispatched(&states, "owner/repo", 7, &[], 0, now));
+        // A different PR for the same issue also counts.
+        assert!(recently_dispatched(
+            &states,
+            "owner/repo",
+            9,
+            &[10],
+            900,
+            now
+        ));
+        assert!(!recently_dispatched(
+            &states,
+            "owner/repo",
+            9,
+            &[11],
+            900,
+            now
+        ));
+        // Issue numbers are per repo.
+        assert!(!recently_dispatched(
+            &states,
+            "owner/repo",
+            9,
+            &[20],
+            900,
+            now
+        ));
+    }
 }
@@ -1,34 +1,123 @@
 //! GitHub operations via the `gh` CLI.
 
 use anyhow::{bail, Context, Result};
-use std::process::{Command, Stdio};
+use chrono::{DateTime, Utc};
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
 
-/// List open issues with the given label, sorted by number ascending.
-pub fn list_labeled_issues(repo: &str, label: &str) -> Result<Vec<u64>> {
-    const LIMIT: usize = 100;
-    let limit_str = LIMIT.to_string();
-    let mut args = vec![
-        "issue", "list", "--repo", repo, "--state", "open", "--json", "number", "--limit",
-        &limit_str,
-    ];
-    let label_args;
-    if !label.is_empty() {
-        label_args = ["--label", label];
-        args.extend_from_slice(&label_args);
-    }
+/// Retries after a rate-limited `gh` call before giving up.
+const RATE_LIMIT_RETRIES: u32 = 3;
 
-    let output = Command::new("gh")
-        .args(&args)
-        .output()
-        .context("Failed to run gh issue list")?;
+/// Sleep before the first rate-limit retry; doubles on each further retry.
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60);
 
-    if !output.status.success() {
+/// Run `gh` with `args` and parse its stdout as JSON.
+///
+/// A call that fails with a rate-limit error (primary or secondary) sleeps
+/// and retries up to [`RATE_LIMIT_RETRIES`] times before erroring, so a busy
+/// multi-repo setup backs off instead of treating the limit as "no results".
+pub fn gh_json(args: &[&str]) -> Result<serde_json::Value> {
+    gh_json_with(
+        args,
+        |args| Command::new("gh").args(args).output(),
+        std::thread::sleep,
+    )
+}
+
+/// [`gh_json`] with an injectable command runner and sleep.
+fn gh_json_with(
+    args: &[&str],
+    mut run: impl FnMut(&[&str]) -> std::io::Result<Output>,
+    mut sleep: impl FnMut(Duration),
+) -> Result<serde_json::Value> {
+    let what = args.iter().take(2).copied().collect::<Vec<_>>().join(" ");
+    let mut backoff = RATE_LIMIT_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        let output = run(args).with_context(|| format!("Failed to run gh {what}"))?;
+        if output.status.success() {
+            return serde_json::from_slice(&output.stdout)
+                .with_context(|| format!("Failed to parse gh {what} output"));
+        }
         let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("gh issue list failed for {repo}: {stderr}");
+        if !is_rate_limited(&stderr) || attempt == RATE_LIMIT_RETRIES {
+            bail!("gh {what} failed: {}", stderr.trim());
+        }
+        attempt += 1;
+        log::warn!(
+            "gh {what} hit the GitHub rate limit; retrying in {}s ({attempt}/{RATE_LIMIT_RETRIES})",
+            backoff.as_secs()
+        );
+        sleep(backoff);
+        backoff *= 2;
     }
+}
 
-    let text = String::from_utf8_lossy(&output.stdout);
-    let parsed: serde_json::Value = serde_json::from_str(&text).unwrap_or(serde_json::json!([]));
+/// Whether `gh` stderr reports a GitHub rate limit.
+fn is_rate_limited(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    stderr.contains("rate limit") || stderr.contains("x-ratelimit-remaining: 0")
+}
+
+/// What candidate filters and dispatch checks need to know about an issue.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IssueDetails {
+    pub labels: Vec<String>,
+    pub body: String,
+    /// `None` if GitHub's timestamp is missing or unparsable.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// An issue's labels, body, and last update time, in one `gh issue view`
+/// (through [`gh_json`], so rate limits are retried).
+pub fn get_issue_details(repo: &str, issue_num: u64) -> Result<IssueDetails> {
+    let n = issue_num.to_string();
+    let v = gh_json(&[
+        "issue",
+        "view",
+        &n,
+        "--repo",
+        repo,
+        "--json",
+        "labels,body,updatedAt",
+    ])
+    .with_context(|| format!("Failed to get issue #{issue_num} in {repo}"))?;
+    Ok(parse_issue_details(&v))
+}
+
+/// [`IssueDetails`] from a `gh issue view --json labels,body,updatedAt` object.
+fn parse_issue_details(v: &serde_json::Value) -> IssueDetails {
+    IssueDetails {
+        labels: label_names(&v["labels"]),
+        body: v["body"].as_str().unwrap_or("").to_string(),
+        updated_at: parse_updated_at(v),
+    }
+}
+
+/// The `updatedAt` timestamp of a `gh issue view --json` object.
+fn parse_updated_at(v: &serde_json::Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(v["updatedAt"].as_str()?)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// Whether `labels` is a superset of `required`.
+pub fn has_all_labels(labels: &[String], required: &[&str]) -> bool {
+    required.iter().all(|r| labels.iter().any(|l| l == r))
+}
+
+/// Maximum issues fetched by a single `gh issue list` call.
+pub const ISSUE_LIST_LIMIT: usize = 100;
+
+/// List open issues with the given label, sorted by number ascending.
+///
+/// When `assignee` is set, only issues assigned to that GitHub user are
+/// returned, so several operators can share a repo without dispatching the
+/// same issues.
+pub fn list_labeled_issues(repo: &str, label: &str, assignee: Option<&str>) -> Result<Vec<u64>> {
+    let args = issue_list_args(repo, label, assignee);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let parsed = gh_json(&args).with_context(|| format!("Failed to list issues for {repo}"))?;
 
     let mut issues = vec![];
     if let Some(arr) = parsed.as_array() {
@@ -37,32 +126,316 @@ pub fn list_labeled_issues(repo: &str, label: &str) -> Result<Vec<u64>> {
                 issues.push(n);
             }
         }
-        if arr.len() == LIMIT {
-            eprintln!("sipag warning: list_labeled_issues returned {LIMIT} issues (limit reached)");
+        if arr.len() == ISSUE_LIST_LIMIT {
+            log::warn!("list_labeled_issues returned {ISSUE_LIST_LIMIT} issues (limit reached)");
         }
     }
     issues.sort_unstable();
     Ok(issues)
 }
 
-/// Count open PRs created by sipag (labeled `sipag`).
-pub fn count_open_sipag_prs(repo: &str) -> Result<usize> {
+/// Build `gh issue list` arguments for open issues, optionally filtered by
+/// label and assignee. Empty strings are treated as "no filter".
+fn issue_list_args(repo: &str, label: &str, assignee: Option<&str>) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "issue", "list", "--repo", repo, "--state", "open", "--json", "number", "--limit",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    args.push(ISSUE_LIST_LIMIT.to_string());
+    if !label.is_empty() {
+        args.push("--label".to_string());
+        args.push(label.to_string());
+    }
+    if let Some(who) = assignee.filter(|a| !a.is_empty()) {
+        args.push("--assignee".to_string());
+        args.push(who.to_string());
+    }
+    args
+}
+
+/// Count open issues on a repo (up to [`ISSUE_LIST_LIMIT`]).
+pub fn count_open_issues(repo: &str) -> Result<usize> {
+    count_open(repo, "issue")
+}
+
+/// Count open PRs on a repo (up to [`ISSUE_LIST_LIMIT`]).
+pub fn count_open_prs(repo: &str) -> Result<usize> {
+    count_open(repo, "pr")
+}
+
+/// `gh <kind> list --state open` and count the results.
+fn count_open(repo: &str, kind: &str) -> Result<usize> {
+    let limit = ISSUE_LIST_LIMIT.to_string();
+    let count = gh_json(&[
+        kind, "list", "--repo", repo, "--state", "open", "--limit", &limit, "--json", "number",
+        "--jq", "length",
+    ])
+    .with_context(|| format!("failed to count open {kind}s for {repo}"))?;
+    count
+        .as_u64()
+        .map(|n| n as usize)
+        .with_context(|| format!("failed to parse open {kind} count"))
+}
+
+/// The open PRs on a repo, fetched once by [`list_open_prs`] so several
+/// views can be derived from a single `gh pr list` call.
+#[derive(Debug, Clone)]
+pub struct OpenPrs {
+    json: String,
+}
+
+/// Fetch every open PR on `repo` (see [`list_open_prs_json`]).
+pub fn list_open_prs(repo: &str) -> Result<OpenPrs> {
+    Ok(OpenPrs {
+        json: list_open_prs_json(repo)?,
+    })
+}
+
+impl OpenPrs {
+    /// Number of open sipag PRs (see [`is_sipag_pr`]).
+    pub fn sipag_count(&self, branch_prefix: &str) -> usize {
+        parse_sipag_prs(&self.json, branch_prefix).len()
+    }
+
+    /// Open sipag PRs that GitHub reports as conflicting with their base.
+    pub fn conflicted(&self, branch_prefix: &str) -> Vec<u64> {
+        parse_conflicted_prs(&self.json, branch_prefix)
+    }
+
+    /// Each open PR with the issues it closes (per [`extract_issue_nums`]
+    /// on its body), for spotting issues already taken.
+    pub fn issue_refs(&self) -> Vec<(u64, Vec<u64>)> {
+        parse_pr_issue_refs(&self.json)
+    }
+}
+
+/// Maximum open PRs fetched by [`list_open_prs_json`]. `gh pr list`
+/// pages through the API (100 per request) until it has this many.
+pub const PR_LIST_LIMIT: usize = 1000;
+
+/// `gh pr list` JSON for every open PR, with the fields sipag filters on.
+///
+/// Not filtered by `--label sipag` server-side: sipag PRs are also
+/// recognized by `branch_prefix`, and [`OpenPrs::issue_refs`] needs all PRs.
+fn list_open_prs_json(repo: &str) -> Result<String> {
+    let limit = PR_LIST_LIMIT.to_string();
+    let prs = gh_json(&[
+        "pr",
+        "list",
+        "--repo",
+        repo,
+        "--state",
+        "open",
+        "--json",
+        "number,title,mergeable,headRefName,labels,body,statusCheckRollup",
+        "--limit",
+        &limit,
+    ])
+    .with_context(|| format!("failed to list open PRs for {repo}"))?;
+    if prs.as_array().is_some_and(|arr| arr.len() == PR_LIST_LIMIT) {
+        log::warn!("open PR list for {repo} returned {PR_LIST_LIMIT} PRs (limit reached)");
+    }
+    Ok(prs.to_string())
+}
+
+/// Extract issue numbers from "Closes/Fixes/Resolves #N" in text.
+pub fn extract_issue_nums(body: &str) -> Vec<u64> {
+    let mut nums = Vec::new();
+    for line in body.lines() {
+        let lower = line.to_lowercase();
+        for keyword in &["closes #", "fixes #", "resolves #"] {
+            let mut search_from = 0;
+            while let Some(pos) = lower[search_from..].find(keyword) {
+                let abs_pos = search_from + pos + keyword.len();
+                let rest = &line[abs_pos..];
+                let num_str: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+                if let Ok(n) = num_str.parse::<u64>() {
+                    if !nums.contains(&n) {
+                        nums.push(n);
+                    }
+                }
+                search_from = abs_pos;
+            }
+        }
+    }
+    nums
+}
+
+/// `(PR number, closed issues)` for each PR in `gh pr list` JSON that
+/// references at least one issue.
+fn parse_pr_issue_refs(json: &str) -> Vec<(u64, Vec<u64>)> {
+    let parsed: serde_json::Value = serde_json::from_str(json).unwrap_or(serde_json::json!([]));
+    parsed
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|pr| {
+                    let number = pr["number"].as_u64()?;
+                    let issues = extract_issue_nums(pr["body"].as_str().unwrap_or(""));
+                    (!issues.is_empty()).then_some((number, issues))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether a PR belongs to sipag: labeled `sipag`, or on a branch starting
+/// with `branch_prefix` (the `branch_prefix` config key, default `sipag/`).
+fn is_sipag_pr(pr: &serde_json::Value, branch_prefix: &str) -> bool {
+    let labeled = pr["labels"]
+        .as_array()
+        .is_some_and(|ls| ls.iter().any(|l| l["name"].as_str() == Some("sipag")));
+    let on_prefix = !branch_prefix.is_empty()
+        && pr["headRefName"]
+            .as_str()
+            .is_some_and(|b| b.starts_with(branch_prefix));
+    labeled || on_prefix
+}
+
+/// The sipag PRs in a `gh pr list` JSON array.
+fn parse_sipag_prs(json: &str, branch_prefix: &str) -> Vec<serde_json::Value> {
+    let parsed: serde_json::Value = serde_json::from_str(json).unwrap_or(serde_json::json!([]));
+    parsed
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter(|pr| is_sipag_pr(pr, branch_prefix))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Extract sipag PR numbers with `"mergeable": "CONFLICTING"` from `gh pr list` JSON.
+///
+/// `UNKNOWN` (GitHub hasn't computed mergeability yet) is not a conflict.
+fn parse_conflicted_prs(json: &str, branch_prefix: &str) -> Vec<u64> {
+    let mut prs: Vec<u64> = parse_sipag_prs(json, branch_prefix)
+        .iter()
+        .filter(|pr| pr["mergeable"].as_str() == Some("CONFLICTING"))
+        .filter_map(|pr| pr["number"].as_u64())
+        .collect();
+    prs.sort_unstable();
+    prs
+}
+
+/// Open sipag PRs grouped by what stands between each one and a merge,
+/// as decided by [`merge_outcome`].
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MergeStatus {
+    /// `MERGEABLE` with every check passing.
+    pub ready: Vec<(u64, String)>,
+    /// Conflicting with the base branch.
+    pub conflicting: Vec<(u64, String)>,
+    /// Checks failing or still pending (including PRs with no checks and
+    /// PRs whose mergeability GitHub hasn't computed yet), with the outcome.
+    pub checks_blocked: Vec<(u64, String, MergeOutcome)>,
+}
+
+/// Group the open sipag PRs on `repo` into a [`MergeStatus`]. Read-only.
+pub fn merge_status(repo: &str, branch_prefix: &str) -> Result<MergeStatus> {
+    Ok(parse_merge_status(
+        &list_open_prs(repo)?.json,
+        branch_prefix,
+    ))
+}
+
+/// Categorize the sipag PRs in `gh pr list` JSON, each group sorted by
+/// PR number.
+fn parse_merge_status(json: &str, branch_prefix: &str) -> MergeStatus {
+    let mut status = MergeStatus::default();
+    for pr in parse_sipag_prs(json, branch_prefix) {
+        let Some(number) = pr["number"].as_u64() else {
+            continue;
+        };
+        let details = parse_pr_details(&pr, number);
+        match merge_outcome(&details) {
+            MergeOutcome::Merged => status.ready.push((number, details.title)),
+            MergeOutcome::SkippedConflicting => status.conflicting.push((number, details.title)),
+            outcome @ (MergeOutcome::SkippedPending | MergeOutcome::SkippedFailing) => {
+                status.checks_blocked.push((number, details.title, outcome))
+            }
+            MergeOutcome::SkippedClosed => {}
+        }
+    }
+    status.ready.sort_unstable();
+    status.conflicting.sort_unstable();
+    status.checks_blocked.sort_unstable_by_key(|(n, _, _)| *n);
+    status
+}
+
+/// List the names of all labels defined on a repo.
+pub fn list_labels(repo: &str) -> Result<Vec<String>> {
     let output = Command::new("gh")
         .args([
-            "pr", "list", "--repo", repo, "--state", "open", "--label", "sipag", "--json",
-            "number", "--jq", "length",
+            "label", "list", "--repo", repo, "--json", "name", "--limit", "200",
         ])
         .output()
-        .context("failed to run gh pr list")?;
+        .context("Failed to run gh label list")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        bail!("gh pr list failed for {repo}: {stderr}");
+        bail!("gh label list failed for {repo}: {stderr}");
     }
-    let text = String::from_utf8_lossy(&output.stdout);
-    text.trim()
-        .parse::<usize>()
-        .context("failed to parse sipag PR count")
+    Ok(parse_label_names(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Whether `label` is defined on `repo`.
+///
+/// Looks the label up directly (`gh api repos/{repo}/labels/{label}`), so
+/// repos with more labels than a single `gh label list` page are handled.
+/// Returns `true` when the lookup fails for any reason other than a 404 —
+/// a gh hiccup shouldn't be reported as a missing label.
+pub fn label_exists(repo: &str, label: &str) -> bool {
+    let output = Command::new("gh")
+        .args([
+            "api",
+            &format!("repos/{repo}/labels/{}", encode_path_segment(label)),
+        ])
+        .output();
+    match output {
+        Ok(o) => label_lookup_found(o.status.success(), &String::from_utf8_lossy(&o.stderr)),
+        Err(_) => true,
+    }
+}
+
+/// Interpret a `gh api repos/{repo}/labels/{label}` result: only an
+/// explicit 404 means the label is missing.
+fn label_lookup_found(success: bool, stderr: &str) -> bool {
+    success || !stderr.contains("HTTP 404")
+}
+
+/// Percent-encode a label name for use as a single URL path segment.
+fn encode_path_segment(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
+/// Extract label names from `gh label list --json name` output.
+fn parse_label_names(json: &str) -> Vec<String> {
+    label_names(&serde_json::from_str(json).unwrap_or(serde_json::json!([])))
+}
+
+/// Names from a JSON array of `{"name": ...}` label objects.
+fn label_names(labels: &serde_json::Value) -> Vec<String> {
+    labels
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|l| l["name"].as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 /// Ensure the `sipag` label exists on a repo (idempotent).
@@ -84,7 +457,7 @@ pub fn ensure_sipag_label(repo: &str) {
         .status();
     // Label already existing is fine (gh exits 0 or 1 for "already exists").
     if let Err(e) = status {
-        eprintln!("sipag warning: failed to ensure sipag label on {repo}: {e}");
+        log::warn!("failed to ensure sipag label on {repo}: {e}");
     }
 }
 
@@ -98,10 +471,10 @@ pub fn label_pr_sipag(repo: &str, pr_num: u64) {
     match output {
         Ok(o) if !o.status.success() => {
             let stderr = String::from_utf8_lossy(&o.stderr);
-            eprintln!("sipag warning: failed to label PR #{pr_num} on {repo}: {stderr}");
+            log::warn!("failed to label PR #{pr_num} on {repo}: {stderr}");
         }
         Err(e) => {
-            eprintln!("sipag warning: failed to label PR #{pr_num} on {repo}: {e}");
+            log::warn!("failed to label PR #{pr_num} on {repo}: {e}");
         }
         _ => {}
     }
@@ -246,6 +619,13 @@ pub struct PrDetails {
     pub body: String,
     pub state: String,
     pub head_ref: String,
+    /// GitHub's `mergeable` verdict (`MERGEABLE`, `CONFLICTING`, `UNKNOWN`).
+    pub mergeable: Option<String>,
+    /// `mergeStateStatus`, e.g. `CLEAN`, `BLOCKED`, `BEHIND`.
+    pub merge_state: Option<String>,
+    /// Whether every status check passed; `None` while checks are pending
+    /// or when the PR has none (see [`checks_passing`]).
+    pub checks_passing: Option<bool>,
 }
 
 /// Merge a PR via squash merge and delete the branch.
@@ -271,6 +651,108 @@ pub fn merge_pr(repo: &str, pr_num: u64) -> Result<()> {
     Ok(())
 }
 
+/// What [`auto_merge`] did with a PR.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// Mergeable with passing checks; merged.
+    Merged,
+    /// Checks are still running (or GitHub hasn't computed mergeability
+    /// yet); try again later.
+    SkippedPending,
+    /// At least one check failed.
+    SkippedFailing,
+    /// The PR conflicts with its base branch.
+    SkippedConflicting,
+    /// The PR is not open.
+    SkippedClosed,
+}
+
+/// Decide whether a PR can be merged without a human: it must be open,
+/// `MERGEABLE`, and have every check passing. PRs without checks count as
+/// pending, so a repo with no CI never auto-merges.
+pub fn merge_outcome(pr: &PrDetails) -> MergeOutcome {
+    if pr.state != "OPEN" {
+        return MergeOutcome::SkippedClosed;
+    }
+    match pr.mergeable.as_deref() {
+        Some("CONFLICTING") => return MergeOutcome::SkippedConflicting,
+        Some("MERGEABLE") => {}
+        _ => return MergeOutcome::SkippedPending,
+    }
+    match pr.checks_passing {
+        Some(true) => MergeOutcome::Merged,
+        Some(false) => MergeOutcome::SkippedFailing,
+        None => MergeOutcome::SkippedPending,
+    }
+}
+
+/// Squash-merge a PR if [`merge_outcome`] says it is green, then run
+/// `post_merge_hook` (if set); otherwise leave it for a later attempt and
+/// report why. A failing hook is logged, not returned, so callers keep going.
+pub fn auto_merge(repo: &str, pr_num: u64, post_merge_hook: Option<&str>) -> Result<MergeOutcome> {
+    let details = get_pr_details(repo, pr_num)?;
+    merge_if_green(repo, &details, post_merge_hook, || merge_pr(repo, pr_num))
+}
+
+/// The decision half of [`auto_merge`], with the merge itself injected.
+fn merge_if_green(
+    repo: &str,
+    details: &PrDetails,
+    post_merge_hook: Option<&str>,
+    merge: impl FnOnce() -> Result<()>,
+) -> Result<MergeOutcome> {
+    let outcome = merge_outcome(details);
+    if outcome == MergeOutcome::Merged {
+        merge()?;
+        if let Some(hook) = post_merge_hook {
+            let ctx = MergeHookContext {
+                repo,
+                pr_num: details.number,
+                issues: extract_issue_nums(&details.body),
+            };
+            if let Err(e) = run_post_merge_hook(hook, &ctx) {
+                log::warn!("{e}");
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+/// What a `post_merge_hook` is told about the merge.
+#[derive(Debug, Clone)]
+pub struct MergeHookContext<'a> {
+    pub repo: &'a str,
+    pub pr_num: u64,
+    /// Issues the PR closes, from its body.
+    pub issues: Vec<u64>,
+}
+
+/// Run `command` with `sh -c`, exporting `SIPAG_REPO`, `SIPAG_PR` and
+/// `SIPAG_ISSUES` (comma-separated). Errors if it can't start or exits
+/// non-zero.
+pub fn run_post_merge_hook(command: &str, ctx: &MergeHookContext) -> Result<()> {
+    let issues: Vec<String> = ctx.issues.iter().map(u64::to_string).collect();
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .env("SIPAG_REPO", ctx.repo)
+        .env("SIPAG_PR", ctx.pr_num.to_string())
+        .env("SIPAG_ISSUES", issues.join(","))
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("failed to run post_merge_hook: {command}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "post_merge_hook for PR #{} in {} exited with {}: {}",
+            ctx.pr_num,
+            ctx.repo,
+            output.status,
+            stderr.trim()
+        );
+    }
+    Ok(())
+}
+
 /// Post a comment on a PR.
 pub fn post_pr_comment(repo: &str, pr_num: u64, body: &str) -> Result<()> {
     let n = pr_num.to_string();
@@ -286,6 +768,33 @@ pub fn post_pr_comment(repo: &str, pr_num: u64, body: &str) -> Result<()> {
     Ok(())
 }
 
+/// `gh issue comment` args posting `body` on an issue.
+fn issue_comment_args(repo: &str, issue_num: u64, body: &str) -> Vec<String> {
+    vec![
+        "issue".to_string(),
+        "comment".to_string(),
+        issue_num.to_string(),
+        "--repo".to_string(),
+        repo.to_string(),
+        "--body".to_string(),
+        body.to_string(),
+    ]
+}
+
+/// Post a comment on an issue.
+pub fn post_issue_comment(repo: &str, issue_num: u64, body: &str) -> Result<()> {
+    let output = Command::new("gh")
+        .args(issue_comment_args(repo, issue_num, body))
+        .output()
+        .context("Failed to run gh issue comment")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to comment on issue #{issue_num} in {repo}: {stderr}");
+    }
+    Ok(())
+}
+
 /// Replace the body of a PR.
 pub fn edit_pr_body(repo: &str, pr_num: u64, body: &str) -> Result<()> {
     let n = pr_num.to_string();
@@ -333,61 +842,240 @@ pub fn get_pr_diff(repo: &str, pr_num: u64) -> Result<String> {
 
 /// Get full details for a PR (title, body, state, head ref).
 pub fn get_pr_details(repo: &str, pr_num: u64) -> Result<PrDetails> {
+    let n = pr_num.to_string();
+    let v = gh_json(&[
+        "pr",
+        "view",
+        &n,
+        "--repo",
+        repo,
+        "--json",
+        "number,title,body,state,headRefName,mergeable,mergeStateStatus,statusCheckRollup",
+    ])
+    .with_context(|| format!("Failed to get PR #{pr_num} details in {repo}"))?;
+    Ok(parse_pr_details(&v, pr_num))
+}
+
+/// Build [`PrDetails`] from `gh pr view --json` output.
+fn parse_pr_details(v: &serde_json::Value, pr_num: u64) -> PrDetails {
+    let opt_str = |key: &str| v[key].as_str().map(|s| s.to_string());
+    PrDetails {
+        number: v["number"].as_u64().unwrap_or(pr_num),
+        title: v["title"].as_str().unwrap_or("").to_string(),
+        body: v["body"].as_str().unwrap_or("").to_string(),
+        state: v["state"].as_str().unwrap_or("OPEN").to_string(),
+        head_ref: v["headRefName"].as_str().unwrap_or("").to_string(),
+        mergeable: opt_str("mergeable"),
+        merge_state: opt_str("mergeStateStatus"),
+        checks_passing: checks_passing(&v["statusCheckRollup"]),
+    }
+}
+
+/// Summarize a `statusCheckRollup` array: `Some(false)` if any check failed,
+/// `Some(true)` if all completed successfully, `None` if any is still
+/// pending or there are no checks.
+///
+/// Check runs report `status`/`conclusion`; legacy commit statuses report
+/// a single `state`.
+fn checks_passing(rollup: &serde_json::Value) -> Option<bool> {
+    let checks = rollup.as_array().filter(|a| !a.is_empty())?;
+    let mut pending = false;
+    for check in checks {
+        let outcome = check["conclusion"]
+            .as_str()
+            .filter(|c| !c.is_empty())
+            .or_else(|| check["state"].as_str())
+            .unwrap_or("");
+        match outcome {
+            "SUCCESS" | "NEUTRAL" | "SKIPPED" => {}
+            "" | "PENDING" | "EXPECTED" => pending = true,
+            _ => return Some(false),
+        }
+    }
+    (!pending).then_some(true)
+}
+
+/// Reviewers whose most recent review of a PR requested changes.
+pub fn changes_requested_reviewers(repo: &str, pr_num: u64) -> Result<Vec<String>> {
     let n = pr_num.to_string();
     let output = Command::new("gh")
-        .args([
-            "pr",
-            "view",
-            &n,
-            "--repo",
-            repo,
-            "--json",
-            "number,title,body,state,headRefName",
-        ])
+        .args(["pr", "view", &n, "--repo", repo, "--json", "reviews"])
         .output()
         .context("Failed to run gh pr view")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to get PR #{pr_num} details in {repo}: {stderr}");
+        bail!("Failed to get reviews for PR #{pr_num} in {repo}: {stderr}");
     }
+    Ok(parse_changes_requested(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
 
-    let v: serde_json::Value = serde_json::from_slice(&output.stdout)?;
-    Ok(PrDetails {
-        number: v["number"].as_u64().unwrap_or(pr_num),
-        title: v["title"].as_str().unwrap_or("").to_string(),
-        body: v["body"].as_str().unwrap_or("").to_string(),
-        state: v["state"].as_str().unwrap_or("OPEN").to_string(),
-        head_ref: v["headRefName"].as_str().unwrap_or("").to_string(),
-    })
+/// Extract reviewers whose latest decisive review is `CHANGES_REQUESTED`
+/// from `gh pr view --json reviews` output.
+///
+/// Reviews arrive oldest first. `COMMENTED` reviews don't change a reviewer's
+/// standing, so an approval after a change request clears it but a comment
+/// does not.
+fn parse_changes_requested(json: &str) -> Vec<String> {
+    let parsed: serde_json::Value = serde_json::from_str(json).unwrap_or(serde_json::json!({}));
+    let mut latest: std::collections::BTreeMap<String, String> = Default::default();
+    for review in parsed["reviews"].as_array().into_iter().flatten() {
+        let (Some(login), Some(state)) =
+            (review["author"]["login"].as_str(), review["state"].as_str())
+        else {
+            continue;
+        };
+        if state == "COMMENTED" || state == "PENDING" {
+            continue;
+        }
+        latest.insert(login.to_string(), state.to_string());
+    }
+    latest
+        .into_iter()
+        .filter(|(_, state)| state == "CHANGES_REQUESTED")
+        .map(|(login, _)| login)
+        .collect()
 }
 
-/// Get the body text of a GitHub issue.
-pub fn get_issue_body(repo: &str, issue_num: u64) -> Result<String> {
-    let n = issue_num.to_string();
+/// `gh pr edit --add-reviewer` args that re-request review from `reviewers`.
+fn rerequest_review_args(repo: &str, pr_num: u64, reviewers: &[String]) -> Vec<String> {
+    vec![
+        "pr".to_string(),
+        "edit".to_string(),
+        pr_num.to_string(),
+        "--repo".to_string(),
+        repo.to_string(),
+        "--add-reviewer".to_string(),
+        reviewers.join(","),
+    ]
+}
+
+/// Re-request review from everyone whose latest review requested changes, so
+/// they are notified that the PR was updated. Returns the reviewers asked.
+pub fn rerequest_review(repo: &str, pr_num: u64) -> Result<Vec<String>> {
+    let reviewers = changes_requested_reviewers(repo, pr_num)?;
+    if reviewers.is_empty() {
+        return Ok(reviewers);
+    }
     let output = Command::new("gh")
-        .args(["issue", "view", &n, "--repo", repo, "--json", "body"])
+        .args(rerequest_review_args(repo, pr_num, &reviewers))
         .output()
-        .context("Failed to run gh issue view")?;
+        .context("Failed to run gh pr edit")?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to get issue #{issue_num} body in {repo}: {stderr}");
+        bail!("Failed to re-request review on PR #{pr_num} in {repo}: {stderr}");
     }
+    Ok(reviewers)
+}
+
+/// What to do before adding a label to issues, given the repo's labels.
+#[derive(Debug, PartialEq)]
+pub enum LabelAddPlan {
+    /// The label exists; add it directly.
+    Add,
+    /// The label is missing and `create_missing_labels` is on; create it first.
+    CreateThenAdd,
+    /// The label is missing and creation is off; warn and skip the add,
+    /// which would otherwise fail on every issue.
+    WarnMissing,
+}
 
-    let v: serde_json::Value = serde_json::from_slice(&output.stdout)?;
-    Ok(v["body"].as_str().unwrap_or("").to_string())
+/// Decide how to add `label` given the labels already defined on the repo.
+pub fn plan_label_add(existing: &[String], label: &str, create_missing: bool) -> LabelAddPlan {
+    if existing.iter().any(|l| l == label) {
+        LabelAddPlan::Add
+    } else if create_missing {
+        LabelAddPlan::CreateThenAdd
+    } else {
+        LabelAddPlan::WarnMissing
+    }
+}
+
+/// Clock skew against GitHub, in seconds, beyond which `sipag doctor` warns.
+pub const MAX_CLOCK_SKEW_SECS: i64 = 60;
+
+/// Local clock minus GitHub's, read from the `Date` header of `gh api -i /`.
+/// Positive means the local clock is ahead. `None` if gh fails or the
+/// header is missing.
+pub fn detect_clock_skew() -> Option<chrono::TimeDelta> {
+    let output = Command::new("gh").args(["api", "-i", "/"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let server = parse_date_header(&String::from_utf8_lossy(&output.stdout))?;
+    Some(Utc::now() - server)
+}
+
+/// The `Date` header (RFC 2822) from an HTTP response dump.
+fn parse_date_header(response: &str) -> Option<DateTime<Utc>> {
+    response
+        .lines()
+        .take_while(|l| !l.trim().is_empty())
+        .find_map(|l| {
+            let (name, value) = l.split_once(':')?;
+            name.trim().eq_ignore_ascii_case("date").then_some(value)
+        })
+        .and_then(|v| DateTime::parse_from_rfc2822(v.trim()).ok())
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Whether `skew` (either direction) is larger than [`MAX_CLOCK_SKEW_SECS`].
+pub fn clock_skew_exceeds_limit(skew: chrono::TimeDelta) -> bool {
+    skew.num_seconds().abs() > MAX_CLOCK_SKEW_SECS
+}
+
+/// Create a label on a repo.
+pub fn create_label(repo: &str, label: &str) -> Result<()> {
+    let output = Command::new("gh")
+        .args(["label", "create", label, "--repo", repo])
+        .output()
+        .context("Failed to run gh label create")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to create label '{label}' on {repo}: {stderr}");
+    }
+    Ok(())
 }
 
 /// Transition labels on a batch of GitHub issues.
 ///
-/// Removes `remove_label` and adds `add_label` on each issue.
+/// Removes `remove_label` and adds `add_label` on each issue. If `add_label`
+/// isn't defined on the repo, it is created first when `create_missing` is
+/// set; otherwise a warning explains why the add is skipped.
 pub fn label_issues(
     repo: &str,
     issue_nums: &[u64],
     remove_label: Option<&str>,
     add_label: Option<&str>,
+    create_missing: bool,
 ) -> Result<()> {
+    let mut add_label = add_label;
+    if let Some(label) = add_label {
+        // If the label list can't be fetched, fall through and let the
+        // per-issue add report its own failure.
+        if let Ok(existing) = list_labels(repo) {
+            match plan_label_add(&existing, label, create_missing) {
+                LabelAddPlan::Add => {}
+                LabelAddPlan::CreateThenAdd => {
+                    if let Err(e) = create_label(repo, label) {
+                        log::warn!("{e}");
+                    }
+                }
+                LabelAddPlan::WarnMissing => {
+                    log::warn!(
+                        "label '{label}' does not exist on {repo}; not adding it \
+                         (set create_missing_labels=true to create it automatically)"
+                    );
+                    add_label = None;
+                }
+            }
+        }
+    }
+
     for &num in issue_nums {
         let n = num.to_string();
 
@@ -400,14 +1088,12 @@ pub fn label_issues(
             {
                 Ok(o) if !o.status.success() => {
                     let stderr = String::from_utf8_lossy(&o.stderr);
-                    eprintln!(
-                        "sipag warning: failed to remove label '{label}' from issue #{num} on {repo}: {stderr}"
+                    log::warn!(
+                        "failed to remove label '{label}' from issue #{num} on {repo}: {stderr}"
                     );
                 }
                 Err(e) => {
-                    eprintln!(
-                        "sipag warning: failed to remove label '{label}' from issue #{num} on {repo}: {e}"
-                    );
+                    log::warn!("failed to remove label '{label}' from issue #{num} on {repo}: {e}");
                 }
                 _ => {}
             }
@@ -421,14 +1107,10 @@ pub fn label_issues(
             {
                 Ok(o) if !o.status.success() => {
                     let stderr = String::from_utf8_lossy(&o.stderr);
-                    eprintln!(
-                        "sipag warning: failed to add label '{label}' to issue #{num} on {repo}: {stderr}"
-                    );
+                    log::warn!("failed to add label '{label}' to issue #{num} on {repo}: {stderr}");
                 }
                 Err(e) => {
-                    eprintln!(
-                        "sipag warning: failed to add label '{label}' to issue #{num} on {repo}: {e}"
-                    );
+                    log::warn!("failed to add label '{label}' to issue #{num} on {repo}: {e}");
                 }
                 _ => {}
             }
@@ -436,3 +1118,536 @@ pub fn label_issues(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn issue_list_args_without_assignee() {
+        let args = issue_list_args("o/r", "ready", None);
+        assert!(args.windows(2).any(|w| w == ["--label", "ready"]));
+        assert!(!args.contains(&"--assignee".to_string()));
+
+        let args = issue_list_args("o/r", "ready", Some(""));
+        assert!(!args.contains(&"--assignee".to_string()));
+    }
+
+    #[test]
+    fn issue_list_args_with_assignee() {
+        let args = issue_list_args("o/r", "ready", Some("octocat"));
+        assert!(args.windows(2).any(|w| w == ["--assignee", "octocat"]));
+        assert!(args.windows(2).any(|w| w == ["--repo", "o/r"]));
+    }
+
+    #[test]
+    fn parse_label_names_from_gh_output() {
+        let json = r#"[{"name":"bug"},{"name":"ready"},{"name":"sipag"}]"#;
+        let labels = parse_label_names(json);
+        assert_eq!(labels, vec!["bug", "ready", "sipag"]);
+        assert!(labels.iter().any(|l| l == "ready"));
+        assert!(!labels.iter().any(|l| l == "redy"));
+    }
+
+    #[test]
+    fn open_prs_derives_every_view_from_one_listing() {
+        let prs = OpenPrs {
+            json: r#"[
+                {"number": 1, "headRefName": "sipag/pr-1", "mergeable": "CONFLICTING", "labels": [], "body": "Closes #10"},
+                {"number": 2, "headRefName": "fix", "mergeable": "MERGEABLE", "labels": [{"name": "sipag"}], "body": ""},
+                {"number": 3, "headRefName": "human", "mergeable": "CONFLICTING", "labels": [], "body": "Fixes #11"}
+            ]"#
+            .to_string(),
+        };
+        assert_eq!(prs.sipag_count("sipag/"), 2);
+        assert_eq!(prs.conflicted("sipag/"), vec![1]);
+        assert_eq!(prs.issue_refs(), vec![(1, vec![10]), (3, vec![11])]);
+    }
+
+    #[test]
+    fn parse_issue_details_reads_all_fields() {
+        let v = serde_json::json!({
+            "labels": [{"name": "ready"}, {"name": "triaged"}],
+            "body": "Fix it",
+            "updatedAt": "2026-01-01T12:00:00Z"
+        });
+        let d = parse_issue_details(&v);
+        assert_eq!(d.labels, vec!["ready", "triaged"]);
+        assert_eq!(d.body, "Fix it");
+        assert!(d.updated_at.is_some());
+        assert_eq!(
+            parse_issue_details(&serde_json::json!({})),
+            IssueDetails::default()
+        );
+    }
+
+    #[test]
+    fn parse_updated_at_reads_rfc3339() {
+        let v = serde_json::json!({"updatedAt": "2026-01-01T12:00:00Z"});
+        assert_eq!(
+            parse_updated_at(&v).unwrap().to_rfc3339(),
+            "2026-01-01T12:00:00+00:00"
+        );
+        assert_eq!(parse_updated_at(&serde_json::json!({})), None);
+        assert_eq!(
+            parse_updated_at(&serde_json::json!({"updatedAt": "soon"})),
+            None
+        );
+    }
+
+    #[test]
+    fn label_lookup_only_404_means_missing() {
+        assert!(label_lookup_found(true, ""));
+        assert!(!label_lookup_found(
+            false,
+            "gh: Not Found (HTTP 404)\n{\"message\":\"Not Found\"}"
+        ));
+        assert!(label_lookup_found(
+            false,
+            "error connecting to api.github.com"
+        ));
+        assert!(label_lookup_found(false, "gh: Bad credentials (HTTP 401)"));
+    }
+
+    #[test]
+    fn encode_path_segment_escapes_label_names() {
+        assert_eq!(encode_path_segment("ready"), "ready");
+        assert_eq!(
+            encode_path_segment("good first issue"),
+            "good%20first%20issue"
+        );
+        assert_eq!(encode_path_segment("area/cli"), "area%2Fcli");
+        assert_eq!(encode_path_segment("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn parse_label_names_bad_json() {
+        assert!(parse_label_names("").is_empty());
+    }
+
+    #[test]
+    fn has_all_labels_superset() {
+        let labels = vec![
+            "ready".to_string(),
+            "triaged".to_string(),
+            "bug".to_string(),
+        ];
+        assert!(has_all_labels(&labels, &["triaged"]));
+        assert!(has_all_labels(&labels, &["ready", "triaged"]));
+        assert!(has_all_labels(&labels, &[]));
+    }
+
+    #[test]
+    fn has_all_labels_missing_one() {
+        let labels = vec!["ready".to_string()];
+        assert!(!has_all_labels(&labels, &["ready", "triaged"]));
+        assert!(!has_all_labels(&[], &["triaged"]));
+    }
+
+    #[test]
+    fn plan_label_add_existing_label() {
+        let existing = vec!["bug".to_string(), "in-progress".to_string()];
+        assert_eq!(
+            plan_label_add(&existing, "in-progress", false),
+            LabelAddPlan::Add
+        );
+        assert_eq!(
+            plan_label_add(&existing, "in-progress", true),
+            LabelAddPlan::Add
+        );
+    }
+
+    #[test]
+    fn plan_label_add_missing_creates_when_enabled() {
+        let existing = vec!["bug".to_string()];
+        assert_eq!(
+            plan_label_add(&existing, "in-progress", true),
+            LabelAddPlan::CreateThenAdd
+        );
+    }
+
+    #[test]
+    fn plan_label_add_missing_warns_when_disabled() {
+        assert_eq!(
+            plan_label_add(&[], "in-progress", false),
+            LabelAddPlan::WarnMissing
+        );
+    }
+
+    #[test]
+    fn parse_conflicted_prs_only_conflicting() {
+        let json = r#"[
+            {"number": 7, "mergeable": "CONFLICTING", "headRefName": "sipag/a"},
+            {"number": 3, "mergeable": "MERGEABLE", "headRefName": "sipag/b"},
+            {"number": 5, "mergeable": "UNKNOWN", "headRefName": "sipag/c"},
+            {"number": 2, "mergeable": "CONFLICTING", "headRefName": "sipag/d"}
+        ]"#;
+        assert_eq!(parse_conflicted_prs(json, "sipag/"), vec![2, 7]);
+    }
+
+    #[test]
+    fn parse_merge_status_groups_sipag_prs() {
+        let json = r#"[
+            {"number": 9, "title": "green", "mergeable": "MERGEABLE", "headRefName": "sipag/a",
+             "statusCheckRollup": [{"status": "COMPLETED", "conclusion": "SUCCESS"}]},
+            {"number": 4, "title": "conflict", "mergeable": "CONFLICTING", "headRefName": "sipag/b",
+             "statusCheckRollup": [{"status": "COMPLETED", "conclusion": "SUCCESS"}]},
+            {"number": 6, "title": "red", "mergeable": "MERGEABLE", "headRefName": "sipag/c",
+             "statusCheckRollup": [{"status": "COMPLETED", "conclusion": "FAILURE"}]},
+            {"number": 5, "title": "running", "mergeable": "MERGEABLE", "headRefName": "sipag/d",
+             "statusCheckRollup": [{"status": "IN_PROGRESS", "conclusion": ""}]},
+            {"number": 8, "title": "no ci", "mergeable": "MERGEABLE", "headRefName": "sipag/e",
+             "statusCheckRollup": []},
+            {"number": 7, "title": "computing", "mergeable": "UNKNOWN", "headRefName": "sipag/f"},
+            {"number": 3, "title": "labeled", "mergeable": "MERGEABLE", "headRefName": "fix",
+             "labels": [{"name": "sipag"}], "statusCheckRollup": [{"state": "SUCCESS"}]},
+            {"number": 1, "title": "human", "mergeable": "CONFLICTING", "headRefName": "fix"}
+        ]"#;
+        let status = parse_merge_status(json, "sipag/");
+        assert_eq!(
+            status.ready,
+            vec![(3, "labeled".to_string()), (9, "green".to_string())]
+        );
+        assert_eq!(status.conflicting, vec![(4, "conflict".to_string())]);
+        assert_eq!(
+            status.checks_blocked,
+            vec![
+                (5, "running".to_string(), MergeOutcome::SkippedPending),
+                (6, "red".to_string(), MergeOutcome::SkippedFailing),
+                (7, "computing".to_string(), MergeOutcome::SkippedPending),
+                (8, "no ci".to_string(), MergeOutcome::SkippedPending),
+            ]
+        );
+        assert_eq!(
+            parse_merge_status("not json", "sipag/"),
+            MergeStatus::default()
+        );
+    }
+
+    #[test]
+    fn parse_changes_requested_uses_latest_decisive_review() {
+        let json = r#"{"reviews": [
+            {"author": {"login": "alice"}, "state": "CHANGES_REQUESTED"},
+            {"author": {"login": "bob"}, "state": "CHANGES_REQUESTED"},
+            {"author": {"login": "bob"}, "state": "APPROVED"},
+            {"author": {"login": "carol"}, "state": "COMMENTED"},
+            {"author": {"login": "dave"}, "state": "CHANGES_REQUESTED"},
+            {"author": {"login": "dave"}, "state": "COMMENTED"},
+            {"author": {"login": "alice"}, "state": "CHANGES_REQUESTED"}
+        ]}"#;
+        assert_eq!(parse_changes_requested(json), vec!["alice", "dave"]);
+        assert!(parse_changes_requested(r#"{"reviews": []}"#).is_empty());
+        assert!(parse_changes_requested("not json").is_empty());
+    }
+
+    #[test]
+    fn issue_comment_args_target_issue() {
+        assert_eq!(
+            issue_comment_args("o/r", 42, "sipag worker started"),
+            vec![
+                "issue",
+                "comment",
+                "42",
+                "--repo",
+                "o/r",
+                "--body",
+                "sipag worker started"
+            ]
+        );
+    }
+
+    #[test]
+    fn rerequest_review_args_join_reviewers() {
+        let reviewers = vec!["alice".to_string(), "dave".to_string()];
+        assert_eq!(
+            rerequest_review_args("o/r", 12, &reviewers),
+            vec![
+                "pr",
+                "edit",
+                "12",
+                "--repo",
+                "o/r",
+                "--add-reviewer",
+                "alice,dave"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_conflicted_prs_bad_json() {
+        assert!(parse_conflicted_prs("not json", "sipag/").is_empty());
+    }
+
+    #[test]
+    fn sipag_prs_use_configured_prefix() {
+        let json = r#"[
+            {"number": 1, "mergeable": "CONFLICTING", "headRefName": "ai/fix-login", "labels": []},
+            {"number": 2, "mergeable": "CONFLICTING", "headRefName": "sipag/old", "labels": []},
+            {"number": 3, "mergeable": "CONFLICTING", "headRefName": "feature/x",
+             "labels": [{"name": "sipag"}]},
+            {"number": 4, "mergeable": "CONFLICTING", "headRefName": "feature/y", "labels": []}
+        ]"#;
+        // Prefix match or the `sipag` label; nothing else.
+        assert_eq!(parse_conflicted_prs(json, "ai/"), vec![1, 3]);
+        assert_eq!(parse_sipag_prs(json, "ai/").len(), 2);
+        assert_eq!(parse_conflicted_prs(json, "sipag/"), vec![2, 3]);
+        // An empty prefix matches no branch — only the label counts.
+        assert_eq!(parse_conflicted_prs(json, ""), vec![3]);
+    }
+
+    fn gh_output(code: i32, stdout: &str, stderr: &str) -> std::io::Result<Output> {
+        use std::os::unix::process::ExitStatusExt;
+        Ok(Output {
+            status: std::process::ExitStatus::from_raw(code << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: stderr.as_bytes().to_vec(),
+        })
+    }
+
+    #[test]
+    fn gh_json_retries_after_rate_limit() {
+        let mut calls = 0;
+        let mut sleeps = vec![];
+        let value = gh_json_with(
+            &["issue", "list"],
+            |_| {
+                calls += 1;
+                if calls < 3 {
+                    gh_output(1, "", "API rate limit exceeded for user ID 1.")
+                } else {
+                    gh_output(0, r#"[{"number": 4}]"#, "")
+                }
+            },
+            |d| sleeps.push(d),
+        )
+        .unwrap();
+        assert_eq!(value[0]["number"], 4);
+        assert_eq!(calls, 3);
+        assert_eq!(sleeps, vec![RATE_LIMIT_BACKOFF, RATE_LIMIT_BACKOFF * 2]);
+    }
+
+    #[test]
+    fn gh_json_gives_up_after_retries() {
+        let mut calls = 0;
+        let err = gh_json_with(
+            &["pr", "list"],
+            |_| {
+                calls += 1;
+                gh_output(1, "", "You have exceeded a secondary rate limit.")
+            },
+            |_| {},
+        )
+        .unwrap_err();
+        assert_eq!(calls, RATE_LIMIT_RETRIES + 1);
+        assert!(err.to_string().contains("secondary rate limit"));
+    }
+
+    #[test]
+    fn gh_json_other_failures_do_not_retry() {
+        let mut calls = 0;
+        let err = gh_json_with(
+            &["issue", "view"],
+            |_| {
+                calls += 1;
+                gh_output(1, "", "could not resolve to an Issue")
+            },
+            |_| panic!("should not sleep"),
+        )
+        .unwrap_err();
+        assert_eq!(calls, 1);
+        assert_eq!(
+            err.to_string(),
+            "gh issue view failed: could not resolve to an Issue"
+        );
+    }
+
+    #[test]
+    fn parse_pr_details_merge_and_checks() {
+        let v: serde_json::Value = serde_json::from_str(
+            r#"{
+                "number": 12, "title": "Fix", "body": "b", "state": "OPEN",
+                "headRefName": "sipag/pr-12",
+                "mergeable": "MERGEABLE", "mergeStateStatus": "CLEAN",
+                "statusCheckRollup": [
+                    {"__typename": "CheckRun", "status": "COMPLETED", "conclusion": "SUCCESS"},
+                    {"__typename": "CheckRun", "status": "COMPLETED", "conclusion": "SKIPPED"},
+                    {"__typename": "StatusContext", "state": "SUCCESS"}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let pr = parse_pr_details(&v, 12);
+        assert_eq!(pr.head_ref, "sipag/pr-12");
+        assert_eq!(pr.mergeable.as_deref(), Some("MERGEABLE"));
+        assert_eq!(pr.merge_state.as_deref(), Some("CLEAN"));
+        assert_eq!(pr.checks_passing, Some(true));
+
+        // Older payloads without the new fields still parse.
+        let pr = parse_pr_details(&serde_json::json!({"title": "t"}), 3);
+        assert_eq!(pr.number, 3);
+        assert_eq!(pr.mergeable, None);
+        assert_eq!(pr.checks_passing, None);
+    }
+
+    #[test]
+    fn checks_passing_failure_and_pending() {
+        let failed = serde_json::json!([
+            {"status": "IN_PROGRESS", "conclusion": ""},
+            {"status": "COMPLETED", "conclusion": "FAILURE"}
+        ]);
+        assert_eq!(checks_passing(&failed), Some(false));
+        let pending = serde_json::json!([
+            {"status": "COMPLETED", "conclusion": "SUCCESS"},
+            {"state": "PENDING"}
+        ]);
+        assert_eq!(checks_passing(&pending), None);
+        assert_eq!(
+            checks_passing(&serde_json::json!([{"state": "ERROR"}])),
+            Some(false)
+        );
+        assert_eq!(checks_passing(&serde_json::json!([])), None);
+        assert_eq!(checks_passing(&serde_json::Value::Null), None);
+    }
+
+    fn merge_pr_details(state: &str, mergeable: Option<&str>, checks: Option<bool>) -> PrDetails {
+        PrDetails {
+            number: 1,
+            title: String::new(),
+            body: String::new(),
+            state: state.to_string(),
+            head_ref: "sipag/pr-1".to_string(),
+            mergeable: mergeable.map(|m| m.to_string()),
+            merge_state: None,
+            checks_passing: checks,
+        }
+    }
+
+    #[test]
+    fn merge_outcome_matrix() {
+        use MergeOutcome::*;
+        let cases = [
+            ("OPEN", Some("MERGEABLE"), Some(true), Merged),
+            ("OPEN", Some("MERGEABLE"), None, SkippedPending),
+            ("OPEN", Some("MERGEABLE"), Some(false), SkippedFailing),
+            ("OPEN", Some("CONFLICTING"), Some(true), SkippedConflicting),
+            ("OPEN", Some("UNKNOWN"), Some(true), SkippedPending),
+            ("OPEN", None, Some(true), SkippedPending),
+            ("MERGED", Some("MERGEABLE"), Some(true), SkippedClosed),
+        ];
+        for (state, mergeable, checks, want) in cases {
+            assert_eq!(
+                merge_outcome(&merge_pr_details(state, mergeable, checks)),
+                want,
+                "{state} {mergeable:?} {checks:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn merge_if_green_merges_only_green_prs() {
+        let merged = std::cell::Cell::new(0);
+        let merge = || {
+            merged.set(merged.get() + 1);
+            Ok(())
+        };
+        let green = merge_pr_details("OPEN", Some("MERGEABLE"), Some(true));
+        assert_eq!(
+            merge_if_green("o/r", &green, None, merge).unwrap(),
+            MergeOutcome::Merged
+        );
+        assert_eq!(merged.get(), 1);
+
+        for (mergeable, checks) in [
+            (Some("MERGEABLE"), None),
+            (Some("MERGEABLE"), Some(false)),
+            (Some("CONFLICTING"), Some(true)),
+        ] {
+            let pr = merge_pr_details("OPEN", mergeable, checks);
+            assert_ne!(
+                merge_if_green("o/r", &pr, None, merge).unwrap(),
+                MergeOutcome::Merged
+            );
+        }
+        assert_eq!(merged.get(), 1);
+
+        let err = merge_if_green("o/r", &green, None, || anyhow::bail!("merge refused"));
+        assert!(err.unwrap_err().to_string().contains("merge refused"));
+    }
+
+    #[test]
+    fn parse_pr_issue_refs_from_bodies() {
+        let json = r#"[
+            {"number": 120, "body": "Closes #34\nFixes #35"},
+            {"number": 121, "body": "No linked issues"},
+            {"number": 122}
+        ]"#;
+        assert_eq!(parse_pr_issue_refs(json), vec![(120, vec![34, 35])]);
+        assert!(parse_pr_issue_refs("not json").is_empty());
+    }
+
+    #[test]
+    fn post_merge_hook_receives_merge_env() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("env");
+        let hook = format!(
+            "printf '%s %s %s' \"$SIPAG_REPO\" \"$SIPAG_PR\" \"$SIPAG_ISSUES\" > {}",
+            out.display()
+        );
+        let ctx = MergeHookContext {
+            repo: "acme/widgets",
+            pr_num: 42,
+            issues: vec![7, 9],
+        };
+        run_post_merge_hook(&hook, &ctx).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "acme/widgets 42 7,9"
+        );
+
+        let err = run_post_merge_hook("echo nope >&2; exit 3", &ctx).unwrap_err();
+        assert!(err.to_string().contains("nope"), "{err}");
+    }
+
+    #[test]
+    fn merge_if_green_runs_hook_only_after_merging() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("env");
+        let hook = format!(
+            "printf '%s %s %s' \"$SIPAG_REPO\" \"$SIPAG_PR\" \"$SIPAG_ISSUES\" > {}",
+            out.display()
+        );
+        let mut pending = merge_pr_details("OPEN", Some("MERGEABLE"), None);
+        pending.body = "Closes #3".to_string();
+        merge_if_green("acme/widgets", &pending, Some(&hook), || Ok(())).unwrap();
+        assert!(!out.exists());
+
+        let mut green = merge_pr_details("OPEN", Some("MERGEABLE"), Some(true));
+        green.body = "Closes #3\nFixes #5".to_string();
+        merge_if_green("acme/widgets", &green, Some(&hook), || Ok(())).unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "acme/widgets 1 3,5");
+
+        // A failing hook doesn't turn a completed merge into an error.
+        let outcome = merge_if_green("acme/widgets", &green, Some("exit 1"), || Ok(()));
+        assert_eq!(outcome.unwrap(), MergeOutcome::Merged);
+    }
+
+    #[test]
+    fn parse_date_header_from_gh_api_dump() {
+        let dump = "HTTP/2.0 200 OK\r\nContent-Type: application/json\r\ndate: Tue, 06 Jan 2026 10:00:00 GMT\r\n\r\n{\"date\": \"ignored\"}";
+        assert_eq!(
+            parse_date_header(dump),
+            Some("2026-01-06T10:00:00Z".parse().unwrap())
+        );
+        assert_eq!(parse_date_header("HTTP/2.0 200 OK\r\n\r\n"), None);
+    }
+
+    #[test]
+    fn clock_skew_threshold() {
+        let at = chrono::TimeDelta::seconds;
+        assert!(!clock_skew_exceeds_limit(at(0)));
+        assert!(!clock_skew_exceeds_limit(at(60)));
+        assert!(!clock_skew_exceeds_limit(at(-60)));
+        assert!(clock_skew_exceeds_limit(at(61)));
+        assert!(clock_skew_exceeds_limit(at(-300)));
+    }
+}
@@ -5,8 +5,9 @@
 //! 2. **Grace period** — workers started less than 60s ago are assumed alive
 //! 3. **Docker ps** (fallback) — for old workers without heartbeat files
 
-use anyhow::Result;
-use std::path::Path;
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::SystemTime;
 
@@ -15,6 +16,14 @@ use crate::state::{self, WorkerState};
 /// Default staleness threshold if no config is provided.
 const DEFAULT_HEARTBEAT_STALE_SECS: u64 = 90;
 
+/// Failure reason for a worker whose heartbeat went stale. Its container may
+/// still be running; [`kill_stale_containers`] kills it and rewrites the
+/// reason to [`HEARTBEAT_KILLED_REASON`].
+const HEARTBEAT_STALE_REASON: &str = "heartbeat stale — worker presumed dead";
+
+/// Failure reason once a stale worker's container has been killed.
+const HEARTBEAT_KILLED_REASON: &str = "heartbeat stale — container killed";
+
 /// Grace period for workers that just started (no heartbeat file yet).
 const STARTUP_GRACE_SECS: u64 = 60;
 
@@ -44,14 +53,32 @@ fn in_grace_period(started: &str) -> bool {
     }
 }
 
+/// Exit code recorded by the dispatch wrapper once `docker run` has returned.
+fn read_exit_code(state_path: &Path) -> Option<i32> {
+    std::fs::read_to_string(state::exit_file_path(state_path))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
 /// Read all worker state files and return current state of all workers.
 ///
-/// For non-terminal workers, checks liveness via heartbeat files (fast path),
-/// grace period, or Docker ps (fallback for old workers without heartbeats).
+/// For non-terminal workers, first checks for an exit code recorded by the
+/// dispatch wrapper (the container is gone — e.g. timed out), then liveness via
+/// heartbeat files (fast path), grace period, or Docker ps (fallback for old
+/// workers without heartbeats).
 /// Dead workers are marked as failed so `sipag ps` and back-pressure
-/// calculations reflect reality.
+/// calculations reflect reality. Never kills a container: see
+/// [`kill_stale_containers`].
+///
+/// The staleness threshold is the configured `heartbeat_stale`, falling back
+/// to the default when the config can't be read.
 pub fn scan_workers(sipag_dir: &Path) -> Vec<WorkerState> {
-    scan_workers_with_stale_secs(sipag_dir, DEFAULT_HEARTBEAT_STALE_SECS)
+    let stale_secs = crate::config::WorkerConfig::load_quiet(sipag_dir)
+        .map(|cfg| cfg.heartbeat_stale_secs)
+        .unwrap_or(DEFAULT_HEARTBEAT_STALE_SECS);
+    scan_workers_with_stale_secs(sipag_dir, stale_secs)
 }
 
 /// Like `scan_workers` but with a configurable staleness threshold.
@@ -64,6 +91,22 @@ pub fn scan_workers_with_stale_secs(sipag_dir: &Path, stale_secs: u64) -> Vec<Wo
             continue;
         }
 
+        // `docker run` already returned but the worker never wrote a terminal
+        // state — fail it with the reason its exit code implies.
+        if let Some(code) = read_exit_code(&w.file_path) {
+            if let Ok(fresh) = state::read_state(&w.file_path) {
+                if fresh.phase.is_terminal() {
+                    *w = fresh;
+                    continue;
+                }
+            }
+            w.exit_code = Some(code);
+            let log_path = crate::tail::log_path(sipag_dir, &w.repo, w.pr_num);
+            let reason = crate::worker::dispatch::classify_failure(Some(code), &log_path);
+            mark_worker_failed(w, sipag_dir, &now, &reason.to_string());
+            continue;
+        }
+
         // Tier 1: Check heartbeat file (one stat() call — no subprocess).
         match check_heartbeat(&w.file_path, stale_secs) {
             Some(true) => continue, // fresh heartbeat → alive
@@ -75,7 +118,7 @@ pub fn scan_workers_with_stale_secs(sipag_dir: &Path, stale_secs: u64) -> Vec<Wo
                         continue;
                     }
                 }
-                mark_worker_failed(w, sipag_dir, &now, "heartbeat stale — worker presumed dead");
+                mark_worker_failed(w, sipag_dir, &now, HEARTBEAT_STALE_REASON);
                 continue;
             }
             None => {} // no heartbeat file — fall through
@@ -87,14 +130,7 @@ pub fn scan_workers_with_stale_secs(sipag_dir: &Path, stale_secs: u64) -> Vec<Wo
         }
 
         // Tier 3: Fallback to docker ps (backward compat for old workers).
-        let container_name =
-            if w.container_id.is_empty() || w.container_id.chars().all(|c| c.is_ascii_digit()) {
-                let repo_slug = w.repo.replace('/', "--");
-                format!("sipag-{repo_slug}-pr-{}", w.pr_num)
-            } else {
-                w.container_id.clone()
-            };
-        if !crate::docker::is_container_running(&container_name) {
+        if !crate::docker::is_container_running(&container_name(w)) {
             // Re-read to avoid race: container may have written terminal state
             // between our initial read and this check.
             if let Ok(fresh) = state::read_state(&w.file_path) {
@@ -115,6 +151,48 @@ pub fn scan_workers_with_stale_secs(sipag_dir: &Path, stale_secs: u64) -> Vec<Wo
     workers
 }
 
+/// Kill the containers of workers failed for a stale heartbeat, which may
+/// still be running wedged (e.g. hung on a network call), and record the
+/// kill so each container is killed once. Returns how many were killed.
+///
+/// Only the dispatch path calls this, with the configured threshold already
+/// applied by the scan; read-only views never kill containers.
+pub fn kill_stale_containers(workers: &mut [WorkerState], kill: impl Fn(&str)) -> usize {
+    let mut killed = 0;
+    for w in workers.iter_mut() {
+        if w.phase != state::WorkerPhase::Failed
+            || w.error.as_deref() != Some(HEARTBEAT_STALE_REASON)
+        {
+            continue;
+        }
+        kill(&container_name(w));
+        w.error = Some(HEARTBEAT_KILLED_REASON.to_string());
+        let _ = state::write_state(w);
+        killed += 1;
+    }
+    killed
+}
+
+/// `docker kill` a container, ignoring failure (it may already be gone).
+pub fn docker_kill(container: &str) {
+    let _ = Command::new("docker")
+        .args(["kill", container])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Docker container name for a worker. Old state files stored an empty or
+/// numeric container ID, so fall back to the deterministic dispatch name.
+fn container_name(w: &WorkerState) -> String {
+    if w.container_id.is_empty() || w.container_id.chars().all(|c| c.is_ascii_digit()) {
+        let repo_slug = w.repo.replace('/', "--");
+        format!("sipag-{repo_slug}-pr-{}", w.pr_num)
+    } else {
+        w.container_id.clone()
+    }
+}
+
 /// Mark a worker as failed and emit a lifecycle event + lesson.
 fn mark_worker_failed(w: &mut WorkerState, sipag_dir: &Path, now: &str, reason: &str) {
     w.phase = state::WorkerPhase::Failed;
@@ -196,9 +274,10 @@ pub fn cleanup_stale(sipag_dir: &Path, max_age_hours: u64) -> usize {
         };
 
         if age_hours >= max_age_hours && state::remove_state(&w.file_path).is_ok() {
-            // Also remove any orphaned heartbeat file.
+            // Also remove any orphaned heartbeat and exit-code files.
             let heartbeat_path = w.file_path.with_extension("heartbeat");
             let _ = std::fs::remove_file(&heartbeat_path);
+            let _ = std::fs::remove_file(state::exit_file_path(&w.file_path));
             cleaned += 1;
         }
     }
@@ -206,6 +285,224 @@ pub fn cleanup_stale(sipag_dir: &Path, max_age_hours: u64) -> usize {
     cleaned
 }
 
+/// Remove state files for terminal workers that ended at least `max_age_days` ago.
+///
+/// Uses the same age check as the TUI archive view (`state::is_expired`).
+/// With `dry_run`, nothing is deleted. Returns the workers that were (or would
+/// be) removed.
+pub fn prune_states(sipag_dir: &Path, max_age_days: u64, dry_run: bool) -> Vec<WorkerState> {
+    let now = chrono::Utc::now();
+    let mut pruned = Vec::new();
+
+    for w in state::list_all(sipag_dir) {
+        if !w.phase.is_terminal() {
+            continue;
+        }
+        let ended = w
+            .ended
+            .as_deref()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc));
+        if !state::is_expired(ended, now, max_age_days) {
+            continue;
+        }
+        if !dry_run {
+            if state::remove_state(&w.file_path).is_err() {
+                continue;
+            }
+            let _ = std::fs::remove_file(w.file_path.with_extension("heartbeat"));
+            let _ = std::fs::remove_file(state::exit_file_path(&w.file_path));
+        }
+        pruned.push(w);
+    }
+
+    pruned
+}
+
+/// Forget every terminal worker of `repo` that covered `issue_num`, so the
+/// issue can be dispatched again from scratch.
+///
+/// Refuses (without removing anything) while a non-terminal worker still
+/// covers the issue. Returns the workers whose state files were removed.
+pub fn reopen_issue(sipag_dir: &Path, repo: &str, issue_num: u64) -> Result<Vec<WorkerState>> {
+    let covering: Vec<WorkerState> = state::list_all(sipag_dir)
+        .into_iter()
+        .filter(|w| w.repo == repo && w.issues.contains(&issue_num))
+        .collect();
+    if let Some(w) = covering.iter().find(|w| !w.phase.is_terminal()) {
+        anyhow::bail!(
+            "issue #{issue_num} on {repo} is still being worked on by PR #{} ({}); \
+             kill that worker first",
+            w.pr_num,
+            w.phase
+        );
+    }
+    for w in &covering {
+        state::remove_state(&w.file_path)?;
+        let _ = std::fs::remove_file(w.file_path.with_extension("heartbeat"));
+        let _ = std::fs::remove_file(state::exit_file_path(&w.file_path));
+    }
+    Ok(covering)
+}
+
+/// Remove worker log files in `logs/` last modified more than `max_age_days`
+/// ago, independent of state retention. Logs of non-terminal workers are
+/// kept regardless of age. `max_age_days == 0` disables pruning.
+///
+/// Returns the removed (or, with `dry_run`, removable) paths.
+pub fn prune_logs(sipag_dir: &Path, max_age_days: u64, dry_run: bool) -> Vec<PathBuf> {
+    if max_age_days == 0 {
+        return Vec::new();
+    }
+    let Ok(entries) = std::fs::read_dir(sipag_dir.join("logs")) else {
+        return Vec::new();
+    };
+    let active: Vec<PathBuf> = state::list_all(sipag_dir)
+        .iter()
+        .filter(|w| !w.phase.is_terminal())
+        .map(|w| crate::tail::log_path(sipag_dir, &w.repo, w.pr_num))
+        .collect();
+    let max_age = std::time::Duration::from_secs(max_age_days * 86_400);
+    let now = SystemTime::now();
+
+    let mut pruned: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "log"))
+        .filter(|p| !active.contains(p))
+        .filter(|p| {
+            std::fs::metadata(p)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|mtime| now.duration_since(mtime).ok())
+                .is_some_and(|age| age > max_age)
+        })
+        .collect();
+    if !dry_run {
+        pruned.retain(|p| std::fs::remove_file(p).is_ok());
+    }
+    pruned.sort();
+    pruned
+}
+
+/// Group the `.log` files among `logs` (path, mtime) that are older than
+/// `max_age_days` into per-day bundles, keyed by the UTC date of their mtime.
+/// Logs in `active` (non-terminal workers) are never selected.
+/// `max_age_days == 0` selects nothing.
+pub fn select_logs_to_archive(
+    logs: &[(PathBuf, SystemTime)],
+    active: &[PathBuf],
+    max_age_days: u64,
+    now: SystemTime,
+) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut bundles: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    if max_age_days == 0 {
+        return bundles;
+    }
+    let max_age = std::time::Duration::from_secs(max_age_days * 86_400);
+    for (path, mtime) in logs {
+        if path.extension().is_none_or(|ext| ext != "log") || active.contains(path) {
+            continue;
+        }
+        if now.duration_since(*mtime).is_ok_and(|age| age > max_age) {
+            let day = chrono::DateTime::<chrono::Utc>::from(*mtime)
+                .format("%Y-%m-%d")
+                .to_string();
+            bundles.entry(day).or_default().push(path.clone());
+        }
+    }
+    for paths in bundles.values_mut() {
+        paths.sort();
+    }
+    bundles
+}
+
+/// Move worker logs older than `max_age_days` into gzipped per-day bundles,
+/// `logs/archive/<date>.tar.gz` (`<date>.<n>.tar.gz` if that already exists),
+/// and remove the originals. Logs of non-terminal workers stay in place.
+///
+/// Returns the bundles written.
+pub fn archive_old_logs(
+    sipag_dir: &Path,
+    max_age_days: u64,
+    now: SystemTime,
+) -> Result<Vec<PathBuf>> {
+    let logs_dir = sipag_dir.join("logs");
+    let Ok(entries) = std::fs::read_dir(&logs_dir) else {
+        return Ok(Vec::new());
+    };
+    let logs: Vec<(PathBuf, SystemTime)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| Some((e.path(), e.metadata().ok()?.modified().ok()?)))
+        .collect();
+    let active: Vec<PathBuf> = state::list_all(sipag_dir)
+        .iter()
+        .filter(|w| !w.phase.is_terminal())
+        .map(|w| crate::tail::log_path(sipag_dir, &w.repo, w.pr_num))
+        .collect();
+
+    let archive_dir = logs_dir.join("archive");
+    let mut written = Vec::new();
+    for (day, paths) in select_logs_to_archive(&logs, &active, max_age_days, now) {
+        std::fs::create_dir_all(&archive_dir)?;
+        let bundle = (1..)
+            .map(|n| match n {
+                1 => archive_dir.join(format!("{day}.tar.gz")),
+                n => archive_dir.join(format!("{day}.{n}.tar.gz")),
+            })
+            .find(|p| !p.exists())
+            .expect("unbounded range");
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&bundle)
+            .arg("-C")
+            .arg(&logs_dir)
+            .args(paths.iter().filter_map(|p| p.file_name()))
+            .stdout(Stdio::null())
+            .status()
+            .context("Failed to run tar")?;
+        if !status.success() {
+            let _ = std::fs::remove_file(&bundle);
+            anyhow::bail!("tar failed writing {}", bundle.display());
+        }
+        for path in &paths {
+            let _ = std::fs::remove_file(path);
+        }
+        written.push(bundle);
+    }
+    Ok(written)
+}
+
+/// Contents of the most recently archived log whose file name satisfies
+/// `matches`, searching the bundles in `logs/archive/` newest first.
+pub fn read_archived_log(sipag_dir: &Path, matches: impl Fn(&str) -> bool) -> Option<String> {
+    let mut bundles: Vec<(SystemTime, PathBuf)> =
+        std::fs::read_dir(sipag_dir.join("logs").join("archive"))
+            .ok()?
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tar.gz"))
+            .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+            .collect();
+    bundles.sort_by(|a, b| b.cmp(a));
+
+    bundles.iter().find_map(|(_, bundle)| {
+        let listing = Command::new("tar").arg("-tzf").arg(bundle).output().ok()?;
+        let member = String::from_utf8_lossy(&listing.stdout)
+            .lines()
+            .find(|m| matches(m))?
+            .to_string();
+        let out = Command::new("tar")
+            .arg("-xzOf")
+            .arg(bundle)
+            .arg(&member)
+            .output()
+            .ok()?;
+        out.status
+            .success()
+            .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,6 +522,8 @@ mod tests {
             ended: None,
             exit_code: None,
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: state::state_file_path(dir, "owner/repo", pr_num),
         };
         state::write_state(&state).unwrap();
@@ -283,6 +582,73 @@ mod tests {
         assert_eq!(workers[0].phase, WorkerPhase::Working); // still alive
     }
 
+    #[test]
+    fn heartbeat_stale_fails_worker() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        let w = make_worker(dir.path(), 11, WorkerPhase::Working, "2020-01-01T00:00:00Z");
+
+        let heartbeat_path = w.file_path.with_extension("heartbeat");
+        std::fs::write(&heartbeat_path, "{}").unwrap();
+        let old = SystemTime::now() - std::time::Duration::from_secs(600);
+        std::fs::File::options()
+            .write(true)
+            .open(&heartbeat_path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        let mut workers = scan_workers_with_stale_secs(dir.path(), 90);
+        assert_eq!(workers[0].phase, WorkerPhase::Failed);
+        assert_eq!(workers[0].error.as_deref(), Some(HEARTBEAT_STALE_REASON));
+
+        // The dispatch path kills the container once and records it.
+        let killed = std::cell::RefCell::new(Vec::new());
+        let kill = |name: &str| killed.borrow_mut().push(name.to_string());
+        assert_eq!(kill_stale_containers(&mut workers, kill), 1);
+        assert_eq!(*killed.borrow(), ["abc"]);
+        assert_eq!(workers[0].error.as_deref(), Some(HEARTBEAT_KILLED_REASON));
+        let reread = state::read_state(&workers[0].file_path).unwrap();
+        assert_eq!(reread.error.as_deref(), Some(HEARTBEAT_KILLED_REASON));
+
+        let mut workers = scan_workers_with_stale_secs(dir.path(), 90);
+        assert_eq!(kill_stale_containers(&mut workers, kill), 0);
+        assert_eq!(killed.borrow().len(), 1);
+    }
+
+    #[test]
+    fn scan_workers_uses_configured_stale_threshold() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        std::fs::write(dir.path().join("config"), "heartbeat_stale=900\n").unwrap();
+        let w = make_worker(dir.path(), 12, WorkerPhase::Working, "2020-01-01T00:00:00Z");
+
+        let heartbeat_path = w.file_path.with_extension("heartbeat");
+        std::fs::write(&heartbeat_path, "{}").unwrap();
+        let old = SystemTime::now() - std::time::Duration::from_secs(600);
+        std::fs::File::options()
+            .write(true)
+            .open(&heartbeat_path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        // 600s old is stale by the 90s default but fresh under heartbeat_stale=900.
+        assert_eq!(scan_workers(dir.path())[0].phase, WorkerPhase::Working);
+    }
+
+    #[test]
+    fn container_name_falls_back_for_legacy_ids() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        let mut w = make_worker(dir.path(), 7, WorkerPhase::Working, "2026-01-01T00:00:00Z");
+        assert_eq!(container_name(&w), "abc");
+        w.container_id = "12345".to_string();
+        assert_eq!(container_name(&w), "sipag-owner--repo-pr-7");
+        w.container_id.clear();
+        assert_eq!(container_name(&w), "sipag-owner--repo-pr-7");
+    }
+
     #[test]
     fn check_heartbeat_returns_none_when_missing() {
         let dir = TempDir::new().unwrap();
@@ -321,6 +687,31 @@ mod tests {
         assert_eq!(workers[0].phase, WorkerPhase::Finished);
     }
 
+    #[test]
+    fn scan_workers_fails_worker_with_timeout_exit_code() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        let w = make_worker(dir.path(), 7, WorkerPhase::Working, "2026-01-01T00:00:00Z");
+        std::fs::write(state::exit_file_path(&w.file_path), "124\n").unwrap();
+
+        let workers = scan_workers(dir.path());
+        assert_eq!(workers[0].phase, WorkerPhase::Failed);
+        assert_eq!(workers[0].exit_code, Some(124));
+        assert_eq!(workers[0].error.as_deref(), Some("timed out"));
+    }
+
+    #[test]
+    fn scan_workers_keeps_terminal_state_despite_exit_code() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        let w = make_worker(dir.path(), 8, WorkerPhase::Finished, "2026-01-01T00:00:00Z");
+        std::fs::write(state::exit_file_path(&w.file_path), "0\n").unwrap();
+
+        let workers = scan_workers(dir.path());
+        assert_eq!(workers[0].phase, WorkerPhase::Finished);
+        assert_eq!(workers[0].error, None);
+    }
+
     #[test]
     fn cleanup_stale_removes_heartbeat_files() {
         let dir = TempDir::new().unwrap();
@@ -338,4 +729,174 @@ mod tests {
         assert_eq!(cleaned, 1);
         assert!(!heartbeat_path.exists());
     }
+
+    #[test]
+    fn prune_states_removes_only_old_terminal_workers() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+
+        let mut old = make_worker(dir.path(), 1, WorkerPhase::Failed, "2020-01-01T00:00:00Z");
+        old.ended = Some("2020-01-01T01:00:00Z".to_string());
+        state::write_state(&old).unwrap();
+
+        let recent_ts = chrono::Utc::now().to_rfc3339();
+        let mut recent = make_worker(dir.path(), 2, WorkerPhase::Finished, &recent_ts);
+        recent.ended = Some(recent_ts.clone());
+        state::write_state(&recent).unwrap();
+
+        let active = make_worker(dir.path(), 3, WorkerPhase::Working, "2020-01-01T00:00:00Z");
+
+        let pruned = prune_states(dir.path(), 7, false);
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].pr_num, 1);
+        assert!(!old.file_path.exists());
+        assert!(recent.file_path.exists());
+        assert!(active.file_path.exists());
+    }
+
+    #[test]
+    fn prune_states_dry_run_keeps_files() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+
+        let mut old = make_worker(dir.path(), 1, WorkerPhase::Finished, "2020-01-01T00:00:00Z");
+        old.ended = Some("2020-01-01T01:00:00Z".to_string());
+        state::write_state(&old).unwrap();
+
+        let pruned = prune_states(dir.path(), 7, true);
+        assert_eq!(pruned.len(), 1);
+        assert!(old.file_path.exists());
+    }
+
+    #[test]
+    fn prune_logs_selects_old_logs_of_inactive_workers() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        std::fs::create_dir_all(dir.path().join("logs")).unwrap();
+        let ten_days_ago = SystemTime::now() - std::time::Duration::from_secs(10 * 86_400);
+        let write_log = |pr_num: u64, mtime: SystemTime| {
+            let path = crate::tail::log_path(dir.path(), "owner/repo", pr_num);
+            std::fs::write(&path, "log\n").unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(mtime)
+                .unwrap();
+            path
+        };
+
+        let old = write_log(1, ten_days_ago);
+        let recent = write_log(2, SystemTime::now());
+        let old_active = write_log(3, ten_days_ago);
+        make_worker(dir.path(), 3, WorkerPhase::Working, "2020-01-01T00:00:00Z");
+
+        assert!(prune_logs(dir.path(), 0, false).is_empty());
+        assert_eq!(prune_logs(dir.path(), 7, true), vec![old.clone()]);
+        assert!(old.exists());
+
+        assert_eq!(prune_logs(dir.path(), 7, false), vec![old.clone()]);
+        assert!(!old.exists());
+        assert!(recent.exists());
+        assert!(old_active.exists());
+        assert!(prune_logs(dir.path(), 30, false).is_empty());
+    }
+
+    #[test]
+    fn reopen_issue_removes_terminal_workers_of_that_issue() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        let done = make_worker(dir.path(), 1, WorkerPhase::Finished, "2026-01-01T00:00:00Z");
+        let mut other = make_worker(dir.path(), 2, WorkerPhase::Failed, "2026-01-01T00:00:00Z");
+        other.issues = vec![7];
+        state::write_state(&other).unwrap();
+
+        let removed = reopen_issue(dir.path(), "owner/repo", 1).unwrap();
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].pr_num, 1);
+        assert!(!done.file_path.exists());
+        assert!(other.file_path.exists());
+
+        // Nothing left to forget, and other repos are untouched.
+        assert!(reopen_issue(dir.path(), "owner/repo", 1)
+            .unwrap()
+            .is_empty());
+        assert!(reopen_issue(dir.path(), "owner/other", 7)
+            .unwrap()
+            .is_empty());
+        assert!(other.file_path.exists());
+    }
+
+    #[test]
+    fn reopen_issue_refuses_while_a_worker_is_running() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        let done = make_worker(dir.path(), 1, WorkerPhase::Finished, "2026-01-01T00:00:00Z");
+        make_worker(dir.path(), 2, WorkerPhase::Working, "2026-01-01T00:00:00Z");
+
+        let err = reopen_issue(dir.path(), "owner/repo", 1).unwrap_err();
+        assert!(err.to_string().contains("PR #2"), "{err}");
+        assert!(done.file_path.exists());
+    }
+
+    #[test]
+    fn select_logs_to_archive_by_age_and_day() {
+        let day = std::time::Duration::from_secs(86_400);
+        let now = SystemTime::UNIX_EPOCH + day * 20_000 + day / 2;
+        let logs = vec![
+            (PathBuf::from("/l/a--pr-1.log"), now - day * 10),
+            (PathBuf::from("/l/a--pr-2.log"), now - day * 10),
+            (PathBuf::from("/l/a--pr-3.log"), now - day * 9),
+            (PathBuf::from("/l/a--pr-4.log"), now - day),
+            (PathBuf::from("/l/a--pr-5.log"), now - day * 30),
+            (PathBuf::from("/l/notes.txt"), now - day * 30),
+        ];
+        let active = vec![PathBuf::from("/l/a--pr-5.log")];
+
+        let bundles = select_logs_to_archive(&logs, &active, 7, now);
+        let days: Vec<&String> = bundles.keys().collect();
+        // Day 20,000 since the epoch is 2024-10-04.
+        assert_eq!(days, ["2024-09-24", "2024-09-25"]);
+        assert_eq!(
+            bundles["2024-09-24"],
+            vec![
+                PathBuf::from("/l/a--pr-1.log"),
+                PathBuf::from("/l/a--pr-2.log")
+            ]
+        );
+        assert_eq!(bundles["2024-09-25"], vec![PathBuf::from("/l/a--pr-3.log")]);
+
+        assert!(select_logs_to_archive(&logs, &active, 0, now).is_empty());
+    }
+
+    #[test]
+    fn archive_old_logs_bundles_and_reads_back() {
+        let dir = TempDir::new().unwrap();
+        let logs = dir.path().join("logs");
+        std::fs::create_dir_all(&logs).unwrap();
+        let old = crate::tail::log_path(dir.path(), "owner/repo", 1);
+        let recent = crate::tail::log_path(dir.path(), "owner/repo", 2);
+        std::fs::write(&old, "old run\n").unwrap();
+        std::fs::write(&recent, "recent run\n").unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(10 * 86_400);
+        std::fs::File::options()
+            .write(true)
+            .open(&recent)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        let bundles = archive_old_logs(dir.path(), 7, later).unwrap();
+        assert_eq!(bundles.len(), 1);
+        assert!(bundles[0].starts_with(logs.join("archive")));
+        assert!(!old.exists());
+        assert!(recent.exists());
+
+        let content = read_archived_log(dir.path(), |m| m.ends_with("--pr-1.log"));
+        assert_eq!(content.as_deref(), Some("old run\n"));
+        assert_eq!(
+            read_archived_log(dir.path(), |m| m.ends_with("--pr-9.log")),
+            None
+        );
+    }
 }
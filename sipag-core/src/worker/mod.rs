@@ -1,5 +1,8 @@
-//! Worker orchestration — dispatch, GitHub operations, lifecycle.
+//! Worker orchestration — candidate selection, dispatch, GitHub operations, lifecycle, branch protection, status.
 
+pub mod candidates;
 pub mod dispatch;
 pub mod github;
 pub mod lifecycle;
+pub mod protection;
+pub mod status;
@@ -0,0 +1,137 @@
+//! Branch protection probe — warn before dispatch when a repo's rules are
+//! ones a sipag worker can't satisfy on its own.
+//!
+//! Reads `gh api repos/{repo}/branches/{branch}/protection` and turns the
+//! response into human-readable warnings. Nothing here blocks dispatch; the
+//! point is to set expectations rather than let PRs sit blocked silently.
+
+use std::process::Command;
+
+/// What a branch's protection rules demand that a worker can't provide.
+#[derive(Debug, Default, PartialEq)]
+pub struct ProtectionAssessment {
+    /// Commits must be signed; worker commits are unsigned.
+    pub requires_signed_commits: bool,
+    /// A code owner must approve.
+    pub requires_code_owner_review: bool,
+    /// Number of approving reviews required before merge.
+    pub required_approvals: u64,
+    /// Required status check names (informational).
+    pub required_checks: Vec<String>,
+}
+
+impl ProtectionAssessment {
+    /// Warnings to show the operator, one per unmet-by-sipag requirement.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        if self.requires_signed_commits {
+            out.push(
+                "requires signed commits — worker commits are unsigned, so the PR cannot merge as-is"
+                    .to_string(),
+            );
+        }
+        if self.requires_code_owner_review {
+            out.push("requires a code owner review before merge".to_string());
+        }
+        if self.required_approvals > 0 {
+            out.push(format!(
+                "requires {} approving review(s) before merge",
+                self.required_approvals
+            ));
+        }
+        if !self.required_checks.is_empty() {
+            out.push(format!(
+                "requires status checks: {}",
+                self.required_checks.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+/// Parse a branch protection API response into an assessment.
+pub fn parse_protection(json: &str) -> ProtectionAssessment {
+    let v: serde_json::Value = serde_json::from_str(json).unwrap_or_default();
+    let reviews = &v["required_pull_request_reviews"];
+    let mut required_checks: Vec<String> = v["required_status_checks"]["contexts"]
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|c| c.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    required_checks.sort();
+    required_checks.dedup();
+
+    ProtectionAssessment {
+        requires_signed_commits: v["required_signatures"]["enabled"]
+            .as_bool()
+            .unwrap_or(false),
+        requires_code_owner_review: reviews["require_code_owner_reviews"]
+            .as_bool()
+            .unwrap_or(false),
+        required_approvals: reviews["required_approving_review_count"]
+            .as_u64()
+            .unwrap_or(0),
+        required_checks,
+    }
+}
+
+/// Fetch and assess protection for `branch` on `repo`.
+///
+/// Returns `None` when the branch is unprotected or the API call fails
+/// (e.g. the token lacks admin scope) — absence of data is not a warning.
+pub fn check_branch_protection(repo: &str, branch: &str) -> Option<ProtectionAssessment> {
+    let output = Command::new("gh")
+        .args(["api", &format!("repos/{repo}/branches/{branch}/protection")])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(parse_protection(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_full_protection() {
+        let json = r#"{
+            "required_signatures": {"enabled": true},
+            "required_pull_request_reviews": {
+                "require_code_owner_reviews": true,
+                "required_approving_review_count": 2
+            },
+            "required_status_checks": {"strict": true, "contexts": ["test", "lint", "test"]}
+        }"#;
+        let a = parse_protection(json);
+        assert_eq!(
+            a,
+            ProtectionAssessment {
+                requires_signed_commits: true,
+                requires_code_owner_review: true,
+                required_approvals: 2,
+                required_checks: vec!["lint".to_string(), "test".to_string()],
+            }
+        );
+        assert_eq!(a.warnings().len(), 4);
+        assert!(a.warnings()[0].contains("signed commits"));
+    }
+
+    #[test]
+    fn parse_minimal_protection_has_no_warnings() {
+        let json =
+            r#"{"required_signatures": {"enabled": false}, "enforce_admins": {"enabled": false}}"#;
+        let a = parse_protection(json);
+        assert_eq!(a, ProtectionAssessment::default());
+        assert!(a.warnings().is_empty());
+    }
+
+    #[test]
+    fn parse_garbage_is_empty() {
+        assert_eq!(parse_protection("nope"), ProtectionAssessment::default());
+    }
+}
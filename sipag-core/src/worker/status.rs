@@ -0,0 +1,240 @@
+//! Per-repo summary for `sipag status`.
+//!
+//! Combines GitHub counts (ready issues, open sipag PRs, conflicted PRs) with
+//! local worker state (active workers and the issues they cover) into a
+//! single "where do things stand" snapshot.
+
+use anyhow::Result;
+
+use super::{candidates, github};
+use crate::config::WorkerConfig;
+use crate::state::WorkerState;
+
+/// Summary counts for one repo.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoStatus {
+    pub repo: String,
+    /// Open issues carrying the work label, ascending.
+    pub ready_issues: Vec<u64>,
+    /// Issues referenced by non-terminal workers for this repo.
+    pub in_progress_issues: usize,
+    /// Non-terminal workers for this repo.
+    pub active_workers: usize,
+    /// Open PRs labeled `sipag`.
+    pub open_sipag_prs: usize,
+    /// Open sipag PRs GitHub reports as conflicting.
+    pub conflicted_prs: Vec<u64>,
+    /// Ready issues that would not be dispatched, and why.
+    pub skipped_issues: Vec<(u64, SkipReason)>,
+}
+
+/// Why a ready issue is not worth dispatching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// An open PR already closes the issue.
+    OpenPr(u64),
+    /// A running worker (for the given PR) already covers the issue.
+    ActiveWorker(u64),
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpenPr(pr) => write!(f, "open PR #{pr} already addresses it"),
+            Self::ActiveWorker(pr) => write!(f, "worker for PR #{pr} is already on it"),
+        }
+    }
+}
+
+/// Why `issue_num` on `repo` would be skipped, if it would be. A running
+/// worker takes precedence over an open PR, since it is the more recent
+/// activity.
+///
+/// `open_prs` holds each open PR with the issues it closes (see
+/// [`github::OpenPrs::issue_refs`]).
+pub fn explain_skip(
+    repo: &str,
+    issue_num: u64,
+    open_prs: &[(u64, Vec<u64>)],
+    workers: &[WorkerState],
+) -> Option<SkipReason> {
+    if let Some(w) = workers
+        .iter()
+        .find(|w| w.repo == repo && !w.phase.is_terminal() && w.issues.contains(&issue_num))
+    {
+        return Some(SkipReason::ActiveWorker(w.pr_num));
+    }
+    open_prs
+        .iter()
+        .find(|(_, issues)| issues.contains(&issue_num))
+        .map(|&(pr, _)| SkipReason::OpenPr(pr))
+}
+
+/// Gather a status snapshot for `repo` from GitHub and the given worker states.
+///
+/// Ready issues are the repo's dispatch candidates under `cfg` (work label,
+/// assignee, required labels).
+pub fn repo_status(repo: &str, cfg: &WorkerConfig, workers: &[WorkerState]) -> Result<RepoStatus> {
+    let ready_issues = candidates::ready_candidates(repo, cfg)?;
+    let prs = github::list_open_prs(repo)?;
+    let open_sipag_prs = prs.sipag_count(&cfg.branch_prefix);
+    let conflicted_prs = prs.conflicted(&cfg.branch_prefix);
+    let (active_workers, in_progress_issues) = local_counts(repo, workers);
+    let open_prs = prs.issue_refs();
+    let skipped_issues = ready_issues
+        .iter()
+        .filter_map(|&n| explain_skip(repo, n, &open_prs, workers).map(|r| (n, r)))
+        .collect();
+    Ok(RepoStatus {
+        repo: repo.to_string(),
+        ready_issues,
+        in_progress_issues,
+        active_workers,
+        open_sipag_prs,
+        conflicted_prs,
+        skipped_issues,
+    })
+}
+
+impl RepoStatus {
+    /// JSON form used by `sipag status --json`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "repo": self.repo,
+            "ready_issues": self.ready_issues,
+            "in_progress_issues": self.in_progress_issues,
+            "active_workers": self.active_workers,
+            "open_sipag_prs": self.open_sipag_prs,
+            "conflicted_prs": self.conflicted_prs,
+            "skipped_issues": self.skipped_issues.iter().map(|(n, reason)| {
+                serde_json::json!({ "issue": n, "reason": reason.to_string() })
+            }).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Build the `sipag status --json` report for several repos.
+///
+/// `has_pending_work` is true when any repo has ready issues, so CI can gate
+/// on a single field.
+pub fn status_report_json(statuses: &[RepoStatus]) -> serde_json::Value {
+    serde_json::json!({
+        "has_pending_work": statuses.iter().any(|s| !s.ready_issues.is_empty()),
+        "repos": statuses.iter().map(RepoStatus::to_json).collect::<Vec<_>>(),
+    })
+}
+
+/// Count active workers and the distinct issues they cover for `repo`.
+fn local_counts(repo: &str, workers: &[WorkerState]) -> (usize, usize) {
+    let active: Vec<&WorkerState> = workers
+        .iter()
+        .filter(|w| w.repo == repo && !w.phase.is_terminal())
+        .collect();
+    let mut issues: Vec<u64> = active.iter().flat_map(|w| w.issues.clone()).collect();
+    issues.sort_unstable();
+    issues.dedup();
+    (active.len(), issues.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::WorkerPhase;
+    use std::path::PathBuf;
+
+    fn worker(repo: &str, pr_num: u64, phase: WorkerPhase, issues: Vec<u64>) -> WorkerState {
+        WorkerState {
+            repo: repo.to_string(),
+            pr_num,
+            issues,
+            branch: String::new(),
+            container_id: String::new(),
+            phase,
+            heartbeat: String::new(),
+            started: String::new(),
+            ended: None,
+            exit_code: None,
+            error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
+            file_path: PathBuf::new(),
+        }
+    }
+
+    fn status(repo: &str, ready: Vec<u64>, conflicted: Vec<u64>) -> RepoStatus {
+        RepoStatus {
+            repo: repo.to_string(),
+            ready_issues: ready,
+            in_progress_issues: 1,
+            active_workers: 1,
+            open_sipag_prs: 2,
+            conflicted_prs: conflicted,
+            skipped_issues: vec![(5, SkipReason::OpenPr(120))],
+        }
+    }
+
+    #[test]
+    fn status_report_json_multiple_repos() {
+        let report = status_report_json(&[
+            status("acme/app", vec![3, 5, 8], vec![12]),
+            status("acme/lib", vec![], vec![]),
+        ]);
+        assert_eq!(report["has_pending_work"], true);
+        let repos = report["repos"].as_array().unwrap();
+        assert_eq!(repos.len(), 2);
+        assert_eq!(repos[0]["repo"], "acme/app");
+        assert_eq!(repos[0]["ready_issues"], serde_json::json!([3, 5, 8]));
+        assert_eq!(repos[0]["conflicted_prs"], serde_json::json!([12]));
+        assert_eq!(repos[0]["skipped_issues"][0]["issue"], 5);
+        assert_eq!(
+            repos[0]["skipped_issues"][0]["reason"],
+            "open PR #120 already addresses it"
+        );
+        assert_eq!(repos[0]["open_sipag_prs"], 2);
+        assert_eq!(repos[1]["ready_issues"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn status_report_json_no_pending_work() {
+        let report = status_report_json(&[status("acme/lib", vec![], vec![])]);
+        assert_eq!(report["has_pending_work"], false);
+    }
+
+    #[test]
+    fn local_counts_filters_repo_and_phase() {
+        let workers = vec![
+            worker("o/r", 1, WorkerPhase::Working, vec![10, 11]),
+            worker("o/r", 2, WorkerPhase::Starting, vec![11, 12]),
+            worker("o/r", 3, WorkerPhase::Finished, vec![13]),
+            worker("o/other", 4, WorkerPhase::Working, vec![14]),
+        ];
+        assert_eq!(local_counts("o/r", &workers), (2, 3));
+        assert_eq!(local_counts("o/none", &workers), (0, 0));
+    }
+
+    #[test]
+    fn explain_skip_classifies_reasons() {
+        let open_prs = vec![(120, vec![34, 35]), (121, vec![36])];
+        let workers = vec![
+            worker("o/r", 7, WorkerPhase::Working, vec![36]),
+            worker("o/r", 8, WorkerPhase::Finished, vec![37]),
+            worker("o/other", 9, WorkerPhase::Working, vec![38]),
+        ];
+        assert_eq!(
+            explain_skip("o/r", 34, &open_prs, &workers),
+            Some(SkipReason::OpenPr(120))
+        );
+        // A running worker wins over the open PR for the same issue.
+        assert_eq!(
+            explain_skip("o/r", 36, &open_prs, &workers),
+            Some(SkipReason::ActiveWorker(7))
+        );
+        // Finished workers and other repos' workers don't count.
+        assert_eq!(explain_skip("o/r", 37, &open_prs, &workers), None);
+        assert_eq!(explain_skip("o/r", 38, &open_prs, &workers), None);
+        assert_eq!(
+            SkipReason::OpenPr(120).to_string(),
+            "open PR #120 already addresses it"
+        );
+    }
+}
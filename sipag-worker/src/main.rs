@@ -5,16 +5,15 @@
 //! field-name mismatches and argument-order bugs by construction.
 
 use anyhow::{bail, Context, Result};
+use sipag_core::prompt;
 use sipag_core::state::{self, WorkerPhase};
+use sipag_core::worker::github;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::time::{Duration, Instant};
 
-/// Worker disposition prompt (embedded at compile time).
-const WORKER_PROMPT: &str = include_str!("../../lib/prompts/worker.md");
-
 /// How often the supervision loop ticks (seconds).
 const TICK_SECS: u64 = 10;
 
@@ -34,12 +33,16 @@ enum PrState {
 }
 
 fn main() {
+    sipag_core::logging::init();
     let code = match run() {
         Ok(code) => code,
         Err(e) => {
             eprintln!("sipag-worker: {e:#}");
-            try_mark_failed(&format!("{e:#}"));
-            1
+            // Exit with the failing command's own code (e.g. git's 128) so
+            // the host sees more than a generic 1.
+            let code = e.downcast_ref::<CmdFailed>().map_or(1, |f| f.code);
+            try_mark_failed(&format!("{e:#}"), code);
+            code
         }
     };
     std::process::exit(code);
@@ -125,6 +128,8 @@ fn run() -> Result<i32> {
     // Phase: starting (state file already created by host dispatch).
     update_phase(&state_path, WorkerPhase::Starting)?;
 
+    write_heartbeat(&state_path, &repo, pr_num, "cloning");
+
     // Clone the repo using a credential file so the token never appears in
     // process args (visible in `ps aux`, /proc/PID/cmdline).
     let gh_token = env::var("GH_TOKEN").unwrap_or_default();
@@ -150,17 +155,65 @@ fn run() -> Result<i32> {
             "store --file /tmp/.git-credentials",
         ],
     )?;
-    run_cmd(
-        "git",
-        &["clone", &format!("https://github.com/{repo}.git"), "/work"],
-    )?;
-    run_cmd("git", &["-C", "/work", "config", "user.name", "sipag"])?;
-    run_cmd(
-        "git",
-        &["-C", "/work", "config", "user.email", "sipag@localhost"],
-    )?;
-    run_cmd("git", &["-C", "/work", "fetch", "origin", &branch])?;
+    // CLONE_DEPTH makes the clone shallow; unset or 0 keeps full history.
+    // `--no-single-branch` keeps origin/* refs so the PR branch can be
+    // checked out below.
+    let depth = env::var("CLONE_DEPTH")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|&d| d > 0);
+    let repo_url = format!("https://github.com/{repo}.git");
+    let depth_arg = depth.map(|d| format!("--depth={d}"));
+    let mut clone_args = vec!["clone"];
+    if let Some(ref d) = depth_arg {
+        clone_args.extend([d.as_str(), "--no-single-branch"]);
+    }
+    clone_args.extend([repo_url.as_str(), "/work"]);
+    run_cmd("git", &clone_args)?;
+    // Commit identity: the host forwards GIT_AUTHOR_* when configured;
+    // otherwise commits are attributed to the generic sipag user.
+    let git_name = env::var("GIT_AUTHOR_NAME").unwrap_or_else(|_| "sipag".to_string());
+    let git_email = env::var("GIT_AUTHOR_EMAIL").unwrap_or_else(|_| "sipag@localhost".to_string());
+    run_cmd("git", &["-C", "/work", "config", "user.name", &git_name])?;
+    run_cmd("git", &["-C", "/work", "config", "user.email", &git_email])?;
+    let mut fetch_args = vec!["-C", "/work", "fetch", "origin", branch.as_str()];
+    if let Some(ref d) = depth_arg {
+        fetch_args.push(d);
+    }
+    run_cmd("git", &fetch_args)?;
     run_cmd("git", &["-C", "/work", "checkout", &branch])?;
+    if env::var("CLONE_SUBMODULES").is_ok_and(|v| v == "1") {
+        run_cmd(
+            "git",
+            &[
+                "-C",
+                "/work",
+                "submodule",
+                "update",
+                "--init",
+                "--recursive",
+            ],
+        )?;
+    }
+    if env::var("CLONE_LFS").is_ok_and(|v| v == "1") {
+        run_cmd("git", &["-C", "/work", "lfs", "pull"])?;
+    }
+    // Propose-only: make any push fail, whatever Claude attempts.
+    let propose_only = env::var("PROPOSE_ONLY").is_ok_and(|v| v == "1");
+    if propose_only {
+        run_cmd(
+            "git",
+            &[
+                "-C",
+                "/work",
+                "remote",
+                "set-url",
+                "--push",
+                "origin",
+                "no-push://propose-only",
+            ],
+        )?;
+    }
 
     // Sanity check: verify the working tree has a reasonable number of files.
     // A branch created from a broken tree (e.g., API error dropping base_tree)
@@ -174,11 +227,18 @@ fn run() -> Result<i32> {
         );
     }
 
-    // Read PR description as the assignment.
-    let pr_body = get_pr_body(&repo, pr_num)?;
+    // Keep the PR out of reviewers' queues until a human promotes it.
+    if env::var("DRAFT").is_ok_and(|v| v == "1") {
+        mark_pr_draft(&repo, pr_num);
+    }
 
-    // Read lessons from previous workers (if any).
-    let lessons_section = read_lessons_file(&repo);
+    // A prompt_filter_command runs on the host, which hands over the
+    // finished prompt; otherwise assemble it here.
+    let prompt = match env::var("PROMPT_FILE") {
+        Ok(path) if !path.is_empty() => fs::read_to_string(&path)
+            .with_context(|| format!("failed to read filtered prompt {path}"))?,
+        _ => build_prompt(&repo, pr_num, &branch, &state_path, propose_only)?,
+    };
 
     // Phase: working.
     update_phase(&state_path, WorkerPhase::Working)?;
@@ -196,28 +256,6 @@ fn run() -> Result<i32> {
         .unwrap_or(30);
     write_heartbeat(&state_path, &repo, pr_num, "working"); // immediate first heartbeat
 
-    // Build the prompt: PR description + lessons + worker disposition.
-    // Replace placeholders in the worker prompt with actual values.
-    let worker_prompt = WORKER_PROMPT
-        .replace("{BRANCH}", &branch)
-        .replace("{PR_NUM}", &pr_num.to_string())
-        .replace("{REPO}", &repo);
-
-    let prompt = format!(
-        "You are a sipag worker implementing a PR. The PR description below is your\n\
-         complete assignment — it contains the architectural insight, approach, affected\n\
-         issues, and constraints.\n\
-         \n\
-         --- PR DESCRIPTION ---\n\
-         \n\
-         {pr_body}\n\
-         \n\
-         --- END PR DESCRIPTION ---\n\
-         \n\
-         {lessons_section}\
-         {worker_prompt}"
-    );
-
     // Capture HEAD sha before Claude runs for push verification.
     let pre_claude_sha =
         get_head_sha().context("failed to get HEAD SHA — git state may be corrupt")?;
@@ -233,6 +271,10 @@ fn run() -> Result<i32> {
         eprint!("{content}");
     }
 
+    if exit_code == 0 && propose_only {
+        return finish_proposal(&state_path, &repo, pr_num, &pre_claude_sha);
+    }
+
     // Post-run verification: check if commits were actually pushed.
     // If the worker self-merged the PR, the branch is deleted on remote,
     // so we skip push verification (the merge is proof enough).
@@ -252,6 +294,7 @@ fn run() -> Result<i32> {
             s.heartbeat = now;
             s.error =
                 Some("no_changes_pushed: claude exited 0 but no commits were pushed".to_string());
+            record_usage(&mut s);
             state::write_state(&s).context("failed to write state file")?;
             emit_event(
                 "worker-failed",
@@ -266,6 +309,21 @@ fn run() -> Result<i32> {
 
     // Report completion.
     finish_state(&state_path, exit_code)?;
+    if exit_code == 0 && env::var("REREQUEST_REVIEW").is_ok_and(|v| v == "1") {
+        match github::rerequest_review(&repo, pr_num) {
+            Ok(reviewers) if !reviewers.is_empty() => {
+                eprintln!(
+                    "sipag-worker: re-requested review from {}",
+                    reviewers.join(", ")
+                );
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("sipag-worker: failed to re-request review: {e}"),
+        }
+    }
+    if exit_code == 0 && env::var("AUDIT_COMMENTS").is_ok_and(|v| v == "1") {
+        post_audit_comments(&state_path, &repo, pr_num);
+    }
     if exit_code == 0 {
         emit_event(
             "worker-finished",
@@ -286,6 +344,71 @@ fn run() -> Result<i32> {
     Ok(exit_code)
 }
 
+/// Wrap up a propose-only run: save everything Claude changed since
+/// `pre_sha` (commits and uncommitted edits) to `/sipag-proposals`, post it
+/// on the PR as a diff, and record the worker as proposed.
+fn finish_proposal(state_path: &Path, repo: &str, pr_num: u64, pre_sha: &str) -> Result<i32> {
+    run_cmd("git", &["-C", "/work", "add", "-A"])?;
+    let output = Command::new("git")
+        .args(["-C", "/work", "diff", "--cached", "--binary", pre_sha])
+        .output()
+        .context("failed to run git diff")?;
+    let diff = String::from_utf8_lossy(&output.stdout).to_string();
+    if !output.status.success() || diff.trim().is_empty() {
+        try_mark_failed(
+            "no_changes_proposed: claude exited 0 but changed nothing",
+            1,
+        );
+        return Ok(1);
+    }
+
+    let patch_name = format!("{}--pr-{pr_num}.patch", repo.replace('/', "--"));
+    let patch_path = Path::new("/sipag-proposals").join(&patch_name);
+    if let Err(e) = fs::write(&patch_path, &diff) {
+        eprintln!(
+            "sipag-worker: failed to save proposal to {}: {e}",
+            patch_path.display()
+        );
+    }
+    if let Err(e) =
+        github::post_pr_comment(repo, pr_num, &prompt::proposal_comment(&diff, &patch_name))
+    {
+        eprintln!("sipag-worker: failed to post proposal on PR #{pr_num}: {e}");
+    }
+
+    let mut s = state::read_state(state_path).context("failed to read state file")?;
+    let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    s.phase = WorkerPhase::Proposed;
+    s.exit_code = Some(0);
+    s.ended = Some(now.clone());
+    s.heartbeat = now;
+    record_usage(&mut s);
+    state::write_state(&s).context("failed to write state file")?;
+    emit_event(
+        "worker-proposed",
+        repo,
+        pr_num,
+        &format!("Change proposed as {patch_name}"),
+    );
+    remove_heartbeat(state_path);
+    Ok(0)
+}
+
+/// Tell each issue the worker covered that its PR is ready for review.
+fn post_audit_comments(state_path: &Path, repo: &str, pr_num: u64) {
+    let issues = state::read_state(state_path)
+        .map(|s| s.issues)
+        .unwrap_or_default();
+    let comment = format!(
+        "sipag worker finished; changes are ready for review in https://github.com/{repo}/pull/{pr_num}"
+    );
+    for n in issues {
+        if let Err(e) = github::post_issue_comment(repo, n, &comment) {
+            eprintln!("sipag-worker: audit comment on #{n} failed: {e}");
+        }
+    }
+}
+
 /// Read a required environment variable.
 fn required_env(name: &str) -> Result<String> {
     env::var(name).with_context(|| format!("missing required environment variable: {name}"))
@@ -315,9 +438,32 @@ fn finish_state(state_path: &Path, exit_code: i32) -> Result<()> {
     if exit_code != 0 {
         s.error = Some(format!("claude exited with code {exit_code}"));
     }
+    record_usage(&mut s);
     state::write_state(&s).context("failed to write state file")
 }
 
+/// Store this container's peak memory and CPU time in the final state.
+fn record_usage(s: &mut state::WorkerState) {
+    let usage = sipag_core::cgroup::read_usage();
+    s.peak_memory_bytes = usage.peak_memory_bytes;
+    s.cpu_secs = usage.cpu_secs;
+}
+
+/// Convert the PR to a draft (best-effort; already-draft PRs are fine).
+fn mark_pr_draft(repo: &str, pr_num: u64) {
+    let output = Command::new("gh")
+        .args(["pr", "ready", &pr_num.to_string(), "--repo", repo, "--undo"])
+        .output();
+    match output {
+        Ok(o) if !o.status.success() => {
+            let stderr = String::from_utf8_lossy(&o.stderr);
+            eprintln!("sipag-worker: failed to mark PR #{pr_num} as draft: {stderr}");
+        }
+        Err(e) => eprintln!("sipag-worker: failed to mark PR #{pr_num} as draft: {e}"),
+        _ => {}
+    }
+}
+
 /// Get the PR body via `gh pr view`.
 fn get_pr_body(repo: &str, pr_num: u64) -> Result<String> {
     let output = Command::new("gh")
@@ -347,6 +493,21 @@ fn get_pr_body(repo: &str, pr_num: u64) -> Result<String> {
     Ok(body)
 }
 
+/// A command run by [`run_cmd`] exited non-zero.
+#[derive(Debug)]
+struct CmdFailed {
+    program: String,
+    code: i32,
+}
+
+impl std::fmt::Display for CmdFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} exited with code {}", self.program, self.code)
+    }
+}
+
+impl std::error::Error for CmdFailed {}
+
 /// Run a command and bail on failure.
 fn run_cmd(program: &str, args: &[&str]) -> Result<()> {
     let status = Command::new(program)
@@ -355,7 +516,11 @@ fn run_cmd(program: &str, args: &[&str]) -> Result<()> {
         .with_context(|| format!("failed to run {program}"))?;
 
     if !status.success() {
-        bail!("{program} exited with code {}", status.code().unwrap_or(-1));
+        return Err(CmdFailed {
+            program: program.to_string(),
+            code: status.code().unwrap_or(-1),
+        }
+        .into());
     }
     Ok(())
 }
@@ -436,41 +601,63 @@ fn is_pr_merged(repo: &str, pr_num: u64) -> bool {
     check_pr_state(repo, pr_num) == PrState::Merged
 }
 
-/// Max bytes of lessons to include in the worker prompt.
-/// Matches sipag_core::lessons::DEFAULT_MAX_BYTES (8KB ~ 20 lessons).
-const LESSONS_MAX_BYTES: usize = 8 * 1024;
+/// Assemble the worker prompt: PR description (capped so a huge body can't
+/// push the prompt past the model's context), lessons from previous workers,
+/// and the worker disposition with its placeholders filled in.
+fn build_prompt(
+    repo: &str,
+    pr_num: u64,
+    branch: &str,
+    state_path: &Path,
+    propose_only: bool,
+) -> Result<String> {
+    let max_body_chars = env::var("MAX_PR_BODY_CHARS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(prompt::DEFAULT_MAX_PR_BODY_CHARS);
+    let pr_body = prompt::truncate_body(&get_pr_body(repo, pr_num)?, max_body_chars);
 
-/// Read the lessons file for a repo from the mounted lessons directory.
-///
-/// Truncates from the front if the file exceeds LESSONS_MAX_BYTES, cutting at
-/// the nearest `## ` heading boundary so entries stay intact. This prevents old
-/// lessons from bloating the prompt.
-fn read_lessons_file(repo: &str) -> String {
     let repo_slug = repo.replace('/', "--");
-    let path = format!("/sipag-lessons/{repo_slug}.md");
-    match std::fs::read_to_string(&path) {
-        Ok(content) if !content.trim().is_empty() => {
-            let trimmed = if content.len() <= LESSONS_MAX_BYTES {
-                content.trim().to_string()
-            } else {
-                // Truncate from front, keeping last LESSONS_MAX_BYTES at a heading boundary.
-                let start = content.len() - LESSONS_MAX_BYTES;
-                let tail = &content[start..];
-                if let Some(pos) = tail.find("\n## ") {
-                    tail[pos + 1..].trim().to_string()
-                } else {
-                    tail.trim().to_string()
-                }
-            };
-            format!(
-                "## Lessons from previous workers\n\n\
-                 Previous workers for this repo recorded the following lessons.\n\
-                 Avoid repeating their mistakes:\n\n\
-                 {trimmed}\n\n",
-            )
-        }
-        _ => String::new(),
-    }
+    let lessons = fs::read_to_string(format!("/sipag-lessons/{repo_slug}.md")).unwrap_or_default();
+
+    let context_issue_limit = env::var("CONTEXT_ISSUE_LIMIT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(prompt::DEFAULT_CONTEXT_ISSUE_LIMIT);
+    let template = prompt::load_prompt_template(
+        Path::new("/sipag-prompts"),
+        prompt::WORKER_PROMPT_FILE,
+        prompt::WORKER_PROMPT,
+    );
+    let worker_prompt = prompt::render_worker_prompt(
+        &template,
+        &prompt::PromptVars {
+            repo,
+            pr_num,
+            branch,
+            context_issue_limit,
+        },
+    );
+
+    let commit_prefix = env::var("COMMIT_PREFIX").ok();
+    let per_issue_timeout = env::var("PER_ISSUE_TIMEOUT")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(0);
+    let issue_count = state::read_state(state_path)
+        .map(|s| s.issues.len())
+        .unwrap_or(0);
+    Ok(prompt::assemble_worker_prompt(
+        &worker_prompt,
+        &prompt::PromptParts {
+            pr_body: &pr_body,
+            lessons: &prompt::lessons_section(&lessons),
+            commit_prefix: commit_prefix.as_deref(),
+            per_issue_timeout,
+            issue_count,
+            propose_only,
+        },
+    ))
 }
 
 /// Start Claude Code inside a tmux session.
@@ -622,7 +809,7 @@ fn supervise_claude(
 }
 
 /// Best-effort attempt to mark the state file as failed on error.
-fn try_mark_failed(error_msg: &str) {
+fn try_mark_failed(error_msg: &str, exit_code: i32) {
     let state_file = match env::var("STATE_FILE") {
         Ok(f) => f,
         Err(_) => return,
@@ -631,10 +818,11 @@ fn try_mark_failed(error_msg: &str) {
     if let Ok(mut s) = state::read_state(&state_path) {
         let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
         s.phase = WorkerPhase::Failed;
-        s.exit_code = Some(1);
+        s.exit_code = Some(exit_code);
         s.ended = Some(now.clone());
         s.heartbeat = now;
         s.error = Some(error_msg.to_string());
+        record_usage(&mut s);
         let _ = state::write_state(&s);
         emit_event("worker-failed", &s.repo, s.pr_num, error_msg);
         remove_heartbeat(&state_path);
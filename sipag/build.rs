@@ -14,6 +14,19 @@ fn main() {
 
     println!("cargo:rustc-env=CARGO_GIT_SHA={hash}");
 
+    // Build timestamp for `sipag version --json`. Left unset when `date` is
+    // unavailable; the binary reports "unknown" in that case.
+    if let Some(built_at) = Command::new("date")
+        .args(["-u", "+%Y-%m-%dT%H:%M:%SZ"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+    {
+        println!("cargo:rustc-env=CARGO_BUILD_TIME={built_at}");
+    }
+
     // Re-run if the git HEAD changes (e.g., new commit).
     println!("cargo:rerun-if-changed=../.git/HEAD");
     println!("cargo:rerun-if-changed=../.git/refs");
@@ -1,11 +1,19 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use sipag_core::{
-    config::{default_sipag_dir, validate_config_file_for_doctor, ConfigEntryStatus, WorkerConfig},
-    docker, init,
+    config::{
+        self, default_sipag_dir, validate_config_file_for_doctor, ConfigEntryStatus, WorkerConfig,
+    },
+    docker, drain, init, kick, repo,
     state::{self, format_duration},
-    worker::{dispatch, github, lifecycle},
+    stats, tail,
+    worker::{
+        candidates, dispatch,
+        github::{self, extract_issue_nums},
+        lifecycle, protection, status,
+    },
 };
+use std::io::IsTerminal;
 use std::path::PathBuf;
 use std::process::Command;
 
@@ -13,6 +21,7 @@ use crate::configure_project;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const GIT_HASH: &str = env!("CARGO_GIT_SHA");
+const BUILD_TIME: Option<&str> = option_env!("CARGO_BUILD_TIME");
 
 #[derive(Parser)]
 #[command(
@@ -33,7 +42,7 @@ pub struct Cli {
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     /// Configure Claude Code agents and commands for a project
-    #[command(alias = "config")]
+    #[command(alias = "config", args_conflicts_with_subcommands = true)]
     Configure {
         /// Target directory (default: current dir)
         #[arg(default_value = ".")]
@@ -42,6 +51,9 @@ pub enum Commands {
         /// Install generic templates without launching Claude
         #[arg(long, default_value_t = false)]
         r#static: bool,
+
+        #[command(subcommand)]
+        action: Option<ConfigAction>,
     },
 
     /// Dispatch a Docker worker for a PR
@@ -49,6 +61,17 @@ pub enum Commands {
         /// PR URL (e.g. https://github.com/owner/repo/pull/42)
         #[arg(value_name = "PR_URL")]
         url: String,
+
+        /// Echo the worker's output to the terminal (prefixed with the PR
+        /// number) until it finishes. Ctrl-C stops following; the worker
+        /// keeps running.
+        #[arg(long, default_value_t = false)]
+        verbose: bool,
+
+        /// Dispatch even if a linked issue contains the skip_marker or lacks
+        /// one of the require_labels
+        #[arg(long)]
+        force: bool,
     },
 
     /// List active and recent workers
@@ -61,7 +84,20 @@ pub enum Commands {
     /// Show logs for a worker
     Logs {
         /// Worker identifier (PR number or container name)
-        id: String,
+        #[arg(required_unless_present = "archive")]
+        id: Option<String>,
+
+        /// Bundle logs older than state_max_age_days into
+        /// logs/archive/<date>.tar.gz instead of showing one
+        #[arg(long, conflicts_with = "id")]
+        archive: bool,
+    },
+
+    /// Follow the log of the most recently started running worker, or with a
+    /// repo, all of its running workers interleaved
+    Tail {
+        /// Repository (owner/repo) whose running workers to multiplex
+        repo: Option<String>,
     },
 
     /// Kill a running worker
@@ -70,33 +106,210 @@ pub enum Commands {
         id: String,
     },
 
+    /// Stop dispatching new workers; with --wait, block until running ones finish
+    Drain {
+        /// Repository (owner/repo) to drain; all repos when omitted
+        repo: Option<String>,
+
+        /// Wait until no workers are running
+        #[arg(long)]
+        wait: bool,
+
+        /// Give up waiting after this many seconds and exit non-zero
+        #[arg(long, requires = "wait")]
+        timeout: Option<u64>,
+    },
+
+    /// Clear a drain so dispatch starts workers again
+    Resume {
+        /// Repository (owner/repo) whose drain to clear; the global drain when omitted
+        repo: Option<String>,
+    },
+
+    /// Let the next dispatch start a worker past back-pressure, once
+    Kick {
+        /// Repository (owner/repo) to kick; any repo's next dispatch when omitted
+        repo: Option<String>,
+    },
+
+    /// Forget finished/failed workers for an issue and re-apply the work
+    /// label so it can be dispatched again
+    #[command(alias = "retry")]
+    Reopen {
+        /// Repository (owner/repo)
+        repo: String,
+        /// Issue number
+        #[arg(value_parser = parse_issue_num, allow_hyphen_values = true)]
+        issue: u64,
+    },
+
+    /// Open an issue's PR in the browser, or the issue itself when no
+    /// worker has opened a PR for it yet
+    Open {
+        /// Repository (owner/repo)
+        repo: String,
+        /// Issue number
+        #[arg(value_parser = parse_issue_num, allow_hyphen_values = true)]
+        issue: u64,
+    },
+
+    /// Show a summary of issues, PRs, and workers for a repo
+    Status {
+        /// Repositories (owner/repo). Defaults to the current directory's
+        /// GitHub remote; with no repo on a terminal, launches the TUI.
+        repos: Vec<String>,
+
+        /// Emit a machine-readable JSON report (for CI gating)
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List open sipag PRs: ready, conflicting, or blocked on checks
+    MergeStatus {
+        /// Repository (owner/repo)
+        repo: String,
+
+        /// Squash-merge the ready PRs (each is re-checked just before merging)
+        #[arg(long)]
+        merge: bool,
+    },
+
+    /// Show aggregate worker statistics
+    Stats {
+        /// Only include workers for this repository (owner/repo)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Only include workers started in the last N days
+        #[arg(long)]
+        days: Option<u64>,
+
+        /// Output format: the aggregate as a table or JSON, or one CSV row
+        /// per finished/failed worker
+        #[arg(long, value_enum, default_value_t = StatsFormat::Table)]
+        format: StatsFormat,
+    },
+
+    /// Remove state files for old finished/failed workers
+    Prune {
+        /// Age threshold in days (default: state_max_age_days from config)
+        #[arg(long)]
+        older_than: Option<u64>,
+
+        /// Show what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Launch interactive TUI
     Tui,
 
     /// Check system prerequisites
-    Doctor,
+    Doctor {
+        /// Emit the checks as JSON instead of the text report
+        #[arg(long)]
+        json: bool,
+    },
 
     /// Print version
-    Version,
+    Version {
+        /// Emit version, git hash, and build time as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatsFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// Check ~/.sipag/config and exit non-zero on any invalid or unknown entry
+    Validate,
+
+    /// Print a key's value from ~/.sipag/config
+    Get {
+        /// Config key (e.g. timeout)
+        key: String,
+    },
+
+    /// Set a key in ~/.sipag/config, keeping comments and other lines
+    Set {
+        /// Config key (e.g. timeout)
+        key: String,
+        /// New value; rejected if `sipag config validate` would flag it
+        value: String,
+    },
+
+    /// List the entries in ~/.sipag/config
+    List,
 }
 
 pub fn run(cli: Cli) -> Result<()> {
     match cli.command {
         None => run_tui(),
+        Some(Commands::Configure {
+            action: Some(ConfigAction::Validate),
+            ..
+        }) => run_config_validate(),
+        Some(Commands::Configure {
+            action: Some(ConfigAction::Get { key }),
+            ..
+        }) => run_config_get(&key),
+        Some(Commands::Configure {
+            action: Some(ConfigAction::Set { key, value }),
+            ..
+        }) => run_config_set(&key, &value),
+        Some(Commands::Configure {
+            action: Some(ConfigAction::List),
+            ..
+        }) => run_config_list(),
         Some(Commands::Configure {
             dir,
             r#static: static_only,
+            action: None,
         }) => configure_project::run_configure(&dir, static_only),
         Some(Commands::Tui) => run_tui(),
-        Some(Commands::Dispatch { url }) => {
+        Some(Commands::Dispatch {
+            url,
+            verbose,
+            force,
+        }) => {
             let (repo, pr) = parse_pr_url(&url)?;
-            run_dispatch(&repo, pr)
+            run_dispatch(&repo, pr, verbose, force)
         }
         Some(Commands::Ps { all }) => run_ps(all),
-        Some(Commands::Logs { id }) => run_logs(&id),
+        Some(Commands::Logs { id, archive }) => match id {
+            Some(id) if !archive => run_logs(&id),
+            _ => run_logs_archive(),
+        },
+        Some(Commands::Tail { repo }) => match repo {
+            Some(repo) => run_tail_repo(&repo),
+            None => run_tail(),
+        },
         Some(Commands::Kill { id }) => run_kill(&id),
-        Some(Commands::Doctor) => run_doctor(),
-        Some(Commands::Version) => run_version(),
+        Some(Commands::Drain {
+            repo,
+            wait,
+            timeout,
+        }) => run_drain(repo.as_deref(), wait, timeout),
+        Some(Commands::Resume { repo }) => run_resume(repo.as_deref()),
+        Some(Commands::Kick { repo }) => run_kick(repo.as_deref()),
+        Some(Commands::Reopen { repo, issue }) => run_reopen(&repo, issue),
+        Some(Commands::Open { repo, issue }) => run_open(&repo, issue),
+        Some(Commands::Status { repos, json }) => run_status(&repos, json),
+        Some(Commands::MergeStatus { repo, merge }) => run_merge_status(&repo, merge),
+        Some(Commands::Stats { repo, days, format }) => run_stats(repo.as_deref(), days, format),
+        Some(Commands::Prune {
+            older_than,
+            dry_run,
+        }) => run_prune(older_than, dry_run),
+        Some(Commands::Doctor { json }) => run_doctor(json),
+        Some(Commands::Version { json }) => run_version(json),
     }
 }
 
@@ -125,7 +338,7 @@ fn parse_pr_url(url: &str) -> Result<(String, u64)> {
     }
 }
 
-fn run_dispatch(repo: &str, pr_num: u64) -> Result<()> {
+fn run_dispatch(repo: &str, pr_num: u64, verbose: bool, force: bool) -> Result<()> {
     let sipag_dir = default_sipag_dir();
     init::init_dirs(&sipag_dir)?;
 
@@ -133,11 +346,24 @@ fn run_dispatch(repo: &str, pr_num: u64) -> Result<()> {
     lifecycle::cleanup_stale(&sipag_dir, 24);
 
     let cfg = WorkerConfig::load(&sipag_dir)?;
+    cfg.check_security_opt()?;
+    lifecycle::prune_logs(&sipag_dir, cfg.log_max_age_days, false);
+
+    match drain::drain_decision(drain::drain_signals(&sipag_dir, repo)) {
+        drain::DrainAction::Dispatch => {}
+        drain::DrainAction::DrainedGlobally => anyhow::bail!(
+            "all repos are draining; not dispatching PR #{pr_num}. Run `sipag resume` \
+             (or `sipag resume {repo}` for just this repo) to dispatch again."
+        ),
+        drain::DrainAction::DrainedRepo => anyhow::bail!(
+            "{repo} is draining; not dispatching PR #{pr_num}. Run `sipag resume {repo}` to dispatch again."
+        ),
+    }
 
     // Preflight checks.
     github::preflight_gh_auth()?;
     docker::preflight_docker_running()?;
-    docker::preflight_docker_image(&cfg.image)?;
+    docker::preflight_docker_image(&cfg.image, cfg.container_platform.as_deref())?;
 
     // Ensure the sipag label exists and is on this PR.
     github::ensure_sipag_label(repo);
@@ -147,14 +373,23 @@ fn run_dispatch(repo: &str, pr_num: u64) -> Result<()> {
     // This reconciles against Docker to detect dead containers, so zombie
     // workers don't inflate the count. Use the configured staleness threshold
     // rather than the hardcoded default so operator tuning is respected.
-    let workers = lifecycle::scan_workers_with_stale_secs(&sipag_dir, cfg.heartbeat_stale_secs);
+    let mut workers = lifecycle::scan_workers_with_stale_secs(&sipag_dir, cfg.heartbeat_stale_secs);
+    // Dispatch is the only place wedged containers get killed; views that
+    // scan workers just mark them failed.
+    lifecycle::kill_stale_containers(&mut workers, lifecycle::docker_kill);
+    // A pending kick lets this dispatch past back-pressure, but it is only
+    // consumed once every later refusal has passed.
+    let mut kicked_past: Option<usize> = None;
     if cfg.max_open_prs > 0 {
         let active = workers.iter().filter(|w| !w.phase.is_terminal()).count();
         if active >= cfg.max_open_prs {
-            anyhow::bail!(
-                "Back-pressure: {active} active workers (max: {}). Wait for workers to finish.",
-                cfg.max_open_prs
-            );
+            if !kick::has_kick(&sipag_dir, repo) {
+                anyhow::bail!(
+                    "Back-pressure: {active} active workers (max: {}). Wait for workers to finish.",
+                    cfg.max_open_prs
+                );
+            }
+            kicked_past = Some(active);
         }
     }
 
@@ -175,7 +410,7 @@ fn run_dispatch(repo: &str, pr_num: u64) -> Result<()> {
             "--repo",
             repo,
             "--json",
-            "headRefName,body",
+            "headRefName,baseRefName,body",
         ])
         .output()
         .context("Failed to run gh pr view")?;
@@ -192,14 +427,156 @@ fn run_dispatch(repo: &str, pr_num: u64) -> Result<()> {
     if branch.is_empty() {
         anyhow::bail!("Could not determine branch for PR #{pr_num}");
     }
+    if !cfg.branch_prefix.is_empty() && !branch.starts_with(&cfg.branch_prefix) {
+        log::info!(
+            "PR branch {branch} does not start with branch_prefix {}; \
+             it is tracked via the sipag label only",
+            cfg.branch_prefix
+        );
+    }
+
+    if cfg.check_pr_requirements {
+        let base = parsed["baseRefName"].as_str().unwrap_or("");
+        if let Some(assessment) = protection::check_branch_protection(repo, base) {
+            for w in assessment.warnings() {
+                log::warn!("{repo}@{base} {w}");
+            }
+        }
+    }
 
     // Extract issue numbers from PR body.
     let issues = extract_issue_nums(&body);
 
+    if dispatch::recently_dispatched(
+        &workers,
+        repo,
+        pr_num,
+        &issues,
+        cfg.dispatch_cooldown,
+        chrono::Utc::now(),
+    ) {
+        anyhow::bail!(
+            "A worker for PR #{pr_num} or one of its issues started less than {}s ago \
+             (dispatch_cooldown); not dispatching again yet",
+            cfg.dispatch_cooldown
+        );
+    }
+
+    // Linked issues' labels and bodies: they must pass the same
+    // require_labels and skip_marker filters as ready issues, and the labels
+    // drive per-label timeouts and the commit prefix.
+    // An issue that can't be read can't be shown to pass those filters,
+    // so fail closed unless --force.
+    let filtered = !cfg.skip_marker.is_empty() || !cfg.require_labels.is_empty();
+    let mut issue_details: Vec<github::IssueDetails> = Vec::new();
+    for &n in &issues {
+        match github::get_issue_details(repo, n) {
+            Ok(details) => issue_details.push(details),
+            Err(e) if filtered && !force => anyhow::bail!(
+                "{e:#}; can't check issue #{n} against skip_marker/require_labels, \
+                 not dispatching PR #{pr_num} (--force overrides)"
+            ),
+            Err(e) => {
+                log::warn!("{e:#}");
+                issue_details.push(Default::default());
+            }
+        }
+    }
+    if !force {
+        for (&n, d) in issues.iter().zip(&issue_details) {
+            if let Some(why) = candidates::dispatch_refusal(n, &d.labels, &d.body, &cfg) {
+                anyhow::bail!("{why}; not dispatching PR #{pr_num} (--force overrides)");
+            }
+        }
+    }
+    let issue_labels: Vec<Vec<String>> = issue_details.into_iter().map(|d| d.labels).collect();
+    let mut cfg = cfg;
+    cfg.timeout = cfg.timeout_for_labels(&issue_labels.concat());
+    let commit_prefix = dispatch::resolve_commit_prefix(&issue_labels, &cfg.commit_prefixes);
+
+    // prompt_filter_command runs here, on the host, so a failing filter
+    // stops dispatch before any container starts.
+    dispatch::prepare_filtered_prompt(
+        &dispatch::PromptRequest {
+            repo,
+            pr_num,
+            branch: &branch,
+            pr_body: &body,
+            issues: &issues,
+            commit_prefix: commit_prefix.as_deref(),
+        },
+        &cfg,
+    )?;
+
     // Load credentials.
-    let creds = sipag_core::config::Credentials::load(&sipag_dir)?;
+    let creds = sipag_core::config::Credentials::load_for_repo(&sipag_dir, repo)?;
 
-    dispatch::dispatch_worker(repo, pr_num, &branch, &issues, &cfg, &creds)?;
+    if let Some(active) = kicked_past {
+        // Removing the signal file is the claim: of two dispatches that both
+        // saw the kick, only one removes it.
+        if !kick::take_kick(&sipag_dir, repo) {
+            anyhow::bail!(
+                "Back-pressure: {active} active workers (max: {}). Wait for workers to finish \
+                 (the kick was already used by another dispatch).",
+                cfg.max_open_prs
+            );
+        }
+        log::info!(
+            "kick signal consumed; dispatching past back-pressure ({active} active, max {})",
+            cfg.max_open_prs
+        );
+    }
+
+    dispatch::dispatch_worker(
+        repo,
+        pr_num,
+        &branch,
+        &issues,
+        &cfg,
+        &creds,
+        commit_prefix.as_deref(),
+    )?;
+
+    if cfg.audit_comments {
+        let comment = format!("sipag worker started on PR #{pr_num} (branch `{branch}`).");
+        for &n in &issues {
+            if let Err(e) = github::post_issue_comment(repo, n, &comment) {
+                log::warn!("audit comment on #{n} failed: {e}");
+            }
+        }
+    }
+
+    if verbose {
+        follow_dispatched(&sipag_dir, repo, pr_num)?;
+    }
+    Ok(())
+}
+
+/// Echo a just-dispatched worker's log, prefixed with its PR number, until
+/// its state turns terminal. The log file stays the source of truth.
+fn follow_dispatched(sipag_dir: &std::path::Path, repo: &str, pr_num: u64) -> Result<()> {
+    let state_path = state::state_file_path(sipag_dir, repo, pr_num);
+    let path = tail::log_path(sipag_dir, repo, pr_num);
+    let mut final_phase = None;
+    tail::follow_log_prefixed(
+        &path,
+        &format!("[#{pr_num}] "),
+        &mut std::io::stdout(),
+        || {
+            let current = lifecycle::scan_workers(sipag_dir)
+                .into_iter()
+                .find(|s| s.file_path == state_path);
+            match current {
+                Some(s) if !s.phase.is_terminal() => false,
+                Some(s) => {
+                    final_phase = Some(s.phase.to_string());
+                    true
+                }
+                None => true,
+            }
+        },
+    )?;
+    println!("── #{pr_num} {}", final_phase.as_deref().unwrap_or("gone"));
     Ok(())
 }
 
@@ -273,8 +650,8 @@ fn run_ps(show_all: bool) -> Result<()> {
             "#{:<7} {:<30} {:<12} {:<8} {}",
             w.pr_num, w.repo, w.phase, age, container_short
         );
-        if let Some(ref err) = w.error {
-            let short = if err.len() > 60 { &err[..60] } else { err };
+        if let Some(summary) = w.failure_summary() {
+            let short: String = summary.chars().take(70).collect();
             println!("         \x1b[31m↳ {short}\x1b[0m");
         }
     };
@@ -299,7 +676,12 @@ fn run_ps(show_all: bool) -> Result<()> {
     // Summary counts.
     let finished_count = all_workers
         .iter()
-        .filter(|w| w.phase == state::WorkerPhase::Finished)
+        .filter(|w| {
+            matches!(
+                w.phase,
+                state::WorkerPhase::Finished | state::WorkerPhase::Proposed
+            )
+        })
         .count();
     let failed_count = all_workers
         .iter()
@@ -334,6 +716,11 @@ fn run_logs(id: &str) -> Result<()> {
                 print!("{content}");
                 return Ok(());
             }
+            let name = log_path.file_name().unwrap_or_default().to_string_lossy();
+            if let Some(content) = lifecycle::read_archived_log(&sipag_dir, |m| m == name) {
+                print!("{content}");
+                return Ok(());
+            }
 
             // Fallback: try docker logs by stored container name.
             let container_name = w.container_id.clone();
@@ -347,6 +734,15 @@ fn run_logs(id: &str) -> Result<()> {
         }
     }
 
+    // The state file may have been pruned along with the log; try the archive.
+    if let Ok(pr_num) = id.trim_start_matches('#').parse::<u64>() {
+        let suffix = format!("--pr-{pr_num}.log");
+        if let Some(content) = lifecycle::read_archived_log(&sipag_dir, |m| m.ends_with(&suffix)) {
+            print!("{content}");
+            return Ok(());
+        }
+    }
+
     // Try as container name directly.
     let status = Command::new("docker")
         .args(["logs", "--tail", "100", id])
@@ -358,6 +754,273 @@ fn run_logs(id: &str) -> Result<()> {
     }
 }
 
+fn run_logs_archive() -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    let cfg = WorkerConfig::load(&sipag_dir)?;
+    let bundles = lifecycle::archive_old_logs(
+        &sipag_dir,
+        cfg.state_max_age_days,
+        std::time::SystemTime::now(),
+    )?;
+    for bundle in &bundles {
+        println!("  {}", bundle.display());
+    }
+    println!(
+        "Archived logs older than {} day(s) into {} bundle(s).",
+        cfg.state_max_age_days,
+        bundles.len()
+    );
+    Ok(())
+}
+
+fn run_tail() -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    let mut followed_any = false;
+
+    // Follow one worker at a time; when it finishes, move on to the next
+    // most recently started one until nothing is running.
+    loop {
+        let workers = lifecycle::scan_workers(&sipag_dir);
+        let Some(w) = tail::most_recent_running(&workers) else {
+            if followed_any {
+                println!("── no more running workers");
+            } else {
+                println!("No running workers.");
+            }
+            return Ok(());
+        };
+        followed_any = true;
+
+        let age = chrono::DateTime::parse_from_rfc3339(&w.started)
+            .map(|t| {
+                let secs = (chrono::Utc::now() - t.with_timezone(&chrono::Utc))
+                    .num_seconds()
+                    .max(0) as u64;
+                format_duration(secs)
+            })
+            .unwrap_or_else(|_| "-".to_string());
+        println!(
+            "── #{} {} {} ({age}) {}",
+            w.pr_num, w.repo, w.phase, w.branch
+        );
+        let path = tail::log_path(&sipag_dir, &w.repo, w.pr_num);
+        let mut final_phase = None;
+        tail::follow_log(&path, &mut std::io::stdout(), || {
+            let current = lifecycle::scan_workers(&sipag_dir)
+                .into_iter()
+                .find(|s| s.file_path == w.file_path);
+            match current {
+                Some(s) if !s.phase.is_terminal() => false,
+                Some(s) => {
+                    final_phase = Some(s.phase.to_string());
+                    true
+                }
+                None => true,
+            }
+        })?;
+        println!(
+            "── #{} {}",
+            w.pr_num,
+            final_phase.as_deref().unwrap_or("gone")
+        );
+    }
+}
+
+/// Parse an issue number argument, accepting an optional leading `#`.
+/// Zero, negative, and non-numeric input are rejected up front so a typo
+/// can't silently match nothing.
+fn parse_issue_num(s: &str) -> Result<u64> {
+    s.trim()
+        .trim_start_matches('#')
+        .parse::<u64>()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| anyhow::anyhow!("expected a positive issue number, got '{s}'"))
+}
+
+/// Reject repo arguments that aren't `owner/repo`.
+fn validate_repo_arg(repo: &str) -> Result<()> {
+    if repo.split('/').filter(|p| !p.is_empty()).count() != 2 {
+        anyhow::bail!("Expected a repo in owner/repo form, got: {repo}");
+    }
+    Ok(())
+}
+
+fn run_tail_repo(repo: &str) -> Result<()> {
+    validate_repo_arg(repo)?;
+    let sipag_dir = default_sipag_dir();
+    let workers = lifecycle::scan_workers(&sipag_dir);
+    if !workers
+        .iter()
+        .any(|w| w.repo == repo && !w.phase.is_terminal())
+    {
+        println!("No running workers for {repo}.");
+        return Ok(());
+    }
+
+    let mut tail = tail::RepoTail::new(&sipag_dir, repo);
+    let mut out = std::io::stdout();
+    let mut workers = workers;
+    while !tail.poll(&workers, &mut out)? {
+        std::thread::sleep(tail::MULTIPLEX_INTERVAL);
+        workers = lifecycle::scan_workers(&sipag_dir);
+    }
+    println!("── no more running workers for {repo}");
+    Ok(())
+}
+
+fn run_drain(repo: Option<&str>, wait: bool, timeout: Option<u64>) -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    if let Some(r) = repo {
+        validate_repo_arg(r)?;
+    }
+    drain::set_draining(&sipag_dir, repo)?;
+    let scope = repo.unwrap_or("all repos");
+    println!("Draining {scope}: sipag dispatch will not start new workers until `sipag resume`.");
+    if !wait {
+        return Ok(());
+    }
+
+    let cfg = WorkerConfig::load(&sipag_dir)?;
+    let result = drain::wait_until_idle(
+        || {
+            let mut workers =
+                lifecycle::scan_workers_with_stale_secs(&sipag_dir, cfg.heartbeat_stale_secs);
+            if let Some(r) = repo {
+                workers.retain(|w| w.repo == r);
+            }
+            workers
+        },
+        timeout.map(std::time::Duration::from_secs),
+        drain::WAIT_POLL_INTERVAL,
+        |running| println!("Waiting for {} running worker(s)...", running.len()),
+    );
+    match result {
+        Ok(()) => {
+            println!("Drained {scope}: no workers running.");
+            Ok(())
+        }
+        Err(running) => {
+            for w in &running {
+                println!("  still running: {} PR #{} ({})", w.repo, w.pr_num, w.phase);
+            }
+            anyhow::bail!("Timed out with {} worker(s) still running", running.len())
+        }
+    }
+}
+
+fn run_resume(repo: Option<&str>) -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    let Some(repo) = repo else {
+        let cleared = drain::clear_draining(&sipag_dir, None)?;
+        drain::clear_force_active(&sipag_dir, None)?;
+        if cleared {
+            println!("Resumed all repos: sipag dispatch will start workers again.");
+        } else {
+            println!("Not draining all repos.");
+        }
+        let still = drain::draining_repos(&sipag_dir);
+        if !still.is_empty() {
+            println!("Still draining: {}", still.join(", "));
+        }
+        return Ok(());
+    };
+
+    validate_repo_arg(repo)?;
+    let cleared = drain::clear_draining(&sipag_dir, Some(repo))?;
+    if drain::is_draining(&sipag_dir) {
+        // Keep the global drain, but let this repo through it.
+        drain::set_force_active(&sipag_dir, repo)?;
+        println!(
+            "Resumed {repo} while all other repos stay drained; \
+             `sipag resume` lifts the global drain."
+        );
+    } else if cleared {
+        println!("Resumed {repo}: sipag dispatch will start workers again.");
+    } else {
+        println!("Not draining {repo}.");
+    }
+    Ok(())
+}
+
+fn run_kick(repo: Option<&str>) -> Result<()> {
+    if let Some(r) = repo {
+        validate_repo_arg(r)?;
+    }
+    kick::set_kick(&default_sipag_dir(), repo)?;
+    match repo {
+        Some(r) => println!("Kicked {r}: its next dispatch bypasses back-pressure once."),
+        None => println!("Kicked all repos: the next dispatch bypasses back-pressure once."),
+    }
+    Ok(())
+}
+
+fn run_reopen(repo: &str, issue: u64) -> Result<()> {
+    validate_repo_arg(repo)?;
+    let sipag_dir = default_sipag_dir();
+    let cfg = WorkerConfig::load(&sipag_dir)?;
+
+    // Reconcile dead containers first so a crashed worker doesn't block.
+    lifecycle::scan_workers(&sipag_dir);
+    let removed = lifecycle::reopen_issue(&sipag_dir, repo, issue)?;
+    for w in &removed {
+        println!("  forgot PR #{} ({})", w.pr_num, w.phase);
+    }
+
+    let labels = cfg.work_labels();
+    let label = labels.first().map(String::as_str);
+    github::label_issues(repo, &[issue], None, label, cfg.create_missing_labels)?;
+    println!(
+        "Reopened #{issue} on {repo}: removed {} worker state file(s){}.",
+        removed.len(),
+        label
+            .map(|l| format!(", labeled '{l}'"))
+            .unwrap_or_default()
+    );
+    Ok(())
+}
+
+fn run_open(repo: &str, issue: u64) -> Result<()> {
+    validate_repo_arg(repo)?;
+    let workers = lifecycle::scan_workers(&default_sipag_dir());
+    let url = resolve_open_url(repo, issue, &workers);
+    if !open_in_browser(&url) {
+        println!("{url}");
+    }
+    Ok(())
+}
+
+/// URL for "take me to what the bot did" on an issue: the PR of the most
+/// recently started worker covering it, else the issue page.
+fn resolve_open_url(repo: &str, issue: u64, states: &[state::WorkerState]) -> String {
+    states
+        .iter()
+        .filter(|w| w.repo == repo && w.issues.contains(&issue))
+        .max_by(|a, b| a.started.cmp(&b.started))
+        .map(|w| format!("https://github.com/{repo}/pull/{}", w.pr_num))
+        .unwrap_or_else(|| format!("https://github.com/{repo}/issues/{issue}"))
+}
+
+/// Hand `url` to the platform opener. Returns false when there is no GUI
+/// to open it in (e.g. over SSH) or the opener fails, so the caller can
+/// print the URL instead.
+fn open_in_browser(url: &str) -> bool {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+            return false;
+        }
+        "xdg-open"
+    };
+    Command::new(opener)
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
 fn run_kill(id: &str) -> Result<()> {
     let sipag_dir = default_sipag_dir();
 
@@ -399,73 +1062,655 @@ fn run_kill(id: &str) -> Result<()> {
     Ok(())
 }
 
-fn run_doctor() -> Result<()> {
+fn run_merge_status(repo: &str, merge: bool) -> Result<()> {
+    validate_repo_arg(repo)?;
+    let cfg = WorkerConfig::load(&default_sipag_dir())?;
+    let status = github::merge_status(repo, &cfg.branch_prefix)?;
+
+    println!("Ready ({}):", status.ready.len());
+    for (num, title) in &status.ready {
+        println!("  #{num} {title}");
+    }
+    println!("Conflicting ({}):", status.conflicting.len());
+    for (num, title) in &status.conflicting {
+        println!("  #{num} {title}");
+    }
+    println!("Checks pending/failing ({}):", status.checks_blocked.len());
+    for (num, title, outcome) in &status.checks_blocked {
+        let why = if *outcome == github::MergeOutcome::SkippedFailing {
+            "failing"
+        } else {
+            "pending"
+        };
+        println!("  #{num} {title} ({why})");
+    }
+
+    if !merge {
+        return Ok(());
+    }
+    // Conflicting and blocked PRs are left for a later run.
+    for (num, _) in &status.ready {
+        match github::auto_merge(repo, *num, cfg.post_merge_hook.as_deref()) {
+            Ok(github::MergeOutcome::Merged) => println!("Merged #{num}"),
+            Ok(outcome) => println!("Skipped #{num}: {}", merge_skip_reason(&outcome)),
+            Err(e) => log::warn!("could not merge #{num}: {e:#}"),
+        }
+    }
+    Ok(())
+}
+
+/// Why `merge-status --merge` left a PR that looked ready when listed.
+fn merge_skip_reason(outcome: &github::MergeOutcome) -> &'static str {
+    match outcome {
+        github::MergeOutcome::Merged => "merged",
+        github::MergeOutcome::SkippedPending => "checks pending",
+        github::MergeOutcome::SkippedFailing => "checks failing",
+        github::MergeOutcome::SkippedConflicting => "conflicting",
+        github::MergeOutcome::SkippedClosed => "no longer open",
+    }
+}
+
+fn run_status(repo_args: &[String], json: bool) -> Result<()> {
+    let repos: Vec<String> = if repo_args.is_empty() {
+        if !json && std::io::stdout().is_terminal() {
+            return run_tui();
+        }
+        vec![repo::resolve_repo(std::path::Path::new("."))?.full_name]
+    } else {
+        for r in repo_args {
+            validate_repo_arg(r)?;
+        }
+        repo_args.to_vec()
+    };
+
     let sipag_dir = default_sipag_dir();
+    let cfg = WorkerConfig::load(&sipag_dir)?;
+    let workers = lifecycle::scan_workers_with_stale_secs(&sipag_dir, cfg.heartbeat_stale_secs);
+
+    let mut statuses = Vec::new();
+    for repo in &repos {
+        // A typo'd or missing work label silently yields zero ready issues;
+        // say so instead.
+        for label in cfg.work_labels() {
+            if !github::label_exists(repo, &label) {
+                let available = github::list_labels(repo)
+                    .map(|labels| labels.join(", "))
+                    .unwrap_or_else(|_| "(could not list labels)".to_string());
+                log::warn!(
+                    "work_label '{label}' does not exist on {repo}. Available labels: {available}"
+                );
+            }
+        }
+        statuses.push(status::repo_status(repo, &cfg, &workers)?);
+    }
 
-    println!("sipag doctor");
-    println!("============\n");
+    if json {
+        let report = status::status_report_json(&statuses);
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
 
-    // 1. Docker
-    print!("Docker daemon:  ");
-    match docker::preflight_docker_running() {
-        Ok(_) => println!("OK"),
-        Err(e) => println!("FAIL — {e}"),
+    for (i, s) in statuses.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{}", s.repo);
+        match cfg.assignee {
+            Some(ref who) => println!(
+                "  ready issues:    {:<4} (label: {}, assignee: {who})",
+                s.ready_issues.len(),
+                cfg.work_label
+            ),
+            None => println!(
+                "  ready issues:    {:<4} (label: {})",
+                s.ready_issues.len(),
+                cfg.work_label
+            ),
+        }
+        println!("  in progress:     {}", s.in_progress_issues);
+        println!("  active workers:  {}", s.active_workers);
+        println!("  open sipag PRs:  {}", s.open_sipag_prs);
+        if s.conflicted_prs.is_empty() {
+            println!("  conflicted PRs:  0");
+        } else {
+            let nums: Vec<String> = s.conflicted_prs.iter().map(|n| format!("#{n}")).collect();
+            println!(
+                "  conflicted PRs:  {:<4} ({})",
+                s.conflicted_prs.len(),
+                nums.join(", ")
+            );
+        }
+        for (n, reason) in &s.skipped_issues {
+            println!("  skipped #{n}: {reason}");
+        }
+    }
+    Ok(())
+}
+
+fn run_stats(repo: Option<&str>, days: Option<u64>, format: StatsFormat) -> Result<()> {
+    if let Some(r) = repo {
+        validate_repo_arg(r)?;
+    }
+    let sipag_dir = default_sipag_dir();
+    let workers = stats::filter_states(
+        lifecycle::scan_workers(&sipag_dir),
+        repo,
+        days,
+        chrono::Utc::now(),
+    );
+    match format {
+        StatsFormat::Json => {
+            let s = stats::compute_stats(&workers);
+            println!("{}", serde_json::to_string_pretty(&stats::stats_json(&s))?);
+        }
+        StatsFormat::Csv => print!("{}", stats::workers_csv(&workers)),
+        StatsFormat::Table if workers.is_empty() => println!("No workers found."),
+        StatsFormat::Table => print_stats_table(&stats::compute_stats(&workers)),
     }
+    Ok(())
+}
+
+fn print_stats_table(s: &stats::Stats) {
+    println!("Workers: {} total", s.total);
+    println!("  starting  {}", s.starting);
+    println!("  working   {}", s.working);
+    println!("  finished  {}", s.finished);
+    if s.proposed > 0 {
+        println!("  proposed  {}", s.proposed);
+    }
+    println!("  failed    {}", s.failed);
+
+    let avg = s
+        .avg_duration_secs
+        .map(format_duration)
+        .unwrap_or_else(|| "-".to_string());
+    println!("\nAvg duration:  {avg}");
+    match s.failure_rate() {
+        Some(rate) => println!(
+            "Failure rate:  {:.0}% ({} of {})",
+            rate * 100.0,
+            s.failed,
+            s.terminal()
+        ),
+        None => println!("Failure rate:  -"),
+    }
+    if let (Some(avg), Some(max)) = (s.avg_peak_memory_bytes, s.max_peak_memory_bytes) {
+        println!(
+            "Peak memory:   avg {}, max {}",
+            format_bytes(avg),
+            format_bytes(max)
+        );
+    }
+    if let Some(cpu) = s.total_cpu_secs {
+        println!("CPU time:      {}", format_duration(cpu.round() as u64));
+    }
+    if s.finished_durations.is_some() || s.failed_durations.is_some() {
+        println!("\nDuration       p50      p90      p99");
+        for (label, p) in [
+            ("finished", s.finished_durations),
+            ("failed", s.failed_durations),
+        ] {
+            if let Some(p) = p {
+                println!(
+                    "  {label:<12} {:<8} {:<8} {}",
+                    format_duration(p.p50),
+                    format_duration(p.p90),
+                    format_duration(p.p99)
+                );
+            }
+        }
+    }
+}
+
+/// Format a byte count with a binary unit (e.g. `512 MiB`).
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn run_prune(older_than: Option<u64>, dry_run: bool) -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    let cfg = WorkerConfig::load(&sipag_dir)?;
+    let days = older_than.unwrap_or(cfg.state_max_age_days);
+
+    let pruned = lifecycle::prune_states(&sipag_dir, days, dry_run);
+    for w in &pruned {
+        println!("  #{:<6} {:<30} {}", w.pr_num, w.repo, w.phase);
+    }
+    let verb = if dry_run { "Would remove" } else { "Removed" };
+    println!(
+        "{verb} {} state file(s) older than {days} day(s).",
+        pruned.len()
+    );
+
+    if cfg.log_max_age_days > 0 {
+        let logs = lifecycle::prune_logs(&sipag_dir, cfg.log_max_age_days, dry_run);
+        for path in &logs {
+            println!("  {}", path.display());
+        }
+        println!(
+            "{verb} {} log file(s) older than {} day(s).",
+            logs.len(),
+            cfg.log_max_age_days
+        );
+    }
+    Ok(())
+}
+
+/// Outcome of a single doctor check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+/// One line of the doctor report.
+#[derive(Debug, Clone, serde::Serialize)]
+struct DoctorCheck {
+    /// Stable identifier, e.g. `docker_daemon` or `config.timeout`.
+    name: String,
+    status: CheckStatus,
+    /// Human-readable result, as printed after the check's label.
+    detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// Run every doctor check against `sipag_dir`.
+fn collect_doctor_checks(sipag_dir: &std::path::Path) -> Vec<DoctorCheck> {
+    let mut checks = Vec::new();
+
+    // 1. Docker
+    checks.push(match docker::preflight_docker_running() {
+        Ok(_) => DoctorCheck::new("docker_daemon", CheckStatus::Ok, "OK"),
+        Err(e) => DoctorCheck::new("docker_daemon", CheckStatus::Fail, format!("FAIL — {e}")),
+    });
 
     // 2. Docker image
-    let cfg = WorkerConfig::load(&sipag_dir)
-        .unwrap_or_else(|_| WorkerConfig::load(std::path::Path::new("/tmp")).unwrap());
-    print!("Docker image:   ");
-    match docker::preflight_docker_image(&cfg.image) {
-        Ok(_) => println!("OK ({})", cfg.image),
-        Err(_) => println!("MISSING ({})", cfg.image),
+    let cfg = WorkerConfig::load_quiet(sipag_dir)
+        .unwrap_or_else(|_| WorkerConfig::load_quiet(std::path::Path::new("/tmp")).unwrap());
+    checks.push(
+        match docker::preflight_docker_image(&cfg.image, cfg.container_platform.as_deref()) {
+            Ok(_) => DoctorCheck::new(
+                "docker_image",
+                CheckStatus::Ok,
+                format!("OK ({})", cfg.image),
+            ),
+            Err(_) => DoctorCheck::new(
+                "docker_image",
+                CheckStatus::Fail,
+                match cfg.container_platform {
+                    Some(ref p) => format!("MISSING ({} for {p})", cfg.image),
+                    None => format!("MISSING ({})", cfg.image),
+                },
+            )
+            .with_hint(format!(
+                "docker {}",
+                docker::pull_args(&cfg.image, cfg.container_platform.as_deref()).join(" ")
+            )),
+        },
+    );
+    if let Some(ref platform) = cfg.container_platform {
+        let host = docker::host_platform();
+        checks.push(if docker::needs_emulation(platform, &host) {
+            DoctorCheck::new(
+                "platform",
+                CheckStatus::Warn,
+                format!(
+                    "NOTE — {platform} differs from host {host}; workers will run under emulation"
+                ),
+            )
+        } else {
+            DoctorCheck::new("platform", CheckStatus::Ok, format!("OK ({platform})"))
+        });
     }
 
     // 3. gh auth
-    print!("GitHub CLI:     ");
-    match github::preflight_gh_auth() {
-        Ok(_) => println!("OK"),
-        Err(e) => println!("FAIL — {e}"),
-    }
+    checks.push(match github::preflight_gh_auth() {
+        Ok(_) => DoctorCheck::new("gh_auth", CheckStatus::Ok, "OK"),
+        Err(e) => DoctorCheck::new("gh_auth", CheckStatus::Fail, format!("FAIL — {e}"))
+            .with_hint("gh auth login"),
+    });
+
+    checks.push(clock_skew_check(github::detect_clock_skew()));
 
     // 4. sipag dir
-    print!("sipag dir:      ");
-    if sipag_dir.exists() {
-        println!("OK ({})", sipag_dir.display());
+    checks.push(if sipag_dir.exists() {
+        DoctorCheck::new(
+            "sipag_dir",
+            CheckStatus::Ok,
+            format!("OK ({})", sipag_dir.display()),
+        )
     } else {
-        println!("MISSING ({})", sipag_dir.display());
+        DoctorCheck::new(
+            "sipag_dir",
+            CheckStatus::Fail,
+            format!("MISSING ({})", sipag_dir.display()),
+        )
+    });
+
+    // 5. TUI binary
+    checks.push(tui_check(&probe_tui(), GIT_HASH));
+
+    // 6. Config file
+    for entry in validate_config_file_for_doctor(sipag_dir).unwrap_or_default() {
+        let name = format!("config.{}", entry.key);
+        let detail_prefix = format!("{}={}", entry.key, entry.value);
+        checks.push(match entry.status {
+            ConfigEntryStatus::Valid => {
+                DoctorCheck::new(name, CheckStatus::Ok, format!("{detail_prefix} — OK"))
+            }
+            ConfigEntryStatus::InvalidValue { clamped_to } => DoctorCheck::new(
+                name,
+                CheckStatus::Warn,
+                format!("{detail_prefix} — WARN — using {clamped_to}"),
+            ),
+            ConfigEntryStatus::Rejected { reason } => DoctorCheck::new(
+                name,
+                CheckStatus::Fail,
+                format!("{detail_prefix} — INVALID — {reason}"),
+            ),
+            ConfigEntryStatus::Unknown { suggestion } => match suggestion {
+                Some(s) => DoctorCheck::new(
+                    name,
+                    CheckStatus::Warn,
+                    format!("{detail_prefix} — UNKNOWN — did you mean '{s}'?"),
+                )
+                .with_hint(format!("rename to {s}")),
+                None => DoctorCheck::new(
+                    name,
+                    CheckStatus::Warn,
+                    format!("{detail_prefix} — UNKNOWN"),
+                ),
+            },
+        });
     }
 
-    // 5. Config file
-    if let Some(entries) = validate_config_file_for_doctor(&sipag_dir) {
-        println!("\nConfig file ({}/config):", sipag_dir.display());
-        for entry in &entries {
-            let status_str = match &entry.status {
-                ConfigEntryStatus::Valid => "OK".to_string(),
-                ConfigEntryStatus::InvalidValue { clamped_to } => {
-                    format!("WARN — using {clamped_to}")
-                }
-                ConfigEntryStatus::Unknown { suggestion } => {
-                    if let Some(s) = suggestion {
-                        format!("UNKNOWN — did you mean '{s}'?")
-                    } else {
-                        "UNKNOWN".to_string()
-                    }
-                }
-            };
-            println!("  {}={} — {}", entry.key, entry.value, status_str);
+    checks
+}
+
+/// What running `sipag-tui --version` produced.
+#[derive(Debug, PartialEq)]
+enum TuiProbe {
+    /// Not found on PATH.
+    Missing,
+    /// Ran, but printed no version (a build that predates `--version`).
+    NoVersion,
+    /// The `--version` line, e.g. `sipag-tui 3.0.2 (abc1234)`.
+    Version(String),
+}
+
+/// Run `sipag-tui --version` with a short deadline. Builds without
+/// `--version` start the TUI instead, so they're killed after the deadline.
+fn probe_tui() -> TuiProbe {
+    let Ok(mut child) = Command::new("sipag-tui")
+        .arg("--version")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+    else {
+        return TuiProbe::Missing;
+    };
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(2);
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if std::time::Instant::now() < deadline => {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+            }
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return TuiProbe::NoVersion;
+            }
+        }
+    }
+    match child.wait_with_output() {
+        Ok(out) => {
+            let line = String::from_utf8_lossy(&out.stdout).trim().to_string();
+            if line.starts_with("sipag-tui ") {
+                TuiProbe::Version(line)
+            } else {
+                TuiProbe::NoVersion
+            }
         }
+        Err(_) => TuiProbe::NoVersion,
     }
+}
 
+/// Classify a TUI probe against this binary's git hash. Builds from the
+/// same commit match; an `unknown` hash on either side can't be compared.
+fn tui_check(probe: &TuiProbe, git_hash: &str) -> DoctorCheck {
+    const NAME: &str = "tui_binary";
+    match probe {
+        TuiProbe::Missing => DoctorCheck::new(NAME, CheckStatus::Fail, "MISSING (sipag-tui)")
+            .with_hint("make install, or cargo install --path tui"),
+        TuiProbe::NoVersion => DoctorCheck::new(
+            NAME,
+            CheckStatus::Warn,
+            "WARN — sipag-tui did not report a version (older build?)",
+        )
+        .with_hint("make install"),
+        TuiProbe::Version(line) => {
+            let tui_hash = line
+                .rsplit_once('(')
+                .map(|(_, rest)| rest.trim_end_matches(')'))
+                .unwrap_or("unknown");
+            if tui_hash == git_hash || tui_hash == "unknown" || git_hash == "unknown" {
+                DoctorCheck::new(NAME, CheckStatus::Ok, format!("OK ({line})"))
+            } else {
+                DoctorCheck::new(
+                    NAME,
+                    CheckStatus::Warn,
+                    format!("MISMATCH — {line}, but sipag is {VERSION} ({git_hash})"),
+                )
+                .with_hint("make install")
+            }
+        }
+    }
+}
+
+/// Compare the local clock with GitHub's. Age-based filters
+/// (`min_issue_age`, the archive view) misbehave when they disagree.
+fn clock_skew_check(skew: Option<chrono::TimeDelta>) -> DoctorCheck {
+    const NAME: &str = "clock_skew";
+    let Some(skew) = skew else {
+        return DoctorCheck::new(
+            NAME,
+            CheckStatus::Warn,
+            "UNKNOWN — could not read GitHub's time",
+        );
+    };
+    let secs = skew.num_seconds();
+    if !github::clock_skew_exceeds_limit(skew) {
+        return DoctorCheck::new(NAME, CheckStatus::Ok, format!("OK ({secs:+}s)"));
+    }
+    let direction = if secs > 0 { "ahead of" } else { "behind" };
+    DoctorCheck::new(
+        NAME,
+        CheckStatus::Warn,
+        format!(
+            "WARN — local clock is {} {direction} GitHub",
+            format_duration(secs.unsigned_abs())
+        ),
+    )
+    .with_hint("enable automatic time sync (NTP)")
+}
+
+/// Label printed before a check's detail in the text report.
+fn doctor_label(name: &str) -> &'static str {
+    match name {
+        "docker_daemon" => "Docker daemon:  ",
+        "docker_image" => "Docker image:   ",
+        "platform" => "Platform:       ",
+        "gh_auth" => "GitHub CLI:     ",
+        "clock_skew" => "Clock skew:     ",
+        "sipag_dir" => "sipag dir:      ",
+        "tui_binary" => "TUI binary:     ",
+        _ => "",
+    }
+}
+
+/// Render the text report: one line per check, with `  → hint` under
+/// every check that is not OK.
+fn render_doctor_text(checks: &[DoctorCheck], sipag_dir: &std::path::Path) -> String {
+    let mut out = String::from("sipag doctor\n============\n\n");
+
+    let mut config_header = false;
+    for check in checks {
+        if check.name.starts_with("config.") {
+            if !config_header {
+                out.push_str(&format!(
+                    "\nConfig file ({}/config):\n",
+                    sipag_dir.display()
+                ));
+                config_header = true;
+            }
+            out.push_str(&format!("  {}\n", check.detail));
+        } else {
+            out.push_str(&format!("{}{}\n", doctor_label(&check.name), check.detail));
+        }
+        if check.status != CheckStatus::Ok {
+            if let Some(hint) = &check.hint {
+                out.push_str(&format!("  → {hint}\n"));
+            }
+        }
+    }
+    out
+}
+
+fn run_doctor(json: bool) -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    let checks = collect_doctor_checks(&sipag_dir);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+        return Ok(());
+    }
+
+    print!("{}", render_doctor_text(&checks, &sipag_dir));
     println!();
     Ok(())
 }
 
-fn run_version() -> Result<()> {
-    println!("sipag {VERSION} ({GIT_HASH})");
+fn run_config_validate() -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    let config_path = sipag_dir.join("config");
+    let Some(entries) = validate_config_file_for_doctor(&sipag_dir) else {
+        println!(
+            "No config file at {} (defaults apply).",
+            config_path.display()
+        );
+        return Ok(());
+    };
+
+    let mut problems = 0;
+    for entry in &entries {
+        let problem = match &entry.status {
+            ConfigEntryStatus::Valid => continue,
+            ConfigEntryStatus::InvalidValue { clamped_to } => {
+                format!("invalid value; would use {clamped_to}")
+            }
+            ConfigEntryStatus::Rejected { reason } => format!("invalid value; {reason}"),
+            ConfigEntryStatus::Unknown {
+                suggestion: Some(s),
+            } => format!("unknown key; did you mean '{s}'?"),
+            ConfigEntryStatus::Unknown { suggestion: None } => "unknown key".to_string(),
+        };
+        println!("{}={} — {problem}", entry.key, entry.value);
+        problems += 1;
+    }
+
+    if problems > 0 {
+        anyhow::bail!("{} has {problems} problem(s)", config_path.display());
+    }
+    println!("{}: OK ({} entries)", config_path.display(), entries.len());
+    Ok(())
+}
+
+fn run_config_get(key: &str) -> Result<()> {
+    match config::config_get(&default_sipag_dir(), key)? {
+        Some(value) => println!("{value}"),
+        // Nothing on stdout, so `$(sipag config get key)` never captures
+        // prose; the non-zero exit tells scripts the key is unset.
+        None => anyhow::bail!("{key} is not set in the config file (default applies)"),
+    }
+    Ok(())
+}
+
+fn run_config_set(key: &str, value: &str) -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    config::config_set(&sipag_dir, key, value)?;
+    println!(
+        "{key}={} written to {}",
+        value.trim(),
+        sipag_dir.join("config").display()
+    );
+    Ok(())
+}
+
+fn run_config_list() -> Result<()> {
+    let sipag_dir = default_sipag_dir();
+    let Some(entries) = validate_config_file_for_doctor(&sipag_dir) else {
+        println!(
+            "No config file at {} (defaults apply).",
+            sipag_dir.join("config").display()
+        );
+        return Ok(());
+    };
+    for entry in &entries {
+        println!("{}={}", entry.key, entry.value);
+    }
+    Ok(())
+}
+
+fn run_version(json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(&version_json())?);
+    } else {
+        println!("sipag {VERSION} ({GIT_HASH})");
+    }
     Ok(())
 }
 
+/// Build metadata for fleet inventory scripts.
+fn version_json() -> serde_json::Value {
+    serde_json::json!({
+        "version": VERSION,
+        "git_hash": GIT_HASH,
+        "built_at": BUILD_TIME.unwrap_or("unknown"),
+    })
+}
+
 fn run_tui() -> Result<()> {
     // Exec the TUI binary.
     let status = Command::new("sipag-tui").status();
@@ -476,29 +1721,6 @@ fn run_tui() -> Result<()> {
     }
 }
 
-/// Extract issue numbers from "Closes/Fixes/Resolves #N" in text.
-fn extract_issue_nums(body: &str) -> Vec<u64> {
-    let mut nums = Vec::new();
-    for line in body.lines() {
-        let lower = line.to_lowercase();
-        for keyword in &["closes #", "fixes #", "resolves #"] {
-            let mut search_from = 0;
-            while let Some(pos) = lower[search_from..].find(keyword) {
-                let abs_pos = search_from + pos + keyword.len();
-                let rest = &line[abs_pos..];
-                let num_str: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
-                if let Ok(n) = num_str.parse::<u64>() {
-                    if !nums.contains(&n) {
-                        nums.push(n);
-                    }
-                }
-                search_from = abs_pos;
-            }
-        }
-    }
-    nums
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -513,6 +1735,13 @@ mod tests {
         assert!(extract_issue_nums("No refs here").is_empty());
     }
 
+    #[test]
+    fn format_bytes_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
     #[test]
     fn extract_issue_nums_deduplicates() {
         assert_eq!(extract_issue_nums("Closes #5\nFixes #5"), vec![5]);
@@ -578,4 +1807,127 @@ mod tests {
     fn parse_pr_url_non_numeric_pr() {
         assert!(parse_pr_url("https://github.com/owner/repo/pull/abc").is_err());
     }
+
+    fn worker(repo: &str, pr_num: u64, issues: Vec<u64>, started: &str) -> state::WorkerState {
+        state::WorkerState {
+            repo: repo.to_string(),
+            pr_num,
+            issues,
+            branch: format!("sipag/pr-{pr_num}"),
+            container_id: format!("sipag-{pr_num}"),
+            phase: state::WorkerPhase::Finished,
+            heartbeat: started.to_string(),
+            started: started.to_string(),
+            ended: None,
+            exit_code: None,
+            error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
+            file_path: PathBuf::new(),
+        }
+    }
+
+    #[test]
+    fn resolve_open_url_prefers_latest_pr_for_issue() {
+        let states = vec![
+            worker("acme/app", 10, vec![7], "2026-01-01T00:00:00Z"),
+            worker("acme/app", 12, vec![7, 8], "2026-01-02T00:00:00Z"),
+            worker("other/app", 99, vec![7], "2026-01-03T00:00:00Z"),
+        ];
+        assert_eq!(
+            resolve_open_url("acme/app", 7, &states),
+            "https://github.com/acme/app/pull/12"
+        );
+    }
+
+    #[test]
+    fn resolve_open_url_falls_back_to_issue() {
+        let states = vec![worker("acme/app", 10, vec![7], "2026-01-01T00:00:00Z")];
+        assert_eq!(
+            resolve_open_url("acme/app", 8, &states),
+            "https://github.com/acme/app/issues/8"
+        );
+        assert_eq!(
+            resolve_open_url("acme/app", 8, &[]),
+            "https://github.com/acme/app/issues/8"
+        );
+    }
+
+    #[test]
+    fn version_json_round_trips_with_package_version() {
+        let text = serde_json::to_string(&version_json()).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed["version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(parsed["git_hash"], GIT_HASH);
+        assert!(parsed["built_at"].is_string());
+    }
+
+    #[test]
+    fn parse_issue_num_accepts_positive_numbers() {
+        assert_eq!(parse_issue_num("42").unwrap(), 42);
+        assert_eq!(parse_issue_num("#7").unwrap(), 7);
+    }
+
+    #[test]
+    fn parse_issue_num_rejects_zero_negative_and_text() {
+        for bad in ["0", "-3", "abc", "12a", ""] {
+            let err = parse_issue_num(bad).unwrap_err().to_string();
+            assert_eq!(
+                err,
+                format!("expected a positive issue number, got '{bad}'")
+            );
+        }
+    }
+
+    #[test]
+    fn clock_skew_check_warns_past_a_minute() {
+        let check = clock_skew_check(Some(chrono::TimeDelta::seconds(-5)));
+        assert_eq!(check.status, CheckStatus::Ok);
+        assert_eq!(check.detail, "OK (-5s)");
+
+        let check = clock_skew_check(Some(chrono::TimeDelta::seconds(300)));
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.contains("ahead of"), "{}", check.detail);
+        assert!(check.hint.is_some());
+
+        let check = clock_skew_check(Some(chrono::TimeDelta::seconds(-120)));
+        assert!(check.detail.contains("behind"), "{}", check.detail);
+
+        assert_eq!(clock_skew_check(None).status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn tui_check_classifies_probe() {
+        let check = tui_check(&TuiProbe::Missing, "abc1234");
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert!(check.hint.is_some());
+
+        let check = tui_check(&TuiProbe::NoVersion, "abc1234");
+        assert_eq!(check.status, CheckStatus::Warn);
+
+        let same = TuiProbe::Version("sipag-tui 3.0.2 (abc1234)".to_string());
+        assert_eq!(tui_check(&same, "abc1234").status, CheckStatus::Ok);
+        assert_eq!(tui_check(&same, "unknown").status, CheckStatus::Ok);
+
+        let check = tui_check(&same, "def5678");
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert!(check.detail.contains("MISMATCH"));
+    }
+
+    #[test]
+    fn doctor_text_prints_hints_under_failing_checks() {
+        let checks = vec![
+            tui_check(&TuiProbe::Missing, "abc1234"),
+            DoctorCheck::new("gh_auth", CheckStatus::Ok, "OK").with_hint("gh auth login"),
+        ];
+        let text = render_doctor_text(&checks, std::path::Path::new("/tmp/sipag"));
+        assert!(
+            text.contains(
+                "TUI binary:     MISSING (sipag-tui)\n  → make install, or cargo install --path tui\n"
+            ),
+            "{text}"
+        );
+        assert!(text.contains("GitHub CLI:     OK\n"), "{text}");
+        assert!(!text.contains("gh auth login"), "{text}");
+    }
 }
@@ -5,6 +5,7 @@ mod templates;
 use clap::Parser;
 
 fn main() {
+    sipag_core::logging::init();
     let cli = cli::Cli::parse();
     if let Err(e) = cli::run(cli) {
         eprintln!("Error: {e:#}");
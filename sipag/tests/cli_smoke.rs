@@ -130,6 +130,48 @@ fn ps_shows_worker() {
         .stdout(predicate::str::contains("finished"));
 }
 
+#[test]
+fn stats_csv_lists_finished_worker() {
+    let dir = temp_sipag_dir();
+    let json = r#"{"repo":"test/repo","pr_num":42,"issues":[1],"branch":"sipag/pr-42","container_id":"abc123","phase":"finished","heartbeat":"2026-01-01T00:00:00Z","started":"2026-01-01T00:00:00Z","ended":"2026-01-01T00:05:00Z"}"#;
+    fs::write(dir.path().join("workers/test--repo--pr-42.json"), json).unwrap();
+
+    sipag()
+        .args(["stats", "--format", "csv"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success()
+        .stdout("repo,issue,outcome,duration_s,started_at\ntest/repo,1,finished,300,2026-01-01T00:00:00Z\n");
+}
+
+// ── Kick ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn kick_writes_signal_files() {
+    let dir = temp_sipag_dir();
+    sipag()
+        .args(["kick", "acme/widgets"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Kicked acme/widgets"));
+    assert!(dir.path().join("kick.d/acme--widgets").exists());
+    assert!(!dir.path().join("kick").exists());
+
+    sipag()
+        .arg("kick")
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success();
+    assert!(dir.path().join("kick").exists());
+
+    sipag()
+        .args(["kick", "not-a-repo"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure();
+}
+
 // ── Logs ────────────────────────────────────────────────────────────────────
 
 #[test]
@@ -183,6 +225,36 @@ fn doctor_shows_sipag_dir_ok() {
         .stdout(predicate::str::contains("sipag dir:      OK"));
 }
 
+#[test]
+fn doctor_json_lists_checks() {
+    let dir = temp_sipag_dir();
+    fs::write(dir.path().join("config"), "timeout=300\n").unwrap();
+
+    let output = sipag()
+        .args(["doctor", "--json"])
+        .env("SIPAG_DIR", dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let checks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = checks
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["name"].as_str().unwrap())
+        .collect();
+    for expected in [
+        "docker_daemon",
+        "docker_image",
+        "gh_auth",
+        "sipag_dir",
+        "config.timeout",
+    ] {
+        assert!(names.contains(&expected), "missing {expected} in {names:?}");
+    }
+}
+
 // ── Dispatch (validation errors) ────────────────────────────────────────────
 
 #[test]
@@ -194,6 +266,25 @@ fn dispatch_requires_pr_url() {
         .stderr(predicate::str::contains("PR_URL"));
 }
 
+#[test]
+fn dispatch_refuses_invalid_security_opt() {
+    let dir = temp_sipag_dir();
+    fs::write(
+        dir.path().join("config"),
+        "security_opt=no-new-privileges,seccomp\n",
+    )
+    .unwrap();
+
+    sipag()
+        .args(["dispatch", "https://github.com/o/r/pull/1"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "security_opt seccomp is not a valid --security-opt value",
+        ));
+}
+
 // ── Ps (state verification) ─────────────────────────────────────────────────
 
 #[test]
@@ -239,6 +330,61 @@ fn ps_shows_finished_and_failed() {
         .stdout(predicate::str::contains("failed"));
 }
 
+// ── Status ──────────────────────────────────────────────────────────────────
+
+#[test]
+fn status_rejects_malformed_repo() {
+    let dir = temp_sipag_dir();
+    sipag()
+        .args(["status", "not-a-repo"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("owner/repo"));
+}
+
+// ── Tail ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn tail_no_running_workers() {
+    let dir = temp_sipag_dir();
+    sipag()
+        .arg("tail")
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No running workers"));
+}
+
+// ── Prune ───────────────────────────────────────────────────────────────────
+
+#[test]
+fn prune_dry_run_then_remove() {
+    let dir = temp_sipag_dir();
+    let path = dir.path().join("workers/o--r--pr-7.json");
+    fs::write(
+        &path,
+        r#"{"repo":"o/r","pr_num":7,"issues":[],"branch":"b","container_id":"c","phase":"failed","heartbeat":"2020-01-01T00:00:00Z","started":"2020-01-01T00:00:00Z","ended":"2020-01-01T01:00:00Z"}"#,
+    )
+    .unwrap();
+
+    sipag()
+        .args(["prune", "--dry-run"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Would remove 1"));
+    assert!(path.exists());
+
+    sipag()
+        .args(["prune", "--older-than", "1"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1"));
+    assert!(!path.exists());
+}
+
 // ── Kill (state mutation) ───────────────────────────────────────────────────
 
 #[test]
@@ -266,6 +412,82 @@ fn kill_by_pr_number_updates_state() {
     );
 }
 
+#[test]
+fn retry_forgets_failed_worker_and_relabels_issue() {
+    let dir = temp_sipag_dir();
+    let json = r#"{"repo":"o/r","pr_num":42,"issues":[7],"branch":"b","container_id":"fake","phase":"failed","heartbeat":"2026-01-01T00:00:00Z","started":"2026-01-01T00:00:00Z","ended":"2026-01-01T00:10:00Z"}"#;
+    let state_path = dir.path().join("workers/o--r--pr-42.json");
+    fs::write(&state_path, json).unwrap();
+
+    // Fake gh that records its arguments and knows the `ready` label.
+    let bin = dir.path().join("bin");
+    fs::create_dir(&bin).unwrap();
+    let gh = bin.join("gh");
+    let calls = dir.path().join("gh-calls");
+    fs::write(
+        &gh,
+        format!(
+            "#!/bin/sh\necho \"$@\" >> {}\n[ \"$1\" = label ] && echo '[{{\"name\":\"ready\"}}]'\nexit 0\n",
+            calls.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&gh, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!("{}:{}", bin.display(), std::env::var("PATH").unwrap());
+
+    sipag()
+        .args(["retry", "o/r", "7"])
+        .env("SIPAG_DIR", dir.path())
+        .env("PATH", path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("forgot PR #42"));
+
+    assert!(!state_path.exists());
+    let calls = fs::read_to_string(&calls).unwrap();
+    assert!(
+        calls.contains("issue edit 7 --repo o/r --add-label ready"),
+        "{calls}"
+    );
+}
+
+#[test]
+fn reopen_rejects_malformed_repo() {
+    let dir = temp_sipag_dir();
+    sipag()
+        .args(["reopen", "not-a-repo", "7"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("owner/repo"));
+}
+
+#[test]
+fn reopen_rejects_bad_issue_numbers() {
+    let dir = temp_sipag_dir();
+    for bad in ["0", "-3", "abc"] {
+        sipag()
+            .args(["reopen", "o/r", bad])
+            .env("SIPAG_DIR", dir.path())
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(format!(
+                "expected a positive issue number, got '{bad}'"
+            )));
+    }
+}
+
+#[test]
+fn merge_status_merge_rejects_invalid_repo_before_touching_github() {
+    let dir = temp_sipag_dir();
+    sipag()
+        .args(["merge-status", "not-a-repo", "--merge"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("owner/repo"));
+}
+
 // ── Doctor (config entries) ─────────────────────────────────────────────────
 
 #[test]
@@ -430,6 +652,103 @@ fn config_alias_works() {
 
 // ── Unknown subcommand ──────────────────────────────────────────────────────
 
+#[test]
+fn config_validate_clean_config() {
+    let dir = temp_sipag_dir();
+    fs::write(dir.path().join("config"), "image=custom:v1\ntimeout=300\n").unwrap();
+
+    sipag()
+        .args(["config", "validate"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("OK (2 entries)"));
+}
+
+#[test]
+fn config_validate_reports_problems() {
+    let dir = temp_sipag_dir();
+    fs::write(
+        dir.path().join("config"),
+        "image=custom:v1\ntimeout=abc\nimgae=typo\n",
+    )
+    .unwrap();
+
+    sipag()
+        .args(["config", "validate"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("timeout=abc"))
+        .stdout(predicate::str::contains("did you mean 'image'?"))
+        .stdout(predicate::str::contains("image=custom:v1").not());
+}
+
+#[test]
+fn config_validate_rejects_invalid_security_opt() {
+    let dir = temp_sipag_dir();
+    fs::write(dir.path().join("config"), "security_opt=bogus\n").unwrap();
+
+    sipag()
+        .args(["config", "validate"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "security_opt=bogus — invalid value; bogus is not a valid --security-opt value",
+        ));
+}
+
+#[test]
+fn config_set_get_list() {
+    let dir = temp_sipag_dir();
+    fs::write(dir.path().join("config"), "# keep me\nimage=custom:v1\n").unwrap();
+    sipag()
+        .args(["config", "set", "timeout", "900"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success();
+    sipag()
+        .args(["config", "get", "timeout"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success()
+        .stdout("900\n");
+    sipag()
+        .args(["config", "list"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("image=custom:v1"))
+        .stdout(predicate::str::contains("timeout=900"));
+    let content = fs::read_to_string(dir.path().join("config")).unwrap();
+    assert!(content.starts_with("# keep me\n"), "{content}");
+}
+
+#[test]
+fn config_get_unset_key_fails_with_empty_stdout() {
+    let dir = temp_sipag_dir();
+    sipag()
+        .args(["config", "get", "timeout"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(predicate::str::contains("timeout is not set"));
+}
+
+#[test]
+fn config_set_rejects_unknown_key() {
+    let dir = temp_sipag_dir();
+    sipag()
+        .args(["config", "set", "timeot", "900"])
+        .env("SIPAG_DIR", dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("did you mean 'timeout'"));
+    assert!(!dir.path().join("config").exists());
+}
+
 #[test]
 fn unknown_subcommand_fails() {
     sipag()
@@ -1,9 +1,11 @@
 use anyhow::Result;
 use chrono::Utc;
 use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use sipag_core::state;
+use sipag_core::{drain, kick, state};
 use std::path::PathBuf;
+use std::time::Instant;
 
+use crate::backlog::{fetch_backlog, BacklogCache};
 use crate::task::Task;
 
 // ── ListMode ──────────────────────────────────────────────────────────────────
@@ -40,6 +42,23 @@ pub struct App {
     pub detail_task_id: Option<(String, u64)>,
     /// Tick counter for throttling log refreshes (refresh every 5 ticks ≈ 1 s).
     tick_count: u8,
+    /// Per-repo open issue/PR counts; `None` unless `tui_backlog_counts` is on.
+    pub backlog: Option<BacklogCache>,
+    /// Whether the global drain signal is set.
+    pub draining_all: bool,
+    /// Repos with their own drain signal.
+    pub draining_repos: Vec<String>,
+    /// Repos resumed during a global drain (force-active overrides).
+    pub force_active_repos: Vec<String>,
+    /// One-line confirmation shown in the footer until the next key press.
+    pub toast: Option<String>,
+    /// Running/finished/failed counts over every worker on disk in the repo
+    /// filter, whichever list mode is shown (see [`App::counts`]).
+    worker_counts: (usize, usize, usize),
+    /// Distinct repos across all workers on disk, sorted.
+    pub repos: Vec<String>,
+    /// Only show workers for this repo; `None` shows all repos.
+    pub repo_filter: Option<String>,
 }
 
 impl App {
@@ -48,10 +67,12 @@ impl App {
     }
 
     pub fn with_dir(sipag_dir: PathBuf) -> Result<Self> {
-        let archive_max_age_days = std::env::var("SIPAG_ARCHIVE_DAYS")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(7);
+        let cfg = sipag_core::config::WorkerConfig::load_quiet(&sipag_dir).ok();
+        let archive_max_age_days = cfg.as_ref().map(|c| c.state_max_age_days).unwrap_or(7);
+        let backlog = cfg
+            .as_ref()
+            .filter(|c| c.tui_backlog_counts)
+            .map(|_| BacklogCache::default());
         let mut app = Self {
             sipag_dir,
             tasks: vec![],
@@ -65,6 +86,14 @@ impl App {
             total_state_files: 0,
             detail_task_id: None,
             tick_count: 0,
+            backlog,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         };
         app.refresh_tasks()?;
         Ok(app)
@@ -77,10 +106,32 @@ impl App {
         // reconcile non-terminal workers against Docker liveness.
         let workers = sipag_core::worker::lifecycle::scan_workers(&self.sipag_dir);
         self.total_state_files = workers.len();
-        let all_tasks: Vec<Task> = workers.into_iter().map(Task::from).collect();
+        self.draining_all = drain::is_draining(&self.sipag_dir);
+        self.draining_repos = drain::draining_repos(&self.sipag_dir);
+        self.force_active_repos = drain::force_active_repos(&self.sipag_dir);
+        let mut repos: Vec<String> = workers.iter().map(|w| w.repo.clone()).collect();
+        repos.sort();
+        repos.dedup();
+        if let Some(backlog) = self.backlog.as_mut() {
+            backlog.track(repos.clone());
+        }
+        // Drop a filter whose repo has no workers left.
+        if self
+            .repo_filter
+            .as_ref()
+            .is_some_and(|r| !repos.contains(r))
+        {
+            self.repo_filter = None;
+        }
+        self.repos = repos;
+        let all_tasks: Vec<Task> = workers
+            .into_iter()
+            .map(Task::from)
+            .filter(|t| self.repo_filter.as_ref().is_none_or(|r| &t.repo == r))
+            .collect();
+        self.worker_counts = phase_counts(&all_tasks);
 
         let now = Utc::now();
-        let max_age = chrono::Duration::days(self.archive_max_age_days as i64);
 
         self.tasks = match self.list_mode {
             ListMode::Active => all_tasks
@@ -90,10 +141,7 @@ impl App {
             ListMode::Archive => all_tasks
                 .into_iter()
                 .filter(|t| t.phase.is_terminal())
-                .filter(|t| {
-                    t.ended
-                        .is_none_or(|ended| now.signed_duration_since(ended) < max_age)
-                })
+                .filter(|t| !state::is_expired(t.ended, now, self.archive_max_age_days))
                 .collect(),
         };
 
@@ -130,6 +178,41 @@ impl App {
         self.selected = self.selected.saturating_sub(1);
     }
 
+    /// `(running, finished, failed)` over all workers, not just the ones
+    /// the current list mode shows.
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.worker_counts
+    }
+
+    /// Select the active worker that started earliest — the likeliest to be stuck.
+    pub fn select_oldest_active(&mut self) {
+        let oldest = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| !t.phase.is_terminal())
+            .filter_map(|(i, t)| t.started.map(|s| (i, s)))
+            .min_by_key(|(_, started)| *started);
+        if let Some((i, _)) = oldest {
+            self.selected = i;
+        }
+    }
+
+    /// Cycle the repo filter: all repos, then each repo in order, then back
+    /// to all. The selection is clamped to the narrowed list.
+    pub fn cycle_repo_filter(&mut self) {
+        self.repo_filter = match &self.repo_filter {
+            None => self.repos.first().cloned(),
+            Some(current) => self
+                .repos
+                .iter()
+                .position(|r| r == current)
+                .and_then(|i| self.repos.get(i + 1))
+                .cloned(),
+        };
+        let _ = self.refresh_tasks();
+    }
+
     // ── View transitions ──────────────────────────────────────────────────────
 
     pub fn toggle_list_mode(&mut self) {
@@ -277,6 +360,19 @@ impl App {
         Some(task.container_id.clone())
     }
 
+    // ── Kick ──────────────────────────────────────────────────────────────────
+
+    /// Write the kick signal for `repo` (or all repos) so the next dispatch
+    /// bypasses back-pressure once.
+    pub fn kick(&mut self, repo: Option<&str>) -> Result<()> {
+        kick::set_kick(&self.sipag_dir, repo)?;
+        self.toast = Some(format!(
+            "kicked {}: next dispatch bypasses back-pressure",
+            repo.unwrap_or("all repos")
+        ));
+        Ok(())
+    }
+
     // ── Key handling ──────────────────────────────────────────────────────────
 
     pub fn handle_key(&mut self, key: KeyEvent) -> Result<bool> {
@@ -290,11 +386,13 @@ impl App {
         if key.modifiers != KeyModifiers::NONE && key.modifiers != KeyModifiers::SHIFT {
             return Ok(false);
         }
+        self.toast = None;
         match key.code {
             KeyCode::Char('q') => return Ok(true),
             KeyCode::Tab => self.toggle_list_mode(),
             KeyCode::Char('j') | KeyCode::Down => self.select_next(),
             KeyCode::Up => self.select_prev(),
+            KeyCode::Char('o') => self.select_oldest_active(),
             KeyCode::Enter => self.open_detail(),
             KeyCode::Char('a') => {
                 if let Some(container) = self.selected_container_name() {
@@ -307,6 +405,13 @@ impl App {
             KeyCode::Char('d') => self.archive_selected()?,
             KeyCode::Char('k') => self.kill_selected()?,
             KeyCode::Char('K') => self.kill_all()?,
+            KeyCode::Char('r') => {
+                if let Some(repo) = self.tasks.get(self.selected).map(|t| t.repo.clone()) {
+                    self.kick(Some(&repo))?;
+                }
+            }
+            KeyCode::Char('R') => self.kick(None)?,
+            KeyCode::Char('f') => self.cycle_repo_filter(),
             _ => {}
         }
         Ok(false)
@@ -329,6 +434,27 @@ impl App {
         Ok(false)
     }
 
+    // ── Backlog ───────────────────────────────────────────────────────────────
+
+    /// Whether dispatch is drained for `repo`, resolved like `sipag dispatch`
+    /// does (see [`drain::drain_decision`]) from the last refresh's signals.
+    pub fn is_repo_draining(&self, repo: &str) -> bool {
+        let has = |repos: &[String]| repos.iter().any(|r| r == repo);
+        drain::drain_decision(drain::DrainSignals {
+            force_active: has(&self.force_active_repos),
+            global: self.draining_all,
+            repo: has(&self.draining_repos),
+        }) != drain::DrainAction::Dispatch
+    }
+
+    /// Apply finished backlog counts and start a background refresh if
+    /// enabled and due.
+    pub fn refresh_backlog(&mut self) {
+        if let Some(backlog) = self.backlog.as_mut() {
+            backlog.refresh_if_due(Instant::now(), fetch_backlog);
+        }
+    }
+
     // ── Tick ──────────────────────────────────────────────────────────────────
 
     pub fn on_tick(&mut self) -> Result<()> {
@@ -359,6 +485,22 @@ impl App {
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
 
+/// Count `(running, finished, failed)` tasks; starting counts as running and
+/// proposed as finished.
+fn phase_counts(tasks: &[Task]) -> (usize, usize, usize) {
+    tasks
+        .iter()
+        .fold((0, 0, 0), |(running, finished, failed), t| match t.phase {
+            state::WorkerPhase::Starting | state::WorkerPhase::Working => {
+                (running + 1, finished, failed)
+            }
+            state::WorkerPhase::Finished | state::WorkerPhase::Proposed => {
+                (running, finished + 1, failed)
+            }
+            state::WorkerPhase::Failed => (running, finished, failed + 1),
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,6 +514,7 @@ mod tests {
             branch: format!("sipag/pr-{pr_num}"),
             container_id: String::new(),
             phase,
+            sub_phase: None,
             started: None,
             ended: None,
             exit_code: None,
@@ -395,9 +538,31 @@ mod tests {
             total_state_files: total,
             detail_task_id: None,
             tick_count: 0,
+            backlog: None,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         }
     }
 
+    #[test]
+    fn phase_counts_over_mixed_tasks() {
+        let tasks = vec![
+            make_task(1, WorkerPhase::Starting),
+            make_task(2, WorkerPhase::Working),
+            make_task(3, WorkerPhase::Working),
+            make_task(4, WorkerPhase::Finished),
+            make_task(5, WorkerPhase::Failed),
+            make_task(6, WorkerPhase::Failed),
+        ];
+        assert_eq!(phase_counts(&tasks), (3, 1, 2));
+        assert_eq!(phase_counts(&[]), (0, 0, 0));
+    }
+
     #[test]
     fn app_new_missing_dir_succeeds() {
         let app = App::with_dir(PathBuf::from("/tmp/sipag-test-nonexistent-dir-xyz"))
@@ -429,6 +594,77 @@ mod tests {
         assert_eq!(app.selected, 0);
     }
 
+    #[test]
+    fn select_oldest_active_picks_earliest_start() {
+        let at = |s: &str| Some(s.parse::<chrono::DateTime<Utc>>().unwrap());
+        let mut t1 = make_task(1, WorkerPhase::Working);
+        t1.started = at("2026-01-01T02:00:00Z");
+        let mut t2 = make_task(2, WorkerPhase::Working);
+        t2.started = at("2026-01-01T00:30:00Z");
+        // Terminal workers are ignored even if they started earlier.
+        let mut t3 = make_task(3, WorkerPhase::Failed);
+        t3.started = at("2026-01-01T00:00:00Z");
+        let mut t4 = make_task(4, WorkerPhase::Starting);
+        t4.started = at("2026-01-01T01:00:00Z");
+        let mut app = make_app_with_tasks(vec![t1, t2, t3, t4]);
+
+        app.select_oldest_active();
+        assert_eq!(app.selected, 1);
+    }
+
+    #[test]
+    fn select_oldest_active_no_active_keeps_selection() {
+        let mut app = make_app_with_tasks(vec![
+            make_task(1, WorkerPhase::Finished),
+            make_task(2, WorkerPhase::Failed),
+        ]);
+        app.selected = 1;
+        app.select_oldest_active();
+        assert_eq!(app.selected, 1);
+    }
+
+    #[test]
+    fn kick_key_writes_signal_for_selected_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = make_app_with_tasks(vec![make_task(1, WorkerPhase::Working)]);
+        app.sipag_dir = dir.path().to_path_buf();
+
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        app.handle_key(key('r')).unwrap();
+        assert!(kick::kick_path(dir.path(), Some("test/repo")).exists());
+        assert!(app.toast.as_deref().unwrap().contains("test/repo"));
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT))
+            .unwrap();
+        assert!(kick::kick_path(dir.path(), None).exists());
+
+        app.handle_key(key('j')).unwrap();
+        assert!(app.toast.is_none());
+    }
+
+    #[test]
+    fn refresh_tasks_reads_drain_signals() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = make_app_with_tasks(vec![]);
+        app.sipag_dir = dir.path().to_path_buf();
+
+        drain::set_draining(dir.path(), Some("test/repo")).unwrap();
+        app.refresh_tasks().unwrap();
+        assert!(!app.draining_all);
+        assert!(app.is_repo_draining("test/repo"));
+        assert!(!app.is_repo_draining("test/other"));
+
+        drain::set_draining(dir.path(), None).unwrap();
+        app.refresh_tasks().unwrap();
+        assert!(app.is_repo_draining("test/other"));
+
+        // `sipag resume test/other` during the global drain.
+        drain::set_force_active(dir.path(), "test/other").unwrap();
+        app.refresh_tasks().unwrap();
+        assert!(!app.is_repo_draining("test/other"));
+        assert!(app.is_repo_draining("test/repo"));
+    }
+
     #[test]
     fn refresh_tasks_reads_state_files() {
         let dir = tempfile::tempdir().unwrap();
@@ -449,6 +685,8 @@ mod tests {
             ended: Some(now),
             exit_code: Some(0),
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: state::state_file_path(dir.path(), "test/repo", 42),
         };
         state::write_state(&s).unwrap();
@@ -466,6 +704,14 @@ mod tests {
             total_state_files: 0,
             detail_task_id: None,
             tick_count: 0,
+            backlog: None,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         };
         app.refresh_tasks().unwrap();
 
@@ -474,6 +720,64 @@ mod tests {
         assert_eq!(app.tasks[0].phase, WorkerPhase::Finished);
     }
 
+    #[test]
+    fn repo_filter_cycles_and_clamps_selection() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("workers")).unwrap();
+        let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        for (repo, pr_num) in [("acme/api", 1), ("acme/api", 2), ("acme/web", 3)] {
+            state::write_state(&state::WorkerState {
+                repo: repo.to_string(),
+                pr_num,
+                issues: vec![],
+                branch: "b".to_string(),
+                container_id: "c".to_string(),
+                phase: WorkerPhase::Finished,
+                heartbeat: now.clone(),
+                started: now.clone(),
+                ended: Some(now.clone()),
+                exit_code: Some(0),
+                error: None,
+                peak_memory_bytes: None,
+                cpu_secs: None,
+                file_path: state::state_file_path(dir.path(), repo, pr_num),
+            })
+            .unwrap();
+        }
+
+        let mut app = make_app_with_tasks(vec![]);
+        app.sipag_dir = dir.path().to_path_buf();
+        app.list_mode = ListMode::Archive;
+        app.refresh_tasks().unwrap();
+        assert_eq!(app.repos, vec!["acme/api", "acme/web"]);
+        assert_eq!(app.tasks.len(), 3);
+        app.selected = 2;
+
+        app.cycle_repo_filter();
+        assert_eq!(app.repo_filter.as_deref(), Some("acme/api"));
+        assert_eq!(app.tasks.len(), 2);
+        assert!(app.tasks.iter().all(|t| t.repo == "acme/api"));
+        assert_eq!(app.selected, 1);
+
+        app.cycle_repo_filter();
+        assert_eq!(app.repo_filter.as_deref(), Some("acme/web"));
+        assert_eq!(app.tasks.len(), 1);
+        assert_eq!(app.selected, 0);
+        assert_eq!(app.counts(), (0, 1, 0));
+
+        app.cycle_repo_filter();
+        assert_eq!(app.repo_filter, None);
+        assert_eq!(app.tasks.len(), 3);
+
+        // A filtered repo whose workers are all gone falls back to all repos.
+        app.cycle_repo_filter();
+        app.cycle_repo_filter();
+        std::fs::remove_file(state::state_file_path(dir.path(), "acme/web", 3)).unwrap();
+        app.refresh_tasks().unwrap();
+        assert_eq!(app.repo_filter, None);
+        assert_eq!(app.tasks.len(), 2);
+    }
+
     #[test]
     fn active_mode_filters_terminal() {
         // With scan_workers reconciliation (no Docker in tests), non-terminal
@@ -496,6 +800,8 @@ mod tests {
             ended: Some(now.clone()),
             exit_code: Some(0),
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: state::state_file_path(dir.path(), "test/repo", 1),
         };
         let failed = state::WorkerState {
@@ -510,6 +816,8 @@ mod tests {
             ended: Some(now),
             exit_code: Some(1),
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: state::state_file_path(dir.path(), "test/repo", 2),
         };
         state::write_state(&finished).unwrap();
@@ -528,6 +836,14 @@ mod tests {
             total_state_files: 0,
             detail_task_id: None,
             tick_count: 0,
+            backlog: None,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         };
         app.refresh_tasks().unwrap();
 
@@ -560,6 +876,8 @@ mod tests {
             ended: Some(now.clone()),
             exit_code: Some(0),
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: state::state_file_path(dir.path(), "test/repo", 1),
         };
         state::write_state(&finished).unwrap();
@@ -577,6 +895,14 @@ mod tests {
             total_state_files: 0,
             detail_task_id: None,
             tick_count: 0,
+            backlog: None,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         };
         app.refresh_tasks().unwrap();
 
@@ -608,6 +934,8 @@ mod tests {
             ended: Some("2000-01-01T01:00:00Z".to_string()),
             exit_code: Some(0),
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: state::state_file_path(dir.path(), "test/repo", 3),
         };
         state::write_state(&old).unwrap();
@@ -625,6 +953,14 @@ mod tests {
             total_state_files: 0,
             detail_task_id: None,
             tick_count: 0,
+            backlog: None,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         };
         app.refresh_tasks().unwrap();
 
@@ -649,6 +985,8 @@ mod tests {
             ended: Some("2026-01-15T10:05:00Z".to_string()),
             exit_code: Some(0),
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: file_path.clone(),
         };
         state::write_state(&s).unwrap();
@@ -666,6 +1004,14 @@ mod tests {
             total_state_files: 0,
             detail_task_id: None,
             tick_count: 0,
+            backlog: None,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         };
         app.refresh_tasks().unwrap();
         assert_eq!(app.tasks.len(), 1);
@@ -728,6 +1074,8 @@ mod tests {
                 ended: Some(now.clone()),
                 exit_code: Some(0),
                 error: None,
+                peak_memory_bytes: None,
+                cpu_secs: None,
                 file_path: state::state_file_path(dir.path(), "test/repo", pr_num),
             };
             state::write_state(&s).unwrap();
@@ -746,6 +1094,14 @@ mod tests {
             total_state_files: 0,
             detail_task_id: Some(("test/repo".to_string(), 20)),
             tick_count: 0,
+            backlog: None,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         };
         app.refresh_tasks().unwrap();
 
@@ -776,6 +1132,8 @@ mod tests {
             ended: Some(now),
             exit_code: Some(0),
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: file_path.clone(),
         };
         state::write_state(&s).unwrap();
@@ -793,6 +1151,14 @@ mod tests {
             total_state_files: 0,
             detail_task_id: Some(("test/repo".to_string(), 1)),
             tick_count: 0,
+            backlog: None,
+            draining_all: false,
+            draining_repos: vec![],
+            force_active_repos: vec![],
+            toast: None,
+            worker_counts: (0, 0, 0),
+            repos: vec![],
+            repo_filter: None,
         };
         app.refresh_tasks().unwrap();
         assert_eq!(app.tasks.len(), 1);
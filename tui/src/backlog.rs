@@ -0,0 +1,252 @@
+//! Per-repo backlog counts for the TUI header.
+//!
+//! Open issue/PR counts come from `gh`, so they are cached and refreshed at
+//! most every [`BACKLOG_REFRESH_INTERVAL`], on a background thread: a rate
+//! limited `gh` call can retry for minutes and must not freeze the render
+//! loop. The whole feature is opt-in via `tui_backlog_counts` in the config.
+
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+use sipag_core::worker::github;
+
+/// Minimum time between two rounds of `gh` calls.
+pub const BACKLOG_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Open issue and PR counts for one repo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepoBacklog {
+    pub open_issues: usize,
+    pub open_prs: usize,
+}
+
+impl RepoBacklog {
+    /// Header text like `12i/3pr`. Counts at the `gh` list limit are shown
+    /// as `100+`, since the real number may be higher.
+    pub fn summary(&self) -> String {
+        let capped = |n: usize| {
+            if n >= github::ISSUE_LIST_LIMIT {
+                format!("{}+", github::ISSUE_LIST_LIMIT)
+            } else {
+                n.to_string()
+            }
+        };
+        format!("{}i/{}pr", capped(self.open_issues), capped(self.open_prs))
+    }
+}
+
+/// Cached backlog counts for the repos the TUI has seen workers for.
+#[derive(Debug, Default)]
+pub struct BacklogCache {
+    /// Repos to count, kept in sync with the worker state files.
+    repos: Vec<String>,
+    counts: BTreeMap<String, RepoBacklog>,
+    last_refresh: Option<Instant>,
+    /// Results of the background fetch still in flight, if any.
+    pending: Option<Receiver<Vec<(String, RepoBacklog)>>>,
+}
+
+impl BacklogCache {
+    /// Replace the set of tracked repos. A newly seen repo forces the next
+    /// refresh so it doesn't wait a full interval for its first counts.
+    pub fn track(&mut self, repos: Vec<String>) {
+        if repos.iter().any(|r| !self.repos.contains(r)) {
+            self.last_refresh = None;
+        }
+        self.counts.retain(|r, _| repos.contains(r));
+        self.repos = repos;
+    }
+
+    /// Whether a refresh is due at `now`.
+    pub fn is_due(&self, now: Instant) -> bool {
+        match self.last_refresh {
+            None => true,
+            Some(last) => now.saturating_duration_since(last) >= BACKLOG_REFRESH_INTERVAL,
+        }
+    }
+
+    /// Apply a finished background fetch, then start a new one with `fetch`
+    /// if a refresh is due and none is in flight. Never blocks.
+    pub fn refresh_if_due<F>(&mut self, now: Instant, fetch: F)
+    where
+        F: Fn(&str) -> Option<RepoBacklog> + Send + 'static,
+    {
+        self.poll();
+        if self.pending.is_some() || !self.is_due(now) {
+            return;
+        }
+        let repos = self.repos.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let results: Vec<(String, RepoBacklog)> = repos
+                .into_iter()
+                .filter_map(|repo| fetch(&repo).map(|b| (repo, b)))
+                .collect();
+            let _ = tx.send(results);
+        });
+        self.pending = Some(rx);
+        self.last_refresh = Some(now);
+    }
+
+    /// Apply the background fetch's results if it has finished. Returns
+    /// whether it had. Repos whose fetch failed keep their previous counts;
+    /// repos untracked meanwhile are ignored.
+    pub fn poll(&mut self) -> bool {
+        let Some(rx) = self.pending.as_ref() else {
+            return false;
+        };
+        match rx.try_recv() {
+            Ok(results) => {
+                for (repo, b) in results {
+                    if self.repos.contains(&repo) {
+                        self.counts.insert(repo, b);
+                    }
+                }
+                self.pending = None;
+                true
+            }
+            Err(TryRecvError::Empty) => false,
+            Err(TryRecvError::Disconnected) => {
+                self.pending = None;
+                false
+            }
+        }
+    }
+
+    /// Cached counts, ordered by repo name.
+    pub fn counts(&self) -> impl Iterator<Item = (&String, &RepoBacklog)> {
+        self.counts.iter()
+    }
+}
+
+/// Fetch live counts for `repo` via `gh`, or `None` if either call fails.
+pub fn fetch_backlog(repo: &str) -> Option<RepoBacklog> {
+    Some(RepoBacklog {
+        open_issues: github::count_open_issues(repo).ok()?,
+        open_prs: github::count_open_prs(repo).ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(n: usize) -> impl Fn(&str) -> Option<RepoBacklog> + Send + 'static {
+        move |_| {
+            Some(RepoBacklog {
+                open_issues: n,
+                open_prs: n,
+            })
+        }
+    }
+
+    /// Refresh, then wait for the background fetch (if one started) to land.
+    fn refresh_and_wait<F>(cache: &mut BacklogCache, now: Instant, fetch: F)
+    where
+        F: Fn(&str) -> Option<RepoBacklog> + Send + 'static,
+    {
+        cache.refresh_if_due(now, fetch);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while cache.pending.is_some() && Instant::now() < deadline {
+            cache.poll();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(cache.pending.is_none(), "background fetch did not finish");
+    }
+
+    #[test]
+    fn refresh_respects_interval() {
+        let start = Instant::now();
+        let mut cache = BacklogCache::default();
+        cache.track(vec!["a/b".to_string()]);
+        assert!(cache.is_due(start));
+
+        refresh_and_wait(&mut cache, start, fixed(1));
+        assert_eq!(cache.counts().next().unwrap().1.open_issues, 1);
+        assert!(!cache.is_due(start + Duration::from_secs(59)));
+
+        // Not due yet — counts are left alone.
+        refresh_and_wait(&mut cache, start + Duration::from_secs(59), fixed(2));
+        assert_eq!(cache.counts().next().unwrap().1.open_issues, 1);
+
+        refresh_and_wait(&mut cache, start + BACKLOG_REFRESH_INTERVAL, fixed(3));
+        assert_eq!(cache.counts().next().unwrap().1.open_issues, 3);
+    }
+
+    #[test]
+    fn new_repo_forces_refresh() {
+        let start = Instant::now();
+        let mut cache = BacklogCache::default();
+        cache.track(vec!["a/b".to_string()]);
+        refresh_and_wait(&mut cache, start, fixed(1));
+
+        cache.track(vec!["a/b".to_string()]);
+        assert!(!cache.is_due(start));
+
+        cache.track(vec!["a/b".to_string(), "c/d".to_string()]);
+        assert!(cache.is_due(start));
+    }
+
+    #[test]
+    fn failed_fetch_keeps_previous_counts() {
+        let start = Instant::now();
+        let mut cache = BacklogCache::default();
+        cache.track(vec!["a/b".to_string()]);
+        refresh_and_wait(&mut cache, start, fixed(4));
+        refresh_and_wait(&mut cache, start + BACKLOG_REFRESH_INTERVAL, |_| None);
+        assert_eq!(cache.counts().next().unwrap().1.open_prs, 4);
+    }
+
+    #[test]
+    fn untracked_repo_counts_are_dropped() {
+        let start = Instant::now();
+        let mut cache = BacklogCache::default();
+        cache.track(vec!["a/b".to_string()]);
+        refresh_and_wait(&mut cache, start, fixed(1));
+        cache.track(vec![]);
+        assert_eq!(cache.counts().count(), 0);
+    }
+
+    #[test]
+    fn refresh_does_not_block_on_a_slow_fetch() {
+        let mut cache = BacklogCache::default();
+        cache.track(vec!["a/b".to_string()]);
+        let (release, gate) = mpsc::channel::<()>();
+        let gate = std::sync::Mutex::new(gate);
+        let started = Instant::now();
+        cache.refresh_if_due(started, move |_| {
+            let _ = gate.lock().unwrap().recv();
+            Some(RepoBacklog {
+                open_issues: 7,
+                open_prs: 1,
+            })
+        });
+        // The fetch is parked on `gate`, yet refresh returned right away.
+        assert!(!cache.poll());
+        assert_eq!(cache.counts().count(), 0);
+
+        // While it is in flight, a due refresh doesn't start a second fetch.
+        cache.refresh_if_due(started + BACKLOG_REFRESH_INTERVAL, fixed(9));
+        release.send(()).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !cache.poll() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(cache.counts().next().unwrap().1.open_issues, 7);
+    }
+
+    #[test]
+    fn summary_marks_capped_counts() {
+        let b = RepoBacklog {
+            open_issues: github::ISSUE_LIST_LIMIT,
+            open_prs: 4,
+        };
+        assert_eq!(b.summary(), "100+i/4pr");
+        let b = RepoBacklog {
+            open_issues: 99,
+            open_prs: 0,
+        };
+        assert_eq!(b.summary(), "99i/0pr");
+    }
+}
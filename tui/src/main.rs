@@ -1,4 +1,5 @@
 mod app;
+mod backlog;
 mod task;
 mod ui;
 
@@ -15,6 +16,19 @@ use std::{
 };
 
 fn main() -> Result<()> {
+    // `sipag doctor` probes this to check the installed TUI matches sipag.
+    if std::env::args()
+        .nth(1)
+        .is_some_and(|a| a == "--version" || a == "-V")
+    {
+        println!(
+            "sipag-tui {} ({})",
+            env!("CARGO_PKG_VERSION"),
+            env!("CARGO_GIT_SHA")
+        );
+        return Ok(());
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
@@ -93,6 +107,7 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut app::App
         // Refresh task list from disk every second
         if last_task_refresh.elapsed() >= Duration::from_secs(1) {
             app.refresh_tasks()?;
+            app.refresh_backlog();
             last_task_refresh = Instant::now();
         }
     }
@@ -11,6 +11,9 @@ pub struct Task {
     pub branch: String,
     pub container_id: String,
     pub phase: WorkerPhase,
+    /// What a running worker is doing right now (`cloning`, `grace_period`,
+    /// ...), from its heartbeat file. `None` once the worker is terminal.
+    pub sub_phase: Option<String>,
     pub started: Option<DateTime<Utc>>,
     pub ended: Option<DateTime<Utc>>,
     pub exit_code: Option<i32>,
@@ -21,7 +24,13 @@ pub struct Task {
 
 impl From<WorkerState> for Task {
     fn from(w: WorkerState) -> Self {
+        let sub_phase = if w.phase.is_terminal() {
+            None
+        } else {
+            sipag_core::state::read_sub_phase(&w.file_path)
+        };
         Task {
+            sub_phase,
             started: parse_rfc3339(&w.started),
             ended: w.ended.as_deref().and_then(parse_rfc3339),
             repo: w.repo,
@@ -60,6 +69,17 @@ fn format_since(dt: &DateTime<Utc>) -> String {
 }
 
 impl Task {
+    /// Phase label for the list: the phase, plus the worker's sub-phase when
+    /// it adds information (e.g. `starting: cloning`).
+    pub fn phase_label(&self) -> String {
+        match self.sub_phase.as_deref() {
+            Some(sub) if sub != self.phase.to_string() => {
+                format!("{}: {}", self.phase, sub.replace('_', " "))
+            }
+            _ => self.phase.to_string(),
+        }
+    }
+
     pub fn format_age(&self) -> String {
         self.started
             .as_ref()
@@ -120,6 +140,8 @@ mod tests {
             ended: None,
             exit_code: None,
             error: None,
+            peak_memory_bytes: None,
+            cpu_secs: None,
             file_path: PathBuf::from("/home/.sipag/workers/Dorky-Robot--sipag--pr-42.json"),
         }
     }
@@ -257,4 +279,33 @@ mod tests {
         let task = Task::from(sample_worker_state());
         assert_eq!(task.duration_secs(), None);
     }
+
+    #[test]
+    fn from_worker_state_reads_sub_phase() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut w = sample_worker_state();
+        w.file_path = dir.path().join("owner--repo--pr-42.json");
+        std::fs::write(
+            w.file_path.with_extension("heartbeat"),
+            r#"{"sub_phase":"grace_period"}"#,
+        )
+        .unwrap();
+
+        let task = Task::from(w.clone());
+        assert_eq!(task.sub_phase.as_deref(), Some("grace_period"));
+        assert_eq!(task.phase_label(), "working: grace period");
+
+        // Terminal workers ignore a leftover heartbeat.
+        w.phase = WorkerPhase::Finished;
+        let task = Task::from(w);
+        assert_eq!(task.sub_phase, None);
+        assert_eq!(task.phase_label(), "finished");
+    }
+
+    #[test]
+    fn phase_label_hides_redundant_sub_phase() {
+        let mut task = Task::from(sample_worker_state());
+        task.sub_phase = Some("working".to_string());
+        assert_eq!(task.phase_label(), "working");
+    }
 }
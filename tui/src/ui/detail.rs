@@ -23,7 +23,10 @@ pub fn render_detail(f: &mut Frame, app: &App) {
     .split(area);
 
     // ── Header bar ────────────────────────────────────────────────────────────
-    let header_text = format!(" sipag  PR #{} — {}", task.pr_num, task.repo);
+    let mut header_text = format!(" sipag  PR #{} — {}", task.pr_num, task.repo);
+    if app.is_repo_draining(&task.repo) {
+        header_text.push_str("  (draining)");
+    }
     let header = Paragraph::new(Line::from(header_text)).style(
         Style::default()
             .fg(Color::White)
@@ -78,6 +81,7 @@ pub fn render_detail(f: &mut Frame, app: &App) {
         WorkerPhase::Starting => Style::default().fg(Color::Yellow),
         WorkerPhase::Working => Style::default().fg(Color::Cyan),
         WorkerPhase::Finished => Style::default().fg(Color::Green),
+        WorkerPhase::Proposed => Style::default().fg(Color::Magenta),
         WorkerPhase::Failed => Style::default().fg(Color::Red),
     };
     top_lines.push(Line::from(vec![
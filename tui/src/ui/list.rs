@@ -24,7 +24,7 @@ pub fn render_list(f: &mut Frame, app: &App) {
     let finished_count = app
         .tasks
         .iter()
-        .filter(|t| t.phase == WorkerPhase::Finished)
+        .filter(|t| matches!(t.phase, WorkerPhase::Finished | WorkerPhase::Proposed))
         .count();
     let failed_count = app
         .tasks
@@ -43,6 +43,32 @@ pub fn render_list(f: &mut Frame, app: &App) {
             app.sipag_dir.display()
         )
     };
+    let header_base = match app.backlog {
+        Some(ref backlog) => {
+            let counts: Vec<String> = backlog
+                .counts()
+                .map(|(repo, b)| format!("{repo} {}", b.summary()))
+                .collect();
+            if counts.is_empty() {
+                header_base
+            } else {
+                format!("{header_base}  backlog: {}", counts.join("  "))
+            }
+        }
+        None => header_base,
+    };
+    let header_base = if app.draining_all && !app.force_active_repos.is_empty() {
+        format!(
+            "{header_base}  DRAINING (except {})",
+            app.force_active_repos.join(", ")
+        )
+    } else if app.draining_all {
+        format!("{header_base}  DRAINING")
+    } else if !app.draining_repos.is_empty() {
+        format!("{header_base}  draining: {}", app.draining_repos.join(", "))
+    } else {
+        header_base
+    };
     let header_style = Style::default()
         .fg(Color::White)
         .bg(Color::DarkGray)
@@ -76,6 +102,7 @@ pub fn render_list(f: &mut Frame, app: &App) {
                 WorkerPhase::Starting => Style::default().fg(Color::Yellow),
                 WorkerPhase::Working => Style::default().fg(Color::Cyan),
                 WorkerPhase::Finished => Style::default().fg(Color::Green),
+                WorkerPhase::Proposed => Style::default().fg(Color::Magenta),
                 WorkerPhase::Failed => Style::default().fg(Color::Red),
             };
 
@@ -94,7 +121,7 @@ pub fn render_list(f: &mut Frame, app: &App) {
             Row::new(vec![
                 Cell::from(pr_str),
                 Cell::from(task.repo.clone()),
-                Cell::from(task.phase.to_string()).style(phase_style),
+                Cell::from(task.phase_label()).style(phase_style),
                 Cell::from(age_str),
             ])
             .height(1)
@@ -104,7 +131,7 @@ pub fn render_list(f: &mut Frame, app: &App) {
     let widths = [
         Constraint::Length(14), // PR (+Ni)
         Constraint::Min(20),    // REPO (flexible)
-        Constraint::Length(10), // PHASE
+        Constraint::Length(22), // PHASE (: sub-phase)
         Constraint::Length(10), // AGE / ENDED
     ];
 
@@ -131,19 +158,38 @@ pub fn render_list(f: &mut Frame, app: &App) {
 
     // ── Footer bar ────────────────────────────────────────────────────────────
     let footer_text = if is_archive {
-        " [Tab/a] active  [j/k] nav  [Enter] details  [x] dismiss  [q] quit"
+        " [Tab/a] active  [j/k] nav  [Enter] details  [x] dismiss  [f] repo  [q] quit"
     } else {
         let has_attachable = app
             .tasks
             .get(app.selected)
             .is_some_and(|t| !t.phase.is_terminal() && !t.container_id.is_empty());
         if has_attachable {
-            " [Tab] archive  [j/↑↓] nav  [⏎] details  [a] attach  [d] done  [k] kill  [K] all  [q] quit"
+            " [Tab] archive  [j/↑↓] nav  [o] oldest  [⏎] details  [a] attach  [d] done  [k] kill  [K] all  [r/R] kick  [f] repo  [q] quit"
         } else {
-            " [Tab/a] archive  [j/↑↓] nav  [⏎] details  [d] done  [k] kill  [K] all  [q] quit"
+            " [Tab/a] archive  [j/↑↓] nav  [o] oldest  [⏎] details  [d] done  [k] kill  [K] all  [r/R] kick  [f] repo  [q] quit"
         }
     };
 
+    let (running, done, failed) = app.counts();
+    let mut rollup = format!(" {running} running  {done} done  {failed} failed");
+    let draining = match app.repo_filter {
+        Some(ref repo) => app.is_repo_draining(repo),
+        None => app.draining_all || !app.draining_repos.is_empty(),
+    };
+    if draining {
+        rollup.push_str("  DRAINING");
+    }
+    if let Some(ref repo) = app.repo_filter {
+        rollup.push_str(&format!("  [{repo}]"));
+    }
+    if is_archive {
+        rollup.push_str(&format!("  (last {}d)", app.archive_max_age_days));
+    }
+    let footer_text = match app.toast {
+        Some(ref toast) => format!("{rollup} │ {toast}"),
+        None => format!("{rollup} │{footer_text}"),
+    };
     let footer = Paragraph::new(Line::from(footer_text))
         .style(Style::default().fg(Color::White).bg(Color::DarkGray));
     f.render_widget(footer, chunks[2]);